@@ -50,8 +50,15 @@ fn main() {
         .unwrap();
 
     // bindings
-    cxx_build::bridges(["src/lib.rs", "src/config.rs", "src/actor.rs"])
-        .file("src/libvalhalla.cpp")
+    let mut bridges = vec!["src/lib.rs", "src/config.rs", "src/actor.rs"];
+    let mut sources = vec!["src/libvalhalla.cpp"];
+    // Test fixtures are kept out of the library unless explicitly requested
+    if std::env::var_os("CARGO_FEATURE_TEST_FIXTURES").is_some() {
+        bridges.push("src/fixtures.rs");
+        sources.push("src/fixtures.cpp");
+    }
+    cxx_build::bridges(bridges)
+        .files(sources)
         // Hacky workaraound for linking issue because `get_formatted_date()` function is being called in header file
         // and somehow compiler is unable to resolve it when building bridge library.
         // ```
@@ -67,7 +74,10 @@ fn main() {
     println!("cargo:rerun-if-changed=src/config.hpp");
     println!("cargo:rerun-if-changed=src/libvalhalla.hpp");
     println!("cargo:rerun-if-changed=src/libvalhalla.cpp");
+    println!("cargo:rerun-if-changed=src/fixtures.hpp");
+    println!("cargo:rerun-if-changed=src/fixtures.cpp");
     println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=src/fixtures.rs");
     println!("cargo:rerun-if-changed=valhalla");
 
     // protos
@@ -0,0 +1,231 @@
+use std::fmt;
+
+use crate::{Access, TimeDomain, ffi};
+
+/// Restriction of access to an edge for some vehicles, see [`crate::GraphTile::access_restrictions()`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AccessRestriction {
+    pub value: RestrictionValue,
+    /// Access modes the restriction applies to.
+    pub modes: Access,
+    /// Whether the restriction doesn't apply to vehicles with a destination on the edge.
+    pub except_destination: bool,
+}
+
+/// Value of an [`AccessRestriction`] with its units. Valhalla stores dimensions in centimeters and weights in
+/// hundredths of a tonne, both are converted here.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RestrictionValue {
+    /// Whether vehicles carrying hazardous materials are allowed.
+    Hazmat {
+        allowed: bool,
+    },
+    MaxHeight {
+        meters: f64,
+    },
+    MaxWidth {
+        meters: f64,
+    },
+    MaxLength {
+        meters: f64,
+    },
+    MaxWeight {
+        tonnes: f64,
+    },
+    MaxAxleLoad {
+        tonnes: f64,
+    },
+    MaxAxles {
+        count: u32,
+    },
+    /// Access is allowed only during the period.
+    TimedAllowed(TimeDomain),
+    /// Access is denied during the period.
+    TimedDenied(TimeDomain),
+    /// Access is allowed during the period only for vehicles with a destination on the edge.
+    DestinationAllowed(TimeDomain),
+    /// Restriction type unknown to these bindings, with the raw value.
+    Unknown {
+        access_type: u8,
+        value: u64,
+    },
+}
+
+impl From<&ffi::AccessRestrictionEntry> for AccessRestriction {
+    fn from(entry: &ffi::AccessRestrictionEntry) -> Self {
+        let hundredths = entry.value as f64 / 100.0;
+        let value = match entry.access_type {
+            ffi::AccessType::kHazmat => RestrictionValue::Hazmat {
+                allowed: entry.value != 0,
+            },
+            ffi::AccessType::kMaxHeight => RestrictionValue::MaxHeight { meters: hundredths },
+            ffi::AccessType::kMaxWidth => RestrictionValue::MaxWidth { meters: hundredths },
+            ffi::AccessType::kMaxLength => RestrictionValue::MaxLength { meters: hundredths },
+            ffi::AccessType::kMaxWeight => RestrictionValue::MaxWeight { tonnes: hundredths },
+            ffi::AccessType::kMaxAxleLoad => RestrictionValue::MaxAxleLoad { tonnes: hundredths },
+            ffi::AccessType::kMaxAxles => RestrictionValue::MaxAxles {
+                count: entry.value as u32,
+            },
+            ffi::AccessType::kTimedAllowed => {
+                RestrictionValue::TimedAllowed(TimeDomain::from_value(entry.value))
+            }
+            ffi::AccessType::kTimedDenied => {
+                RestrictionValue::TimedDenied(TimeDomain::from_value(entry.value))
+            }
+            ffi::AccessType::kDestinationAllowed => {
+                RestrictionValue::DestinationAllowed(TimeDomain::from_value(entry.value))
+            }
+            _ => RestrictionValue::Unknown {
+                access_type: entry.access_type.repr,
+                value: entry.value,
+            },
+        };
+        Self {
+            value,
+            modes: Access::from_bits_retain(entry.modes),
+            except_destination: entry.except_destination,
+        }
+    }
+}
+
+impl TimeDomain {
+    /// Unpacks Valhalla's `TimeDomain` encoding, used as the value of conditional restrictions.
+    pub fn from_value(value: u64) -> Self {
+        ffi::decode_time_domain(value)
+    }
+}
+
+const DAYS: [&str; 7] = ["Su", "Mo", "Tu", "We", "Th", "Fr", "Sa"];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Formats the period in the OSM `opening_hours` syntax, e.g. `Mar 01-Oct 31 Mo-Fr 07:00-09:00`.
+impl fmt::Display for TimeDomain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+
+        if self.begin_month != 0 || self.end_month != 0 {
+            let date = |month: u8, day_dow: u8, week: u8| {
+                let month = MONTHS
+                    .get(usize::from(month).wrapping_sub(1))
+                    .copied()
+                    .unwrap_or("");
+                match (self.nth_dow, day_dow) {
+                    (_, 0) => month.to_string(),
+                    // Week 5 means the last week of the month
+                    (true, _) => {
+                        let day = DAYS[usize::from(day_dow - 1) % 7];
+                        let week = if week == 5 { -1 } else { i32::from(week) };
+                        format!("{month} {day}[{week}]")
+                    }
+                    (false, _) => format!("{month} {day_dow:02}"),
+                }
+            };
+            parts.push(format!(
+                "{}-{}",
+                date(self.begin_month, self.begin_day_dow, self.begin_week),
+                date(self.end_month, self.end_day_dow, self.end_week)
+            ));
+        }
+
+        if self.dow != 0 {
+            // Bit 0 is Sunday, but weeks start on Monday in `opening_hours`
+            let days: Vec<usize> = [1, 2, 3, 4, 5, 6, 0]
+                .into_iter()
+                .filter(|day| self.dow & (1 << day) != 0)
+                .collect();
+            let position = |day: usize| (day + 6) % 7;
+            let mut ranges: Vec<(usize, usize)> = Vec::new();
+            for day in days {
+                match ranges.last_mut() {
+                    Some((_, end)) if position(*end) + 1 == position(day) => *end = day,
+                    _ => ranges.push((day, day)),
+                }
+            }
+            let ranges: Vec<String> = ranges
+                .into_iter()
+                .flat_map(|(start, end)| match position(end) - position(start) {
+                    0 => vec![DAYS[start].to_string()],
+                    1 => vec![DAYS[start].to_string(), DAYS[end].to_string()],
+                    _ => vec![format!("{}-{}", DAYS[start], DAYS[end])],
+                })
+                .collect();
+            parts.push(ranges.join(","));
+        }
+
+        if self.begin_hrs != 0 || self.begin_mins != 0 || self.end_hrs != 0 || self.end_mins != 0 {
+            parts.push(format!(
+                "{:02}:{:02}-{:02}:{:02}",
+                self.begin_hrs, self.begin_mins, self.end_hrs, self.end_mins
+            ));
+        }
+
+        if parts.is_empty() {
+            f.write_str("24/7")
+        } else {
+            f.write_str(&parts.join(" "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_domain_display() {
+        let empty = TimeDomain {
+            nth_dow: false,
+            dow: 0,
+            begin_month: 0,
+            begin_day_dow: 0,
+            begin_week: 0,
+            begin_hrs: 0,
+            begin_mins: 0,
+            end_month: 0,
+            end_day_dow: 0,
+            end_week: 0,
+            end_hrs: 0,
+            end_mins: 0,
+        };
+        assert_eq!(empty.to_string(), "24/7");
+
+        let workdays = TimeDomain {
+            dow: 0b0111110,
+            begin_hrs: 7,
+            end_hrs: 9,
+            end_mins: 30,
+            ..empty
+        };
+        assert_eq!(workdays.to_string(), "Mo-Fr 07:00-09:30");
+
+        let weekend = TimeDomain {
+            dow: 0b1000001,
+            ..empty
+        };
+        assert_eq!(weekend.to_string(), "Sa,Su");
+
+        let summer = TimeDomain {
+            dow: 0b0101010,
+            begin_month: 3,
+            begin_day_dow: 1,
+            end_month: 10,
+            end_day_dow: 31,
+            ..empty
+        };
+        assert_eq!(summer.to_string(), "Mar 01-Oct 31 Mo,We,Fr");
+
+        let dst = TimeDomain {
+            nth_dow: true,
+            begin_month: 3,
+            begin_day_dow: 1,
+            begin_week: 5,
+            end_month: 10,
+            end_day_dow: 1,
+            end_week: 5,
+            ..empty
+        };
+        assert_eq!(dst.to_string(), "Mar Su[-1]-Oct Su[-1]");
+    }
+}
@@ -1,17 +1,148 @@
 #pragma once
 
+#include <cmath>
+#include <limits>
+
 #include <valhalla/loki/worker.h>
+#include <valhalla/meili/map_matcher.h>
+#include <valhalla/meili/map_matcher_factory.h>
 #include <valhalla/odin/worker.h>
 #include <valhalla/thor/worker.h>
 #include <valhalla/tyr/serializers.h>
 
-// This struct is generated by `cxx` based on shared definition in `valhalla/src/actor.rs.h`.
+#include "libvalhalla.hpp"
+
+// `GraphId` is shared with `lib.rs` bridge where it is bound to `valhalla::baldr::GraphId`.
+using GraphId = valhalla::baldr::GraphId;
+
+// These structs are generated by `cxx` based on shared definition in `valhalla/src/actor.rs.h`.
 struct Response;
+struct MatchedPath;
 
 // This strange FD *before* this include is requred to have an ability to use generated Rust types in C++ code.
 struct Actor;
 #include "valhalla/src/actor.rs.h"
 
+/// Converts request's `shape` into Meili measurements, using matcher's defaults for missing accuracy and radius.
+inline std::vector<valhalla::meili::Measurement>
+to_measurements(const valhalla::meili::MapMatcher& matcher,
+                const google::protobuf::RepeatedPtrField<valhalla::Location>& shape) {
+  const auto& config = matcher.config();
+  std::vector<valhalla::meili::Measurement> measurements;
+  measurements.reserve(shape.size());
+  for (const auto& pt : shape) {
+    measurements.emplace_back(valhalla::midgard::PointLL{pt.ll().lng(), pt.ll().lat()},
+                              pt.has_accuracy_case() ? pt.accuracy() : config.emission_cost.gps_accuracy_meters,
+                              pt.has_radius_case() ? pt.radius() : config.candidate_search.search_radius_meters,
+                              pt.time());
+  }
+  return measurements;
+}
+
+/// Runs Meili over the given measurements and collects per-point candidates and costs from the matcher's state
+/// container, which stays alive until the next match.
+inline rust::Vec<MatchedPath> match_measurements(valhalla::meili::MapMatcher& matcher,
+                                                 const std::vector<valhalla::meili::Measurement>& measurements,
+                                                 uint32_t k) {
+  const auto results = matcher.OfflineMatch(measurements, k);
+  const auto& container = matcher.state_container();
+
+  rust::Vec<MatchedPath> paths;
+  paths.reserve(results.size());
+  float best_score = std::numeric_limits<float>::max();
+  for (const auto& result : results) {
+    MatchedPath path{.raw_score = result.score, .confidence = 0.0f};
+    best_score = std::min(best_score, result.score);
+
+    valhalla::meili::StateId prev_state;
+    path.points.reserve(result.results.size());
+    for (const auto& match : result.results) {
+      MatchedPoint point{
+        .lat = match.lnglat.lat(),
+        .lon = match.lnglat.lng(),
+        .edge_id = match.edgeid,
+        .distance_along = match.distance_along,
+        .distance_from = match.distance_from,
+        .epoch_time = match.epoch_time,
+        .is_break_point = match.is_break_point,
+        .begins_discontinuity = match.begins_discontinuity,
+        .ends_discontinuity = match.ends_discontinuity,
+      };
+
+      // Interpolated points have no state and thus no candidates
+      if (match.HasState()) {
+        for (const auto& stateid : container.column(match.stateid.time())) {
+          const auto emission_cost = matcher.emission_cost_model()(stateid);
+          const auto transition_cost =
+              prev_state.IsValid() ? matcher.transition_cost_model()(prev_state, stateid) : -1.0f;
+          for (const auto& edge : container.state(stateid).candidate().edges) {
+            point.candidates.push_back(MatchCandidate{
+              .edge_id = edge.id,
+              .percent_along = edge.percent_along,
+              .distance = edge.distance,
+              .emission_cost = emission_cost,
+              .transition_cost = transition_cost,
+              .selected = stateid == match.stateid && edge.id == match.edgeid,
+            });
+          }
+        }
+        prev_state = match.stateid;
+      }
+      path.points.push_back(std::move(point));
+    }
+
+    path.segments.reserve(result.segments.size());
+    for (const auto& segment : result.segments) {
+      path.segments.push_back(MatchSegment{
+        .edge_id = segment.edgeid,
+        .source = segment.source,
+        .target = segment.target,
+        .first_match_idx = segment.first_match_idx,
+        .last_match_idx = segment.last_match_idx,
+        .discontinuity = segment.discontinuity,
+      });
+    }
+    paths.push_back(std::move(path));
+  }
+
+  // Softmax over negated scores (lower is better), so confidences of all alternatives sum up to 1
+  float total = 0.0f;
+  for (auto& path : paths) {
+    path.confidence = std::exp(best_score - path.raw_score);
+    total += path.confidence;
+  }
+  for (auto& path : paths) {
+    path.confidence /= total;
+  }
+  return paths;
+}
+
+/// `baldr::GraphReader` over an already loaded [`TileSet`], so actors don't open the extracts again and read
+/// exactly the tiles of the reader they were created for.
+class TileSetGraphReader final : public valhalla::baldr::GraphReader {
+public:
+  TileSetGraphReader(const boost::property_tree::ptree& mjolnir, std::shared_ptr<TileSet> tileset)
+      : valhalla::baldr::GraphReader(without_extracts(mjolnir)), tileset_(std::move(tileset)) {
+    // `tile_extract_t` can only open extracts itself, so start with an empty one and point it to the loaded tiles
+    auto extract = std::make_shared<tile_extract_t>(boost::property_tree::ptree{});
+    extract->tiles = tileset_->tiles_;
+    extract->traffic_tiles = tileset_->traffic_tiles_;
+    extract->archive = tileset_->tar_;
+    extract->traffic_archive = tileset_->traffic_tar_;
+    tile_extract_ = std::move(extract);
+  }
+
+private:
+  /// Keeps the memory of the tiles alive
+  std::shared_ptr<TileSet> tileset_;
+
+  static boost::property_tree::ptree without_extracts(boost::property_tree::ptree mjolnir) {
+    mjolnir.erase("tile_extract");
+    mjolnir.erase("traffic_extract");
+    return mjolnir;
+  }
+};
+
 /// Copy&paste of the `valhalla::tyr::actor_t` class, but without the parsing json request format.
 struct Actor final {
   std::shared_ptr<valhalla::baldr::GraphReader> reader;
@@ -19,14 +150,21 @@ struct Actor final {
   valhalla::loki::loki_worker_t loki_worker;
   valhalla::thor::thor_worker_t thor_worker;
   valhalla::odin::odin_worker_t odin_worker;
+  /// Separate from the one owned by `thor_worker` to have an access to Meili's internal state
+  valhalla::meili::MapMatcherFactory matcher_factory;
 
-  Actor() : reader{}, loki_worker({}, reader), thor_worker({}, reader), odin_worker({}) {}
+  Actor()
+      : reader{}, loki_worker({}, reader), thor_worker({}, reader), odin_worker({}), matcher_factory({}, reader) {}
 
   Actor(const boost::property_tree::ptree& config)
-      : reader(std::make_shared<valhalla::baldr::GraphReader>(config.get_child("mjolnir"))),
+      : Actor(config, std::make_shared<valhalla::baldr::GraphReader>(config.get_child("mjolnir"))) {}
+
+  Actor(const boost::property_tree::ptree& config, std::shared_ptr<valhalla::baldr::GraphReader> graph_reader)
+      : reader(std::move(graph_reader)),
         loki_worker(config, reader),
         thor_worker(config, reader),
-        odin_worker(config) {
+        odin_worker(config),
+        matcher_factory(config, reader) {
     if (reader->GetTileSet().empty()) {
       throw std::runtime_error("Failed to load tileset");
     }
@@ -81,6 +219,21 @@ struct Actor final {
     });
   }
 
+  Response height(rust::Slice<const uint8_t> request) {
+    // Elevation sampling (skadi) is hosted by loki and reads `additional_data.elevation` from the config
+    return act(request, valhalla::Options::height, [this](valhalla::Api& api) { return loki_worker.height(api); });
+  }
+
+  rust::Vec<MatchedPath> trace_match(rust::Slice<const uint8_t> request) {
+    return with_api(request, valhalla::Options::trace_attributes, [this](valhalla::Api& api) {
+      loki_worker.trace(api);
+
+      const auto& options = api.options();
+      std::unique_ptr<valhalla::meili::MapMatcher> matcher(matcher_factory.Create(options));
+      return match_measurements(*matcher, to_measurements(*matcher, options.shape()), options.alternates() + 1);
+    });
+  }
+
   Response transit_available(rust::Slice<const uint8_t> request) {
     return act(request, valhalla::Options::transit_available,
                [this](valhalla::Api& api) { return loki_worker.transit_available(api); });
@@ -115,9 +268,22 @@ struct Actor final {
   }
 
 private:
-  /// `request` is a serialized [`valhalla::Options`] protobuf object.
   template <typename Fn>
   Response act(rust::Slice<const uint8_t> request, valhalla::Options::Action action, Fn&& action_fn) {
+    return with_api(request, action, [&](valhalla::Api& api) {
+      const auto format = api.options().format();
+      std::string output = action_fn(api);
+
+      return Response{
+        .data = std::make_unique<std::string>(std::move(output)),
+        .format = format,
+      };
+    });
+  }
+
+  /// `request` is a serialized [`valhalla::Options`] protobuf object.
+  template <typename Fn>
+  auto with_api(rust::Slice<const uint8_t> request, valhalla::Options::Action action, Fn&& fn) {
     google::protobuf::Arena arena;
     auto* api = google::protobuf::Arena::Create<valhalla::Api>(&arena);
     if (!api->mutable_options()->ParseFromArray(request.data(), request.size())) {
@@ -126,7 +292,6 @@ private:
 
     // This function sets many defaults in the API object and validates the request.
     valhalla::ParseApi("", action, *api);
-    const auto format = api->options().format();
 
     /// It's important to call `cleanup` after each action call to ensure that next
     /// action does not accidentally start where the previous one left off.
@@ -137,15 +302,13 @@ private:
         actor_.loki_worker.cleanup();
         actor_.thor_worker.cleanup();
         actor_.odin_worker.cleanup();
+        // Candidate cache is kept between requests like in the baseline actor, `ClearCache` only drops it once
+        // it grows past `meili.grid.cache_size`
+        actor_.matcher_factory.ClearCache();
       }
     } guard(*this);
 
-    std::string output = action_fn(*api);
-
-    return Response{
-      .data = std::make_unique<std::string>(std::move(output)),
-      .format = format,
-    };
+    return fn(*api);
   }
 };
 
@@ -153,6 +316,15 @@ std::unique_ptr<Actor> new_actor(const boost::property_tree::ptree& config) {
   return std::make_unique<Actor>(config);
 }
 
+std::unique_ptr<Actor> new_actor_with_tileset(const boost::property_tree::ptree& config,
+                                              const std::shared_ptr<TileSet>& tileset) {
+  if (!tileset->tar_) {
+    throw std::runtime_error("Actors can only use tilesets loaded from a tile extract");
+  }
+  return std::make_unique<Actor>(config,
+                                 std::make_shared<TileSetGraphReader>(config.get_child("mjolnir"), tileset));
+}
+
 std::unique_ptr<std::string> parse_json_request(rust::Str json, int action) {
   valhalla::Api api;
   valhalla::ParseApi(static_cast<std::string>(json), static_cast<valhalla::Options::Action>(action), api);
@@ -1,6 +1,8 @@
 use prost::Message;
 
-use crate::{Config, Error, proto::options::Format};
+use crate::{Config, Error, GraphId, GraphReader, proto::options::Format};
+
+pub use ffi::{MatchCandidate, MatchSegment, MatchedPath, MatchedPoint};
 
 pub mod proto {
     include!(concat!(env!("OUT_DIR"), "/valhalla.rs"));
@@ -8,7 +10,7 @@ pub mod proto {
 
 #[allow(clippy::needless_lifetimes)] // clippy goes nuts with cxx
 #[cxx::bridge]
-mod ffi {
+pub(crate) mod ffi {
     /// Helper struct to provide an access to C++'s buffer with serialized response data.
     struct Response {
         /// Raw response data, either a JSON string or binary data.
@@ -17,14 +19,94 @@ mod ffi {
         format: i32,
     }
 
+    /// Map-matching result for a whole trace, one of the `k` best paths found by Meili.
+    #[derive(Clone, Debug)]
+    struct MatchedPath {
+        /// One entry per input trace point, in the same order.
+        points: Vec<MatchedPoint>,
+        /// Edges of the matched path, with the indices of the trace points they were matched from.
+        segments: Vec<MatchSegment>,
+        /// Cumulative Viterbi cost of the path, lower is better.
+        raw_score: f32,
+        /// Confidence in `[0, 1]` relative to the other returned alternatives. Always 1 for a single path.
+        confidence: f32,
+    }
+
+    /// Map-matching result for a single trace point.
+    #[derive(Clone, Debug)]
+    struct MatchedPoint {
+        /// Latitude of the matched point, or of the input point if it wasn't matched.
+        lat: f64,
+        /// Longitude of the matched point, or of the input point if it wasn't matched.
+        lon: f64,
+        /// Matched edge or invalid [`GraphId`] if the point wasn't matched.
+        edge_id: GraphId,
+        /// Position of the matched point along the edge, in `[0, 1]`.
+        distance_along: f32,
+        /// Distance in meters from the input point to the matched point.
+        distance_from: f32,
+        /// Timestamp of the input point in seconds, if provided.
+        epoch_time: f64,
+        /// Whether the path is broken at this point, e.g. because of too large gap between points.
+        is_break_point: bool,
+        /// Whether the path starts a new discontinuous section at this point.
+        begins_discontinuity: bool,
+        /// Whether the path ends a discontinuous section at this point.
+        ends_discontinuity: bool,
+        /// All candidates Meili considered for this point. Empty for points that were interpolated
+        /// instead of being matched on their own, as they were too close to the previous point.
+        candidates: Vec<MatchCandidate>,
+    }
+
+    /// Candidate edge that Meili considered for a trace point.
+    #[derive(Clone, Debug)]
+    struct MatchCandidate {
+        /// Candidate edge.
+        edge_id: GraphId,
+        /// Position of the projected point along the edge, in `[0, 1]`.
+        percent_along: f64,
+        /// Distance in meters from the input point to the projected point.
+        distance: f32,
+        /// Cost of observing the input point from this candidate, based on the distance and GPS accuracy.
+        emission_cost: f32,
+        /// Cost of moving from the candidate selected for the previous point to this one. Negative if there is
+        /// no previous point or this candidate is unreachable from it.
+        transition_cost: f32,
+        /// Whether this candidate was selected for the point in the current path.
+        selected: bool,
+    }
+
+    /// Part of the edge traversed by the matched path.
+    #[derive(Clone, Debug)]
+    struct MatchSegment {
+        edge_id: GraphId,
+        /// Start of the traversed part of the edge, in `[0, 1]`.
+        source: f64,
+        /// End of the traversed part of the edge, in `[0, 1]`.
+        target: f64,
+        /// Index of the first trace point matched to this segment or -1 if none.
+        first_match_idx: i32,
+        /// Index of the last trace point matched to this segment or -1 if none.
+        last_match_idx: i32,
+        /// Whether the path is discontinuous after this segment.
+        discontinuity: bool,
+    }
+
     unsafe extern "C++" {
         include!("valhalla/src/actor.hpp");
 
         #[namespace = "boost::property_tree"]
         type ptree = crate::config::ffi::ptree;
 
+        type GraphId = crate::GraphId;
+        type TileSet = crate::ffi::TileSet;
+
         type Actor;
         fn new_actor(config: &ptree) -> Result<UniquePtr<Actor>>;
+        fn new_actor_with_tileset(
+            config: &ptree,
+            tileset: &SharedPtr<TileSet>,
+        ) -> Result<UniquePtr<Actor>>;
         // All methods accept [`proto::Options`] object serialized as a byte slice.
         fn route(self: Pin<&mut Actor>, request: &[u8]) -> Result<Response>;
         fn locate(self: Pin<&mut Actor>, request: &[u8]) -> Result<Response>;
@@ -33,6 +115,8 @@ mod ffi {
         fn isochrone(self: Pin<&mut Actor>, request: &[u8]) -> Result<Response>;
         fn trace_route(self: Pin<&mut Actor>, request: &[u8]) -> Result<Response>;
         fn trace_attributes(self: Pin<&mut Actor>, request: &[u8]) -> Result<Response>;
+        fn trace_match(self: Pin<&mut Actor>, request: &[u8]) -> Result<Vec<MatchedPath>>;
+        fn height(self: Pin<&mut Actor>, request: &[u8]) -> Result<Response>;
         fn transit_available(self: Pin<&mut Actor>, request: &[u8]) -> Result<Response>;
         fn expansion(self: Pin<&mut Actor>, request: &[u8]) -> Result<Response>;
         fn centroid(self: Pin<&mut Actor>, request: &[u8]) -> Result<Response>;
@@ -99,6 +183,58 @@ impl From<ffi::Response> for Response {
     }
 }
 
+/// Single edge explored by the routing algorithm, in the order of expansion. See [`Actor::expansion_edges()`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExpansionEdge {
+    pub edge_id: GraphId,
+    /// Edge the algorithm came from or invalid [`GraphId`] for the edges at the origin.
+    pub pred_edge_id: GraphId,
+    /// Accumulated cost of the path up to the end of this edge.
+    pub cost: u32,
+    /// Accumulated duration in seconds of the path up to the end of this edge.
+    pub duration: u32,
+    /// Accumulated distance in meters of the path up to the end of this edge.
+    pub distance: u32,
+    pub status: proto::expansion::EdgeStatus,
+}
+
+impl proto::Expansion {
+    /// Typed view over the parallel arrays of the expansion. Values of the properties that were not requested
+    /// via [`proto::Options`] `expansion_properties` are set to their defaults.
+    pub fn edges(&self) -> impl Iterator<Item = ExpansionEdge> + '_ {
+        let len = [
+            self.edge_id.len(),
+            self.pred_edge_id.len(),
+            self.costs.len(),
+            self.durations.len(),
+            self.distances.len(),
+            self.edge_status.len(),
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or_default();
+
+        (0..len).map(|i| ExpansionEdge {
+            edge_id: self
+                .edge_id
+                .get(i)
+                .map_or_else(GraphId::default, |&id| GraphId::new(id)),
+            pred_edge_id: self
+                .pred_edge_id
+                .get(i)
+                .map_or_else(GraphId::default, |&id| GraphId::new(id)),
+            cost: self.costs.get(i).copied().unwrap_or_default(),
+            duration: self.durations.get(i).copied().unwrap_or_default(),
+            distance: self.distances.get(i).copied().unwrap_or_default(),
+            status: self
+                .edge_status
+                .get(i)
+                .and_then(|&status| proto::expansion::EdgeStatus::try_from(status).ok())
+                .unwrap_or_default(),
+        })
+    }
+}
+
 /// High-level interface to interact with [Valhalla's API](https://valhalla.github.io/valhalla/api/).
 /// On contrary to the Valhalla REST and C++ APIs, this interface is designed to be used with [`proto::Options`] only,
 /// to avoid unnecessary conversions and to provide a strongly typed interface.
@@ -124,6 +260,15 @@ impl Actor {
         })
     }
 
+    /// Creates an actor that reads the tiles of an already loaded `reader` instead of opening the extracts from
+    /// `config` again. Only readers created by [`GraphReader::new()`] are supported.
+    pub(crate) fn with_reader(config: &Config, reader: &GraphReader) -> Result<Self, Error> {
+        Ok(Self {
+            inner: ffi::new_actor_with_tileset(config.inner(), &reader.0)?,
+            buffer: Vec::with_capacity(Self::INPUT_BUFFER_SIZE),
+        })
+    }
+
     /// Calculates a route between locations.
     ///
     /// # Example
@@ -317,6 +462,67 @@ impl Actor {
         self.act(ffi::Actor::trace_attributes, request)
     }
 
+    /// Map-matches a GPS trace like [`Actor::trace_attributes()`], but returns Meili's per-point diagnostics:
+    /// candidate edges with their emission and transition costs, break points and the path confidence.
+    ///
+    /// Set the `alternates` option to get up to `alternates + 1` best paths, ordered by their score.
+    ///
+    /// # Example
+    /// ```
+    /// # fn call_trace_match(mut actor: valhalla::Actor) {
+    /// use valhalla::proto;
+    ///
+    /// let request = proto::Options {
+    ///     costing_type: proto::costing::Type::Auto as i32,
+    ///     has_encoded_polyline: Some(proto::options::HasEncodedPolyline::EncodedPolyline(
+    ///         "_grbgAh~{nhF?lBAzBFvBHxBEtBKdB".into(),
+    ///     )),
+    ///     ..Default::default()
+    /// };
+    /// let paths = actor.trace_match(&request).unwrap();
+    /// for point in &paths[0].points {
+    ///     println!("{} candidates, matched to {}", point.candidates.len(), point.edge_id);
+    /// }
+    /// # }
+    /// ```
+    pub fn trace_match(&mut self, request: &proto::Options) -> Result<Vec<MatchedPath>, Error> {
+        self.encode(request);
+        let result = self.inner.as_mut().unwrap().trace_match(&self.buffer);
+        self.shrink_buffer();
+        Ok(result?)
+    }
+
+    /// Samples elevation along the given `shape` or `encoded_polyline`. Always returns a Valhalla JSON response.
+    ///
+    /// Elevation data is read from the SRTM `.hgt` tiles in the `additional_data.elevation` directory of the
+    /// [`Config`]. Set the `range` option to also get the cumulative distance along the shape for every height.
+    ///
+    /// # Example
+    /// ```
+    /// # fn call_height(mut actor: valhalla::Actor) {
+    /// use valhalla::proto;
+    ///
+    /// let request = proto::Options {
+    ///     shape: vec![
+    ///         proto::Location {
+    ///             ll: valhalla::LatLon(55.6086, 13.0005).into(),
+    ///             ..Default::default()
+    ///         },
+    ///         proto::Location {
+    ///             ll: valhalla::LatLon(55.5944, 13.0002).into(),
+    ///             ..Default::default()
+    ///         },
+    ///     ],
+    ///     has_range: Some(proto::options::HasRange::Range(true)),
+    ///     ..Default::default()
+    /// };
+    /// let response = actor.height(&request).unwrap();
+    /// # }
+    /// ```
+    pub fn height(&mut self, request: &proto::Options) -> Result<Response, Error> {
+        self.act(ffi::Actor::height, request)
+    }
+
     /// Checks if transit/public transportation is available at given locations.
     ///
     /// # Example
@@ -372,6 +578,70 @@ impl Actor {
         self.act(ffi::Actor::expansion, request)
     }
 
+    /// Same as [`Actor::expansion()`], but returns explored edges as typed data in the order of expansion instead
+    /// of GeoJSON. Response format and `expansion_properties` of the request are overridden to get all properties.
+    ///
+    /// # Example
+    /// ```
+    /// # fn call_expansion_edges(mut actor: valhalla::Actor) {
+    /// use valhalla::proto;
+    ///
+    /// let request = proto::Options {
+    ///     has_expansion_action: Some(proto::options::HasExpansionAction::ExpansionAction(
+    ///         proto::options::Action::Route as i32,
+    ///     )),
+    ///     costing_type: proto::costing::Type::Auto as i32,
+    ///     locations: vec![
+    ///         proto::Location {
+    ///             ll: valhalla::LatLon(55.6086, 13.0005).into(),
+    ///             ..Default::default()
+    ///         },
+    ///         proto::Location {
+    ///             ll: valhalla::LatLon(55.5944, 13.0002).into(),
+    ///             ..Default::default()
+    ///         },
+    ///     ],
+    ///     ..Default::default()
+    /// };
+    /// let edges = actor.expansion_edges(&request).unwrap();
+    /// let settled = edges
+    ///     .iter()
+    ///     .filter(|edge| edge.status == proto::expansion::EdgeStatus::Settled)
+    ///     .count();
+    /// # }
+    /// ```
+    pub fn expansion_edges(
+        &mut self,
+        request: &proto::Options,
+    ) -> Result<Vec<ExpansionEdge>, Error> {
+        use proto::options::ExpansionProperties;
+
+        let request = proto::Options {
+            format: Format::Pbf as i32,
+            expansion_properties: [
+                ExpansionProperties::Costs,
+                ExpansionProperties::Durations,
+                ExpansionProperties::Distances,
+                ExpansionProperties::EdgeStatus,
+                ExpansionProperties::EdgeId,
+                ExpansionProperties::PredEdgeId,
+            ]
+            .into_iter()
+            .map(|property| property as i32)
+            .collect(),
+            ..request.clone()
+        };
+        match self.expansion(&request)? {
+            Response::Pbf(api) => Ok(api
+                .expansion
+                .map(|expansion| expansion.edges().collect())
+                .unwrap_or_default()),
+            response => Err(Error(
+                format!("Expected PBF expansion response, got: {response:?}").into(),
+            )),
+        }
+    }
+
     /// Finds the least cost convergence point from multiple locations.
     ///
     /// # Example
@@ -426,18 +696,25 @@ impl Actor {
             &'a [u8],
         ) -> Result<ffi::Response, cxx::Exception>,
     {
+        self.encode(request);
+        let result = action_fn(self.inner.as_mut().unwrap(), &self.buffer);
+        self.shrink_buffer();
+
+        Ok(Response::from(result?))
+    }
+
+    /// Encodes the request into the reusable buffer.
+    fn encode(&mut self, request: &proto::Options) {
         self.buffer.clear();
         self.buffer.reserve(request.encoded_len());
         request.encode_raw(&mut self.buffer);
+    }
 
-        let result = action_fn(self.inner.as_mut().unwrap(), &self.buffer);
-
-        // Single huge request can lead to excessive memory usage, let's keep it manageable.
+    /// Single huge request can lead to excessive memory usage, let's keep it manageable.
+    fn shrink_buffer(&mut self) {
         if self.buffer.capacity() > Self::INPUT_BUFFER_SIZE {
             self.buffer = Vec::with_capacity(Self::INPUT_BUFFER_SIZE);
         }
-
-        Ok(Response::from(result?))
     }
 
     /// Helper function to convert a Valhalla JSON string into Valhalla PBF request as [`proto::Options`] object.
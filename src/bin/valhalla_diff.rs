@@ -0,0 +1,139 @@
+//! Compares two builds of a tileset and writes the differing edges as GeoJSON, e.g.
+//! `valhalla_diff old/tiles.tar new/tiles.tar -o diff.geojson`.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    process::ExitCode,
+};
+
+use valhalla::{Config, GraphDiff, GraphId, GraphReader};
+
+const USAGE: &str = "\
+Usage: valhalla_diff <old> <new> [options]
+
+<old> and <new> are paths to tiles.tar extracts or Valhalla config files.
+
+Options:
+    -t, --tile <level/tile_id>  Compare only the given tile, can be repeated
+    -o, --output <file>         Output GeoJSON file, stdout by default
+    -q, --quiet                 Don't print per-tile summaries";
+
+struct Args {
+    old: String,
+    new: String,
+    tiles: Vec<GraphId>,
+    output: Option<String>,
+    quiet: bool,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut positional = Vec::new();
+    let mut tiles = Vec::new();
+    let mut output = None;
+    let mut quiet = false;
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("Missing value for {arg}"))
+        };
+        match arg.as_str() {
+            "-t" | "--tile" => tiles.push(parse_tile(&value()?)?),
+            "-o" | "--output" => output = Some(value()?),
+            "-q" | "--quiet" => quiet = true,
+            "-h" | "--help" => return Err(String::new()),
+            _ if arg.starts_with('-') => return Err(format!("Unknown option {arg}")),
+            _ => positional.push(arg),
+        }
+    }
+
+    let [old, new] = <[String; 2]>::try_from(positional)
+        .map_err(|_| "Expected paths to the old and new tilesets".to_string())?;
+    Ok(Args {
+        old,
+        new,
+        tiles,
+        output,
+        quiet,
+    })
+}
+
+/// Parses tiles like `2/762485`.
+fn parse_tile(value: &str) -> Result<GraphId, String> {
+    value
+        .split_once('/')
+        .and_then(|(level, tile)| GraphId::from_parts(level.parse().ok()?, tile.parse().ok()?, 0))
+        .ok_or_else(|| format!("Invalid tile: {value}"))
+}
+
+/// Opens a tiles.tar extract directly or a tileset described by a config file.
+fn open_reader(path: &str) -> Result<GraphReader, String> {
+    let config = if path.ends_with(".tar") {
+        Config::from_tile_extract(path)
+    } else {
+        Config::from_file(path)
+    };
+    let config = config.map_err(|err| format!("{path}: {err}"))?;
+    GraphReader::new(&config).map_err(|err| format!("{path}: {err}"))
+}
+
+fn run(args: Args) -> Result<usize, String> {
+    let old = open_reader(&args.old)?;
+    let new = open_reader(&args.new)?;
+
+    let mut diff = GraphDiff::new(&old, &new);
+    if !args.tiles.is_empty() {
+        diff = diff.tiles(args.tiles);
+    }
+    let report = diff.compare();
+
+    if !args.quiet {
+        for tile in report
+            .tiles
+            .iter()
+            .filter(|tile| tile.added + tile.removed + tile.changed > 0)
+        {
+            eprintln!(
+                "{}/{}: {} added, {} removed, {} changed, {} unchanged",
+                tile.tile.level(),
+                tile.tile.tileid(),
+                tile.added,
+                tile.removed,
+                tile.changed,
+                tile.unchanged
+            );
+        }
+    }
+
+    let out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(File::create(path).map_err(|err| format!("{path}: {err}"))?),
+        None => Box::new(io::stdout().lock()),
+    };
+    report
+        .write_geojson(BufWriter::new(out))
+        .map_err(|err| format!("{err}"))
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            if !err.is_empty() {
+                eprintln!("{err}\n");
+            }
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match run(args) {
+        Ok(count) => {
+            eprintln!("Found {count} differing edges");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
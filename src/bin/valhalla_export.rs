@@ -0,0 +1,137 @@
+//! Exports graph edges or nodes for inspection in GIS tools, e.g.
+//! `valhalla_export config.json edges --format fgb --bbox 42.50,1.51,42.51,1.53 -o edges.fgb`.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    process::ExitCode,
+};
+
+use valhalla::{Config, ExportFormat, ExportLayer, GraphExport, GraphId, GraphReader, LatLon};
+
+const USAGE: &str = "\
+Usage: valhalla_export <config.json> <edges|nodes> [options]
+
+Options:
+    -f, --format <geojson|geojsonseq|csv|fgb>     Output format, GeoJSON by default
+    -b, --bbox <min_lat,min_lon,max_lat,max_lon>  Export only objects within the bounding box
+    -t, --tile <level/tile_id>                    Export only the given tile, can be repeated
+        --skip-shortcuts                          Skip shortcut edges
+    -o, --output <file>                           Output file, stdout by default";
+
+struct Args {
+    config: String,
+    layer: ExportLayer,
+    format: ExportFormat,
+    bbox: Option<(LatLon, LatLon)>,
+    tiles: Vec<GraphId>,
+    skip_shortcuts: bool,
+    output: Option<String>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut positional = Vec::new();
+    let mut format = ExportFormat::GeoJson;
+    let mut bbox = None;
+    let mut tiles = Vec::new();
+    let mut skip_shortcuts = false;
+    let mut output = None;
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("Missing value for {arg}"))
+        };
+        match arg.as_str() {
+            "-f" | "--format" => format = value()?.parse().map_err(|err| format!("{err}"))?,
+            "-b" | "--bbox" => bbox = Some(parse_bbox(&value()?)?),
+            "-t" | "--tile" => tiles.push(parse_tile(&value()?)?),
+            "--skip-shortcuts" => skip_shortcuts = true,
+            "-o" | "--output" => output = Some(value()?),
+            "-h" | "--help" => return Err(String::new()),
+            _ if arg.starts_with('-') => return Err(format!("Unknown option {arg}")),
+            _ => positional.push(arg),
+        }
+    }
+
+    let [config, layer] = <[String; 2]>::try_from(positional)
+        .map_err(|_| "Expected config path and layer".to_string())?;
+    Ok(Args {
+        config,
+        layer: layer.parse().map_err(|err| format!("{err}"))?,
+        format,
+        bbox,
+        tiles,
+        skip_shortcuts,
+        output,
+    })
+}
+
+fn parse_bbox(value: &str) -> Result<(LatLon, LatLon), String> {
+    let coords: Vec<f64> = value
+        .split(',')
+        .map(|coord| coord.trim().parse())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("Invalid bbox: {value}"))?;
+    match coords.as_slice() {
+        &[min_lat, min_lon, max_lat, max_lon] => {
+            Ok((LatLon(min_lat, min_lon), LatLon(max_lat, max_lon)))
+        }
+        _ => Err(format!("Invalid bbox: {value}")),
+    }
+}
+
+/// Parses tiles like `2/762485`.
+fn parse_tile(value: &str) -> Result<GraphId, String> {
+    value
+        .split_once('/')
+        .and_then(|(level, tile)| GraphId::from_parts(level.parse().ok()?, tile.parse().ok()?, 0))
+        .ok_or_else(|| format!("Invalid tile: {value}"))
+}
+
+fn run(args: Args) -> Result<usize, String> {
+    let config = Config::from_file(&args.config).map_err(|err| format!("{err}"))?;
+    let reader = GraphReader::new(&config).map_err(|err| format!("{err}"))?;
+
+    let mut export = GraphExport::new(&reader);
+    if let Some((min, max)) = args.bbox {
+        export = export.bbox(min, max);
+    }
+    if !args.tiles.is_empty() {
+        export = export.tiles(args.tiles);
+    }
+    if args.skip_shortcuts {
+        export = export.skip_shortcuts();
+    }
+
+    let out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(File::create(path).map_err(|err| format!("{path}: {err}"))?),
+        None => Box::new(io::stdout().lock()),
+    };
+    export
+        .write(args.layer, args.format, BufWriter::new(out))
+        .map_err(|err| format!("{err}"))
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            if !err.is_empty() {
+                eprintln!("{err}\n");
+            }
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match run(args) {
+        Ok(count) => {
+            eprintln!("Exported {count} features");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
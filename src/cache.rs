@@ -0,0 +1,134 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+};
+
+use crate::{GraphId, GraphReader, GraphTile};
+
+/// Thread-safe wrapper around [`GraphReader`] that keeps recently used tiles in memory.
+///
+/// [`GraphReader::get_tile()`] parses the tile on every call and, for readers created with
+/// [`GraphReader::from_loader()`], loads it again. `CachedGraphReader` keeps parsed tiles until their total
+/// size exceeds the byte budget and then evicts the least recently used ones. Clones share the same cache.
+/// ```rust
+/// let config = valhalla::Config::from_file("path/to/config.json").unwrap();
+/// let reader = valhalla::GraphReader::new(&config).unwrap();
+/// let cached = valhalla::CachedGraphReader::new(reader, 256 * 1024 * 1024);
+/// let tile = cached.get_tile(valhalla::GraphId::new(0));
+/// println!("{:?}", cached.stats());
+/// ```
+#[derive(Clone)]
+pub struct CachedGraphReader {
+    reader: GraphReader,
+    cache: Arc<Mutex<Lru>>,
+}
+
+/// Cache metrics returned by [`CachedGraphReader::stats()`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Number of requests served from the cache.
+    pub hits: u64,
+    /// Number of requests that had to load the tile from the reader, including missing tiles.
+    pub misses: u64,
+    /// Number of tiles evicted to stay within the budget.
+    pub evictions: u64,
+    /// Number of tiles currently in the cache.
+    pub tiles: usize,
+    /// Total size of tiles currently in the cache.
+    pub bytes: usize,
+}
+
+impl CachedGraphReader {
+    /// Wraps `reader` with a cache that holds at most `budget` bytes of tiles.
+    pub fn new(reader: GraphReader, budget: usize) -> Self {
+        Self {
+            reader,
+            cache: Arc::new(Mutex::new(Lru {
+                budget,
+                ..Default::default()
+            })),
+        }
+    }
+
+    /// Same as [`GraphReader::get_tile()`], but returns the cached tile if there is one.
+    pub fn get_tile(&self, id: GraphId) -> Option<GraphTile> {
+        let key = id.tile().value;
+        if let Some(tile) = self.lock().get(key) {
+            return Some(tile);
+        }
+        // Load without holding the lock, so other threads can use the cache meanwhile
+        let tile = self.reader.get_tile(id)?;
+        self.lock().insert(key, tile.clone());
+        Some(tile)
+    }
+
+    /// Underlying reader, e.g. to list tiles.
+    pub fn reader(&self) -> &GraphReader {
+        &self.reader
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.lock().stats
+    }
+
+    /// Drops all cached tiles, keeping hit and miss counters.
+    pub fn clear(&self) {
+        let mut cache = self.lock();
+        cache.tiles.clear();
+        cache.order.clear();
+        cache.stats.tiles = 0;
+        cache.stats.bytes = 0;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Lru> {
+        self.cache.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+#[derive(Default)]
+struct Lru {
+    budget: usize,
+    /// Cached tiles by tile base id, with the tick of their last use.
+    tiles: HashMap<u64, (GraphTile, u64)>,
+    /// Tile base ids ordered by their last use, oldest first.
+    order: BTreeMap<u64, u64>,
+    tick: u64,
+    stats: CacheStats,
+}
+
+impl Lru {
+    fn get(&mut self, key: u64) -> Option<GraphTile> {
+        let Some((tile, used)) = self.tiles.get_mut(&key) else {
+            self.stats.misses += 1;
+            return None;
+        };
+        self.stats.hits += 1;
+        self.tick += 1;
+        self.order.remove(used);
+        self.order.insert(self.tick, key);
+        *used = self.tick;
+        Some(tile.clone())
+    }
+
+    fn insert(&mut self, key: u64, tile: GraphTile) {
+        let size = tile.size();
+        // Another thread could load the same tile meanwhile, or it doesn't fit at all
+        if size > self.budget || self.tiles.contains_key(&key) {
+            return;
+        }
+        while self.stats.bytes + size > self.budget {
+            let Some((_, oldest)) = self.order.pop_first() else {
+                break;
+            };
+            if let Some((evicted, _)) = self.tiles.remove(&oldest) {
+                self.stats.bytes -= evicted.size();
+                self.stats.evictions += 1;
+            }
+        }
+        self.tick += 1;
+        self.order.insert(self.tick, key);
+        self.tiles.insert(key, (tile, self.tick));
+        self.stats.bytes += size;
+        self.stats.tiles = self.tiles.len();
+    }
+}
@@ -2,34 +2,293 @@
 
 #include <valhalla/baldr/rapidjson_utils.h>
 #include <boost/property_tree/ptree.hpp>
+#include <rapidjson/stringbuffer.h>
+#include <rapidjson/writer.h>
+
+#include <fstream>
+#include <iterator>
 
 #include "cxx.h"
 
-std::unique_ptr<boost::property_tree::ptree> from_file(rust::slice<const uint8_t> path) {
-  auto pt = std::make_unique<boost::property_tree::ptree>();
-  try {
-    std::string str(reinterpret_cast<const char*>(path.data()), path.size());
-    rapidjson::read_json(str, *pt);
-  } catch (const std::exception& e) {
-    throw std::runtime_error("Failed to read config file: " + std::string(e.what()));
+using ptree = boost::property_tree::ptree;
+
+// Generated header with shared structs used by the functions below
+#include "valhalla/src/config.rs.h"
+
+/// Marks values of the layout ptree that were stored as JSON numbers, booleans or nulls
+inline const std::string RAW_VALUE = "raw";
+/// Marks values of the layout ptree that were stored as JSON strings
+inline const std::string STRING_VALUE = "string";
+/// Marks arrays in the layout ptree, objects have empty data
+inline const std::string ARRAY_VALUE = "array";
+
+/// Builds a ptree with the same layout as `rapidjson::add_object()` gives to the parsed JSON, but with the JSON type
+/// of every node as its data. ptree keeps all values as strings and stores empty objects, arrays and strings the
+/// same way, so this is the only way to tell `1` from `"1"` or `""` from `{}`.
+inline void add_types(const rapidjson::Value& value, ptree& types) {
+  if (value.IsObject()) {
+    for (const auto& member : value.GetObject()) {
+      ptree child;
+      add_types(member.value, child);
+      types.push_back({member.name.GetString(), child});
+    }
+  } else if (value.IsArray()) {
+    types.put_value(ARRAY_VALUE);
+    for (const auto& item : value.GetArray()) {
+      ptree child;
+      add_types(item, child);
+      types.push_back({"", child});
+    }
+  } else {
+    types.put_value(value.IsString() ? STRING_VALUE : RAW_VALUE);
   }
-  return pt;
 }
 
-std::unique_ptr<boost::property_tree::ptree> from_json(rust::str config) {
+inline std::unique_ptr<ptree> parse_json(const char* data, size_t size, ptree& types) {
   rapidjson::Document d;
-  d.Parse(config.data(), config.size());
+  d.Parse(data, size);
   if (d.HasParseError()) {
     throw std::runtime_error("Could not parse json, error at offset: " + std::to_string(d.GetErrorOffset()));
   }
 
-  auto pt = std::make_unique<boost::property_tree::ptree>();
+  auto pt = std::make_unique<ptree>();
+  const auto& value = const_cast<const rapidjson::Document&>(d);
   if (d.IsObject()) {
-    rapidjson::add_object(const_cast<const rapidjson::Document*>(&d)->GetObject(), *pt);
+    rapidjson::add_object(value.GetObject(), *pt);
   } else if (d.IsArray()) {
-    rapidjson::add_array(const_cast<const rapidjson::Document*>(&d)->GetArray(), *pt);
+    rapidjson::add_array(value.GetArray(), *pt);
   } else {
     throw std::runtime_error("Json is not an object or array");
   }
+  types = ptree();
+  add_types(value, types);
   return pt;
 }
+
+inline std::unique_ptr<ptree> new_ptree() {
+  return std::make_unique<ptree>();
+}
+
+inline std::unique_ptr<ptree> from_file(rust::slice<const uint8_t> path, ptree& types) {
+  try {
+    std::string str(reinterpret_cast<const char*>(path.data()), path.size());
+    std::ifstream file(str);
+    if (!file) {
+      throw std::runtime_error("Cannot open file " + str);
+    }
+    std::string json((std::istreambuf_iterator<char>(file)), std::istreambuf_iterator<char>());
+    return parse_json(json.data(), json.size(), types);
+  } catch (const std::exception& e) {
+    throw std::runtime_error("Failed to read config file: " + std::string(e.what()));
+  }
+}
+
+inline std::unique_ptr<ptree> from_json(rust::str config, ptree& types) {
+  return parse_json(config.data(), config.size(), types);
+}
+
+inline ptree::path_type to_path(rust::str path) {
+  return ptree::path_type(std::string(path), '.');
+}
+
+inline std::unique_ptr<ptree> clone(const ptree& pt) {
+  return std::make_unique<ptree>(pt);
+}
+
+inline bool get(const ptree& pt, rust::str path, rust::String& value) {
+  auto child = pt.get_child_optional(to_path(path));
+  if (!child || !child->empty()) {
+    return false;
+  }
+  value = child->data();
+  return true;
+}
+
+/// Type of the value if it's a valid JSON number, boolean or null on its own
+inline boost::optional<rapidjson::Type> raw_type(const std::string& data) {
+  rapidjson::Document d;
+  d.Parse(data.data(), data.size());
+  if (data.empty() || d.HasParseError() || !(d.IsNumber() || d.IsBool() || d.IsNull())) {
+    return boost::none;
+  }
+  return d.GetType();
+}
+
+/// Values replacing numbers, booleans and nulls keep their JSON type as long as they are still valid as one
+inline void put(ptree& pt, ptree& types, rust::str path, rust::str value) {
+  auto existing = types.get_child_optional(to_path(path));
+  std::string data(value);
+  bool raw = existing && existing->data() == RAW_VALUE && raw_type(data);
+  pt.put(to_path(path), data);
+  types.put(to_path(path), raw ? RAW_VALUE : STRING_VALUE);
+}
+
+inline bool erase(ptree& pt, const std::string& path) {
+  auto pos = path.rfind('.');
+  auto parent = pos == std::string::npos ? boost::optional<ptree&>(pt)
+                                         : pt.get_child_optional(ptree::path_type(path.substr(0, pos), '.'));
+  return parent && parent->erase(pos == std::string::npos ? path : path.substr(pos + 1)) > 0;
+}
+
+inline bool remove(ptree& pt, ptree& types, rust::str path) {
+  std::string str(path);
+  erase(types, str);
+  return erase(pt, str);
+}
+
+/// Arrays are stored in ptree as children with empty keys
+inline bool is_array(const ptree& pt) {
+  return !pt.empty() && pt.front().first.empty();
+}
+
+/// Whether the node is an empty object according to its layout node
+inline bool is_empty_object(const ptree& pt, const ptree& types) {
+  return pt.empty() && types.data().empty();
+}
+
+/// Layout node of the next child, or an empty one if the layout has no more children
+inline const ptree& next_types(const ptree& types, ptree::const_iterator& child_types) {
+  static const ptree no_types;
+  return child_types != types.end() ? (child_types++)->second : no_types;
+}
+
+inline std::string child_path(const std::string& path, const std::string& key) {
+  return path.empty() ? key : path + "." + key;
+}
+
+/// Adds dot-separated paths of the values in `pt` to `paths`. Arrays are single values, empty objects have none.
+inline void value_paths(const ptree& pt,
+                        const ptree& types,
+                        const std::string& path,
+                        rust::Vec<rust::String>& paths) {
+  if (pt.empty() || is_array(pt)) {
+    if (!is_empty_object(pt, types)) {
+      paths.push_back(rust::String(path));
+    }
+    return;
+  }
+  auto child_types = types.begin();
+  for (const auto& [key, child] : pt) {
+    value_paths(child, next_types(types, child_types), child_path(path, key), paths);
+  }
+}
+
+/// `types` and `other_types` have the same layout as `pt` and `other`, see `add_types()`. If they don't, missing
+/// layout nodes are treated as objects, which only loses the JSON types of the values below them.
+inline void merge_at(ptree& pt,
+                     ptree& types,
+                     const ptree& other,
+                     const ptree& other_types,
+                     const std::string& path,
+                     rust::Vec<rust::String>& applied) {
+  if (is_empty_object(other, other_types)) {
+    // Empty objects have nothing to merge
+    return;
+  }
+  if (other.empty() || is_array(other) || pt.empty() || is_array(pt)) {
+    pt = other;
+    types = other_types;
+    value_paths(other, other_types, path, applied);
+    return;
+  }
+  auto child_types = other_types.begin();
+  for (const auto& [key, child] : other) {
+    const auto& child_type = next_types(other_types, child_types);
+    auto existing = pt.find(key);
+    if (existing == pt.not_found()) {
+      pt.push_back({key, child});
+      types.push_back({key, child_type});
+      value_paths(child, child_type, child_path(path, key), applied);
+      continue;
+    }
+    auto existing_type = types.find(key);
+    auto& existing_types =
+        existing_type != types.not_found() ? existing_type->second : types.push_back({key, ptree()})->second;
+    merge_at(existing->second, existing_types, child, child_type, child_path(path, key), applied);
+  }
+}
+
+/// Returns dot-separated paths of the values set by `other`
+inline rust::Vec<rust::String> merge(ptree& pt, ptree& types, const ptree& other, const ptree& other_types) {
+  rust::Vec<rust::String> applied;
+  merge_at(pt, types, other, other_types, "", applied);
+  return applied;
+}
+
+/// `types` is `nullptr` for values without a counterpart in the layout, which are all written as strings
+inline void write_json(const ptree& pt,
+                       const ptree* types,
+                       rapidjson::Writer<rapidjson::StringBuffer>& writer) {
+  if (types && pt.empty() && types->data() == ARRAY_VALUE) {
+    writer.StartArray();
+    writer.EndArray();
+    return;
+  }
+  if (types && pt.data().empty() && is_empty_object(pt, *types)) {
+    writer.StartObject();
+    writer.EndObject();
+    return;
+  }
+  if (pt.empty()) {
+    // Values lose their types in ptree, so only values that were stored as numbers, booleans and nulls are
+    // written as such
+    const auto& data = pt.data();
+    boost::optional<rapidjson::Type> type;
+    if (types && types->data() == RAW_VALUE) {
+      type = raw_type(data);
+    }
+    if (type) {
+      writer.RawValue(data.data(), data.size(), *type);
+    } else {
+      writer.String(data.data(), data.size());
+    }
+    return;
+  }
+
+  auto child_types = types ? types->begin() : ptree::const_iterator();
+  auto next_type = [&]() -> const ptree* {
+    return types && child_types != types->end() ? &(child_types++)->second : nullptr;
+  };
+  if (is_array(pt)) {
+    writer.StartArray();
+    for (const auto& [_, child] : pt) {
+      write_json(child, next_type(), writer);
+    }
+    writer.EndArray();
+  } else {
+    writer.StartObject();
+    for (const auto& [key, child] : pt) {
+      writer.Key(key.data(), key.size());
+      write_json(child, next_type(), writer);
+    }
+    writer.EndObject();
+  }
+}
+
+inline rust::String to_json(const ptree& pt, const ptree& types) {
+  rapidjson::StringBuffer buffer;
+  rapidjson::Writer<rapidjson::StringBuffer> writer(buffer);
+  if (pt.empty()) {
+    writer.StartObject();
+    writer.EndObject();
+  } else {
+    write_json(pt, &types, writer);
+  }
+  return rust::String(buffer.GetString(), buffer.GetSize());
+}
+
+inline rust::Vec<ConfigEntry> children(const ptree& pt, rust::str path) {
+  rust::Vec<ConfigEntry> entries;
+  auto node = pt.get_child_optional(to_path(path));
+  if (!node) {
+    return entries;
+  }
+  for (const auto& [key, child] : *node) {
+    entries.push_back(ConfigEntry{
+        .key = rust::String(key),
+        .value = child.empty() ? rust::String(child.data()) : rust::String(),
+        .has_children = !child.empty(),
+    });
+  }
+  return entries;
+}
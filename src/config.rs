@@ -1,16 +1,53 @@
-use std::{os::unix::ffi::OsStrExt, path::Path};
+use std::{fmt::Display, os::unix::ffi::OsStrExt, path::Path, str::FromStr};
 
 use crate::Error;
 
+pub use ffi::ConfigEntry;
+pub use loader::{ConfigLoader, ConfigReport, ConfigSource};
+pub use validate::{ConfigIssue, ValueKind};
+
+mod builder;
+mod loader;
+mod validate;
+
+pub use builder::{
+    ConfigBuilder, CostingLimits, HierarchyLimits, LokiConfig, LokiServiceDefaults, MeiliConfig,
+    MeiliModeParameters, MeiliParameters, MjolnirConfig, OdinConfig, ServiceLimits, ThorConfig,
+};
+
 #[cxx::bridge]
 pub(crate) mod ffi {
+    /// Direct child of a configuration node, returned by [`Config::children()`](crate::Config::children).
+    #[derive(Clone, Debug, PartialEq)]
+    struct ConfigEntry {
+        /// Key of the child, empty for array items.
+        key: String,
+        /// Value of the child, empty if it has children.
+        value: String,
+        /// Whether the child is an object or a non-empty array.
+        has_children: bool,
+    }
+
     unsafe extern "C++" {
         include!("valhalla/src/config.hpp");
 
         #[namespace = "boost::property_tree"]
         type ptree;
-        fn from_file(path: &[u8]) -> Result<UniquePtr<ptree>>;
-        fn from_json(config: &str) -> Result<UniquePtr<ptree>>;
+        fn new_ptree() -> UniquePtr<ptree>;
+        fn from_file(path: &[u8], types: Pin<&mut ptree>) -> Result<UniquePtr<ptree>>;
+        fn from_json(config: &str, types: Pin<&mut ptree>) -> Result<UniquePtr<ptree>>;
+        fn clone(pt: &ptree) -> UniquePtr<ptree>;
+        fn get(pt: &ptree, path: &str, value: &mut String) -> bool;
+        fn put(pt: Pin<&mut ptree>, types: Pin<&mut ptree>, path: &str, value: &str);
+        fn remove(pt: Pin<&mut ptree>, types: Pin<&mut ptree>, path: &str) -> bool;
+        fn merge(
+            pt: Pin<&mut ptree>,
+            types: Pin<&mut ptree>,
+            other: &ptree,
+            other_types: &ptree,
+        ) -> Vec<String>;
+        fn to_json(pt: &ptree, types: &ptree) -> String;
+        fn children(pt: &ptree, path: &str) -> Vec<ConfigEntry>;
     }
 }
 
@@ -19,7 +56,20 @@ pub(crate) mod ffi {
 /// Provides methods to read configuration from a file or JSON string, created by `valhalla_build_config` script.
 /// For more information about the configuration and available options see the Valhalla documentation:
 /// https://github.com/valhalla/valhalla/blob/master/scripts/valhalla_build_config
-pub struct Config(cxx::UniquePtr<ffi::ptree>);
+pub struct Config {
+    inner: cxx::UniquePtr<ffi::ptree>,
+    /// Same layout as `inner`, marks values that were stored as JSON numbers, booleans or nulls.
+    types: cxx::UniquePtr<ffi::ptree>,
+}
+
+impl Clone for Config {
+    fn clone(&self) -> Self {
+        Config {
+            inner: ffi::clone(self.inner()),
+            types: ffi::clone(&self.types),
+        }
+    }
+}
 
 impl Config {
     /// Reads configuration from the given Valhalla configuration file.
@@ -27,9 +77,9 @@ impl Config {
     /// let config = valhalla::Config::from_file("path/to/config.json");
     /// ```
     pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
-        Ok(Config(ffi::from_file(
-            path.as_ref().as_os_str().as_bytes(),
-        )?))
+        let mut types = ffi::new_ptree();
+        let inner = ffi::from_file(path.as_ref().as_os_str().as_bytes(), types.pin_mut())?;
+        Ok(Config { inner, types })
     }
 
     /// Reads configuration from Valhalla configuration JSON string.
@@ -38,7 +88,9 @@ impl Config {
     /// let config = valhalla::Config::from_json(&json);
     /// ```
     pub fn from_json(config: &str) -> Result<Self, Error> {
-        Ok(Config(ffi::from_json(config)?))
+        let mut types = ffi::new_ptree();
+        let inner = ffi::from_json(config, types.pin_mut())?;
+        Ok(Config { inner, types })
     }
 
     /// Creates a new Valhalla configuration from path to the tiles tar extract.
@@ -46,15 +98,89 @@ impl Config {
     /// let config = valhalla::Config::from_tile_extract("path/to/tiles.tar");
     /// ```
     pub fn from_tile_extract(tile_extract: impl AsRef<Path>) -> Result<Self, Error> {
-        let config = format!(
-            "{{\"mjolnir\":{{\"tile_extract\":\"{}\"}}}}",
-            tile_extract.as_ref().display()
+        ConfigBuilder::new()
+            .tile_extract(tile_extract.as_ref())
+            .build()
+    }
+
+    /// Reads a value at the dot-separated `path`, e.g. `service_limits.auto.max_distance`.
+    /// Returns `None` if there is no such value, it's an object or array, or it can't be parsed as `T`.
+    /// ```rust
+    /// let config = valhalla::Config::from_file("path/to/config.json").unwrap();
+    /// let max_distance: Option<f64> = config.get("service_limits.auto.max_distance");
+    /// ```
+    pub fn get<T: FromStr>(&self, path: &str) -> Option<T> {
+        let mut value = String::new();
+        if !ffi::get(self.inner(), path, &mut value) {
+            return None;
+        }
+        value.parse().ok()
+    }
+
+    /// Sets a value at the dot-separated `path`, creating missing parent objects.
+    /// Valhalla stores all values as strings and converts them on read, so any [`Display`] value is accepted.
+    /// A value replacing a number, boolean or `null` keeps that JSON type in [`Config::to_json()`] if it's still
+    /// valid as one, other values are written as strings.
+    /// ```rust
+    /// let mut config = valhalla::Config::from_file("path/to/config.json").unwrap();
+    /// config.set("service_limits.auto.max_distance", 10_000_000.0);
+    /// ```
+    pub fn set(&mut self, path: &str, value: impl Display) {
+        ffi::put(
+            self.inner.pin_mut(),
+            self.types.pin_mut(),
+            path,
+            &value.to_string(),
         );
-        Self::from_json(&config)
+    }
+
+    /// Removes a value or a whole section at the dot-separated `path`. Returns `false` if there was nothing to remove.
+    pub fn remove(&mut self, path: &str) -> bool {
+        ffi::remove(self.inner.pin_mut(), self.types.pin_mut(), path)
+    }
+
+    /// Recursively merges `other` into this configuration. Values and arrays from `other` replace existing ones,
+    /// including empty strings and arrays, objects are merged key by key. Empty objects leave the existing values
+    /// unchanged.
+    pub fn merge(&mut self, other: &Config) {
+        self.merge_values(other);
+    }
+
+    /// Same as [`Config::merge()`], but returns dot-separated paths of the values that `other` set.
+    /// Arrays are reported as single values.
+    pub(crate) fn merge_values(&mut self, other: &Config) -> Vec<String> {
+        ffi::merge(
+            self.inner.pin_mut(),
+            self.types.pin_mut(),
+            other.inner(),
+            &other.types,
+        )
+    }
+
+    /// Serializes configuration back to JSON. Values that were stored as numbers, booleans or `null` are written
+    /// as such, everything else as strings, even if it looks like a number.
+    pub fn to_json(&self) -> String {
+        ffi::to_json(self.inner(), &self.types)
+    }
+
+    /// Direct children of the node at the dot-separated `path`, or of the root if `path` is empty.
+    /// Returns an empty list if there is no such node.
+    /// ```rust
+    /// let config = valhalla::Config::from_file("path/to/config.json").unwrap();
+    /// for entry in config.children("service_limits") {
+    ///     println!("{}: {}", entry.key, entry.has_children);
+    /// }
+    /// ```
+    pub fn children(&self, path: &str) -> Vec<ConfigEntry> {
+        ffi::children(self.inner(), path)
     }
 
     /// Reference to the inner Valhalla configuration object.
     pub(crate) fn inner(&self) -> &ffi::ptree {
-        self.0.as_ref().unwrap()
+        self.inner.as_ref().unwrap()
     }
 }
+
+// Safety: `ptree` is a plain tree of strings, it's mutated only via `&mut Config`.
+unsafe impl Send for ffi::ptree {}
+unsafe impl Sync for ffi::ptree {}
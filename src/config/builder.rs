@@ -0,0 +1,821 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use crate::{Config, Error, json::Json};
+
+/// Typed builder for Valhalla [`Config`] with defaults equivalent to the output of `valhalla_build_config`.
+///
+/// Only the sections used at runtime are covered: `mjolnir`, `additional_data`, `loki`, `thor`, `meili`, `odin`
+/// and `service_limits`. Settings for building tiles, logging and the HTTP service are omitted.
+/// ```rust
+/// use valhalla::ConfigBuilder;
+///
+/// let config = ConfigBuilder::new()
+///     .tile_extract("path/to/tiles.tar")
+///     .traffic_extract("path/to/traffic.tar")
+///     .mjolnir(|mjolnir| mjolnir.max_cache_size = 256 * 1024 * 1024)
+///     .service_limits(|limits| limits.auto.max_distance = 10_000_000.0)
+///     .build();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ConfigBuilder {
+    mjolnir: MjolnirConfig,
+    additional_data: AdditionalDataConfig,
+    loki: LokiConfig,
+    thor: ThorConfig,
+    meili: MeiliConfig,
+    odin: OdinConfig,
+    service_limits: ServiceLimits,
+}
+
+impl ConfigBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Path to the tar extract with all graph tiles. Takes precedence over [`ConfigBuilder::tile_dir()`].
+    pub fn tile_extract(mut self, path: impl Into<PathBuf>) -> Self {
+        self.mjolnir.tile_extract = Some(path.into());
+        self
+    }
+
+    /// Path to the directory with graph tiles stored as individual files.
+    pub fn tile_dir(mut self, path: impl Into<PathBuf>) -> Self {
+        self.mjolnir.tile_dir = Some(path.into());
+        self
+    }
+
+    /// Path to the memory-mapped tar extract with live traffic data.
+    pub fn traffic_extract(mut self, path: impl Into<PathBuf>) -> Self {
+        self.mjolnir.traffic_extract = Some(path.into());
+        self
+    }
+
+    /// Path to the directory with SRTM `.hgt` elevation tiles, used by [`crate::Actor::height()`].
+    pub fn elevation(mut self, path: impl Into<PathBuf>) -> Self {
+        self.additional_data.elevation = Some(path.into());
+        self
+    }
+
+    pub fn mjolnir(mut self, f: impl FnOnce(&mut MjolnirConfig)) -> Self {
+        f(&mut self.mjolnir);
+        self
+    }
+
+    pub fn loki(mut self, f: impl FnOnce(&mut LokiConfig)) -> Self {
+        f(&mut self.loki);
+        self
+    }
+
+    pub fn thor(mut self, f: impl FnOnce(&mut ThorConfig)) -> Self {
+        f(&mut self.thor);
+        self
+    }
+
+    pub fn meili(mut self, f: impl FnOnce(&mut MeiliConfig)) -> Self {
+        f(&mut self.meili);
+        self
+    }
+
+    pub fn odin(mut self, f: impl FnOnce(&mut OdinConfig)) -> Self {
+        f(&mut self.odin);
+        self
+    }
+
+    pub fn service_limits(mut self, f: impl FnOnce(&mut ServiceLimits)) -> Self {
+        f(&mut self.service_limits);
+        self
+    }
+
+    /// Valhalla configuration JSON document, as it would be written to `config.json`.
+    pub fn to_json(&self) -> String {
+        Json::object()
+            .with("mjolnir", &self.mjolnir)
+            .with("additional_data", &self.additional_data)
+            .with("loki", &self.loki)
+            .with("thor", &self.thor)
+            .with("meili", &self.meili)
+            .with("odin", &self.odin)
+            .with("service_limits", &self.service_limits)
+            .to_string()
+    }
+
+    pub fn build(&self) -> Result<Config, Error> {
+        Config::from_json(&self.to_json())
+    }
+}
+
+/// `mjolnir` section: where to read graph tiles from and how to cache them.
+#[derive(Clone, Debug)]
+pub struct MjolnirConfig {
+    pub tile_extract: Option<PathBuf>,
+    pub tile_dir: Option<PathBuf>,
+    pub traffic_extract: Option<PathBuf>,
+    /// Maximum size of the tile cache in bytes.
+    pub max_cache_size: u64,
+    pub use_lru_mem_cache: bool,
+    pub lru_mem_cache_hard_control: bool,
+    pub use_simple_mem_cache: bool,
+    pub global_synchronized_cache: bool,
+    pub max_concurrent_reader_users: u32,
+}
+
+impl Default for MjolnirConfig {
+    fn default() -> Self {
+        Self {
+            tile_extract: None,
+            tile_dir: None,
+            traffic_extract: None,
+            max_cache_size: 1_000_000_000,
+            use_lru_mem_cache: false,
+            lru_mem_cache_hard_control: false,
+            use_simple_mem_cache: false,
+            global_synchronized_cache: false,
+            max_concurrent_reader_users: 1,
+        }
+    }
+}
+
+impl From<&MjolnirConfig> for Json {
+    fn from(mjolnir: &MjolnirConfig) -> Self {
+        Json::object()
+            .with_opt("tile_extract", mjolnir.tile_extract.as_deref())
+            .with_opt("tile_dir", mjolnir.tile_dir.as_deref())
+            .with_opt("traffic_extract", mjolnir.traffic_extract.as_deref())
+            .with("max_cache_size", mjolnir.max_cache_size)
+            .with("use_lru_mem_cache", mjolnir.use_lru_mem_cache)
+            .with(
+                "lru_mem_cache_hard_control",
+                mjolnir.lru_mem_cache_hard_control,
+            )
+            .with("use_simple_mem_cache", mjolnir.use_simple_mem_cache)
+            .with(
+                "global_synchronized_cache",
+                mjolnir.global_synchronized_cache,
+            )
+            .with(
+                "max_concurrent_reader_users",
+                mjolnir.max_concurrent_reader_users,
+            )
+    }
+}
+
+/// `additional_data` section.
+#[derive(Clone, Debug, Default)]
+struct AdditionalDataConfig {
+    elevation: Option<PathBuf>,
+}
+
+impl From<&AdditionalDataConfig> for Json {
+    fn from(additional_data: &AdditionalDataConfig) -> Self {
+        Json::object().with_opt("elevation", additional_data.elevation.as_deref())
+    }
+}
+
+/// `loki` section: enabled actions and correlation of input locations to the graph.
+#[derive(Clone, Debug)]
+pub struct LokiConfig {
+    pub actions: Vec<String>,
+    pub use_connectivity: bool,
+    pub service_defaults: LokiServiceDefaults,
+}
+
+impl Default for LokiConfig {
+    fn default() -> Self {
+        Self {
+            actions: [
+                "locate",
+                "route",
+                "height",
+                "sources_to_targets",
+                "optimized_route",
+                "isochrone",
+                "trace_route",
+                "trace_attributes",
+                "transit_available",
+                "expansion",
+                "centroid",
+                "status",
+            ]
+            .map(String::from)
+            .to_vec(),
+            use_connectivity: true,
+            service_defaults: LokiServiceDefaults::default(),
+        }
+    }
+}
+
+impl From<&LokiConfig> for Json {
+    fn from(loki: &LokiConfig) -> Self {
+        let defaults = &loki.service_defaults;
+        Json::object()
+            .with("actions", loki.actions.clone())
+            .with("use_connectivity", loki.use_connectivity)
+            .with(
+                "service_defaults",
+                Json::object()
+                    .with("minimum_reachability", defaults.minimum_reachability)
+                    .with("radius", defaults.radius)
+                    .with("search_cutoff", defaults.search_cutoff)
+                    .with("node_snap_tolerance", defaults.node_snap_tolerance)
+                    .with("street_side_tolerance", defaults.street_side_tolerance)
+                    .with(
+                        "street_side_max_distance",
+                        defaults.street_side_max_distance,
+                    )
+                    .with("heading_tolerance", defaults.heading_tolerance),
+            )
+    }
+}
+
+/// Default location search parameters used when a request doesn't specify them.
+#[derive(Clone, Debug)]
+pub struct LokiServiceDefaults {
+    pub minimum_reachability: u32,
+    /// Search radius in meters.
+    pub radius: u32,
+    /// Maximum search distance in meters.
+    pub search_cutoff: u32,
+    /// Distance in meters to snap to the edge end node.
+    pub node_snap_tolerance: u32,
+    pub street_side_tolerance: u32,
+    pub street_side_max_distance: u32,
+    /// Heading tolerance in degrees.
+    pub heading_tolerance: u32,
+}
+
+impl Default for LokiServiceDefaults {
+    fn default() -> Self {
+        Self {
+            minimum_reachability: 50,
+            radius: 0,
+            search_cutoff: 35000,
+            node_snap_tolerance: 5,
+            street_side_tolerance: 5,
+            street_side_max_distance: 1000,
+            heading_tolerance: 60,
+        }
+    }
+}
+
+/// Hierarchy limits of a path algorithm, keyed by the hierarchy level.
+#[derive(Clone, Debug, Default)]
+pub struct HierarchyLimits {
+    /// Maximum number of transitions up to this level.
+    pub max_up_transitions: BTreeMap<u8, u32>,
+    /// Distance in meters from origin and destination within which this level is expanded.
+    pub expand_within_distance: BTreeMap<u8, f64>,
+}
+
+impl HierarchyLimits {
+    fn new(max_up_transitions: &[(u8, u32)], expand_within_distance: &[(u8, f64)]) -> Self {
+        Self {
+            max_up_transitions: max_up_transitions.iter().copied().collect(),
+            expand_within_distance: expand_within_distance.iter().copied().collect(),
+        }
+    }
+
+    fn to_json(&self, max_up_transitions: &str, expand_within_distance: &str) -> Json {
+        let mut json = Json::object().with(max_up_transitions, level_map(&self.max_up_transitions));
+        if !self.expand_within_distance.is_empty() {
+            json = json.with(
+                expand_within_distance,
+                level_map(&self.expand_within_distance),
+            );
+        }
+        json
+    }
+}
+
+fn level_map<T: Copy + Into<Json>>(map: &BTreeMap<u8, T>) -> Json {
+    map.iter().fold(Json::object(), |json, (level, &value)| {
+        json.with(&level.to_string(), value)
+    })
+}
+
+/// `thor` section: path algorithms.
+#[derive(Clone, Debug)]
+pub struct ThorConfig {
+    /// Matrix algorithm, one of `select_optimal`, `costmatrix` or `timedistancematrix`.
+    pub source_to_target_algorithm: String,
+    pub max_reserved_labels_count_astar: u32,
+    pub max_reserved_labels_count_bidir_astar: u32,
+    pub max_reserved_labels_count_dijkstras: u32,
+    pub max_reserved_labels_count_bidir_dijkstras: u32,
+    pub clear_reserved_memory: bool,
+    pub extended_search: bool,
+    pub costmatrix_allow_second_pass: bool,
+    pub costmatrix_check_reverse_connection: bool,
+    pub costmatrix_max_reserved_locations: u32,
+    pub costmatrix_max_iterations: u32,
+    pub costmatrix_hierarchy_limits: HierarchyLimits,
+    pub bidirectional_astar_hierarchy_limits: HierarchyLimits,
+    pub unidirectional_astar_hierarchy_limits: HierarchyLimits,
+}
+
+impl Default for ThorConfig {
+    fn default() -> Self {
+        Self {
+            source_to_target_algorithm: "select_optimal".into(),
+            max_reserved_labels_count_astar: 2_000_000,
+            max_reserved_labels_count_bidir_astar: 1_000_000,
+            max_reserved_labels_count_dijkstras: 4_000_000,
+            max_reserved_labels_count_bidir_dijkstras: 2_000_000,
+            clear_reserved_memory: false,
+            extended_search: false,
+            costmatrix_allow_second_pass: false,
+            costmatrix_check_reverse_connection: false,
+            costmatrix_max_reserved_locations: 25,
+            costmatrix_max_iterations: 2800,
+            costmatrix_hierarchy_limits: HierarchyLimits::new(&[(1, 400), (2, 100)], &[]),
+            bidirectional_astar_hierarchy_limits: HierarchyLimits::new(
+                &[(1, 400), (2, 100)],
+                &[(0, 1e8), (1, 20000.0), (2, 5000.0)],
+            ),
+            unidirectional_astar_hierarchy_limits: HierarchyLimits::new(
+                &[(1, 400), (2, 100)],
+                &[(0, 1e8), (1, 100000.0), (2, 5000.0)],
+            ),
+        }
+    }
+}
+
+impl From<&ThorConfig> for Json {
+    fn from(thor: &ThorConfig) -> Self {
+        let limits = |limits: &HierarchyLimits| {
+            Json::object().with(
+                "hierarchy_limits",
+                limits.to_json("max_up_transitions", "expand_within_distance"),
+            )
+        };
+        Json::object()
+            .with(
+                "source_to_target_algorithm",
+                thor.source_to_target_algorithm.as_str(),
+            )
+            .with(
+                "max_reserved_labels_count_astar",
+                thor.max_reserved_labels_count_astar,
+            )
+            .with(
+                "max_reserved_labels_count_bidir_astar",
+                thor.max_reserved_labels_count_bidir_astar,
+            )
+            .with(
+                "max_reserved_labels_count_dijkstras",
+                thor.max_reserved_labels_count_dijkstras,
+            )
+            .with(
+                "max_reserved_labels_count_bidir_dijkstras",
+                thor.max_reserved_labels_count_bidir_dijkstras,
+            )
+            .with("clear_reserved_memory", thor.clear_reserved_memory)
+            .with("extended_search", thor.extended_search)
+            .with(
+                "costmatrix",
+                limits(&thor.costmatrix_hierarchy_limits)
+                    .with("allow_second_pass", thor.costmatrix_allow_second_pass)
+                    .with(
+                        "check_reverse_connection",
+                        thor.costmatrix_check_reverse_connection,
+                    )
+                    .with(
+                        "max_reserved_locations",
+                        thor.costmatrix_max_reserved_locations,
+                    )
+                    .with("max_iterations", thor.costmatrix_max_iterations),
+            )
+            .with(
+                "bidirectional_astar",
+                limits(&thor.bidirectional_astar_hierarchy_limits),
+            )
+            .with(
+                "unidirectional_astar",
+                limits(&thor.unidirectional_astar_hierarchy_limits),
+            )
+    }
+}
+
+/// `meili` section: map-matching parameters.
+#[derive(Clone, Debug)]
+pub struct MeiliConfig {
+    /// Default travel mode.
+    pub mode: String,
+    /// Parameters that can be overridden per request.
+    pub customizable: Vec<String>,
+    pub verbose: bool,
+    pub default: MeiliParameters,
+    /// Per-mode overrides of [`MeiliConfig::default`] parameters, keyed by mode, e.g. `auto` or `pedestrian`.
+    pub modes: BTreeMap<String, MeiliModeParameters>,
+    /// Size of the candidate search grid cell in meters.
+    pub grid_size: u32,
+    pub grid_cache_size: u32,
+}
+
+impl Default for MeiliConfig {
+    fn default() -> Self {
+        let mode = |search_radius, turn_penalty_factor| MeiliModeParameters {
+            search_radius,
+            turn_penalty_factor: Some(turn_penalty_factor),
+        };
+        Self {
+            mode: "auto".into(),
+            customizable: [
+                "mode",
+                "search_radius",
+                "turn_penalty_factor",
+                "gps_accuracy",
+                "interpolation_distance",
+                "sigma_z",
+                "beta",
+                "max_route_distance_factor",
+                "max_route_time_factor",
+            ]
+            .map(String::from)
+            .to_vec(),
+            verbose: false,
+            default: MeiliParameters::default(),
+            modes: [
+                ("auto".to_string(), mode(Some(50.0), 200.0)),
+                ("pedestrian".to_string(), mode(Some(50.0), 100.0)),
+                ("bicycle".to_string(), mode(None, 140.0)),
+                ("multimodal".to_string(), mode(None, 70.0)),
+            ]
+            .into(),
+            grid_size: 500,
+            grid_cache_size: 100240,
+        }
+    }
+}
+
+impl From<&MeiliConfig> for Json {
+    fn from(meili: &MeiliConfig) -> Self {
+        let default = &meili.default;
+        let mut json = Json::object()
+            .with("mode", meili.mode.as_str())
+            .with("customizable", meili.customizable.clone())
+            .with("verbose", meili.verbose)
+            .with(
+                "default",
+                Json::object()
+                    .with("sigma_z", default.sigma_z)
+                    .with("gps_accuracy", default.gps_accuracy)
+                    .with("beta", default.beta)
+                    .with(
+                        "max_route_distance_factor",
+                        default.max_route_distance_factor,
+                    )
+                    .with("max_route_time_factor", default.max_route_time_factor)
+                    .with("max_search_radius", default.max_search_radius)
+                    .with("breakage_distance", default.breakage_distance)
+                    .with("interpolation_distance", default.interpolation_distance)
+                    .with("search_radius", default.search_radius)
+                    .with("geometry", default.geometry)
+                    .with("route", default.route)
+                    .with("turn_penalty_factor", default.turn_penalty_factor),
+            );
+        for (mode, parameters) in &meili.modes {
+            json = json.with(
+                mode,
+                Json::object()
+                    .with_opt("search_radius", parameters.search_radius)
+                    .with_opt("turn_penalty_factor", parameters.turn_penalty_factor),
+            );
+        }
+        json.with(
+            "grid",
+            Json::object()
+                .with("size", meili.grid_size)
+                .with("cache_size", meili.grid_cache_size),
+        )
+    }
+}
+
+/// Default map-matching parameters for all modes.
+#[derive(Clone, Debug)]
+pub struct MeiliParameters {
+    pub sigma_z: f64,
+    /// GPS accuracy in meters.
+    pub gps_accuracy: f64,
+    pub beta: f64,
+    pub max_route_distance_factor: f64,
+    pub max_route_time_factor: f64,
+    pub max_search_radius: f64,
+    /// Distance in meters between two points after which the trace is split.
+    pub breakage_distance: f64,
+    /// Points closer than this distance in meters are interpolated instead of being matched.
+    pub interpolation_distance: f64,
+    /// Candidate search radius in meters.
+    pub search_radius: f64,
+    pub geometry: bool,
+    pub route: bool,
+    pub turn_penalty_factor: f64,
+}
+
+impl Default for MeiliParameters {
+    fn default() -> Self {
+        Self {
+            sigma_z: 4.07,
+            gps_accuracy: 5.0,
+            beta: 3.0,
+            max_route_distance_factor: 5.0,
+            max_route_time_factor: 5.0,
+            max_search_radius: 100.0,
+            breakage_distance: 2000.0,
+            interpolation_distance: 10.0,
+            search_radius: 50.0,
+            geometry: false,
+            route: true,
+            turn_penalty_factor: 0.0,
+        }
+    }
+}
+
+/// Map-matching parameters overridden for a single mode.
+#[derive(Clone, Debug, Default)]
+pub struct MeiliModeParameters {
+    pub search_radius: Option<f64>,
+    pub turn_penalty_factor: Option<f64>,
+}
+
+/// `odin` section: narrative generation.
+#[derive(Clone, Debug)]
+pub struct OdinConfig {
+    pub markup_enabled: bool,
+    pub phoneme_format: String,
+}
+
+impl Default for OdinConfig {
+    fn default() -> Self {
+        Self {
+            markup_enabled: false,
+            phoneme_format:
+                "<TEXTUAL_STRING> (<span class=<QUOTES>phoneme<QUOTES>>/<VERBAL_STRING>/</span>)"
+                    .into(),
+        }
+    }
+}
+
+impl From<&OdinConfig> for Json {
+    fn from(odin: &OdinConfig) -> Self {
+        Json::object().with(
+            "markup_formatter",
+            Json::object()
+                .with("markup_enabled", odin.markup_enabled)
+                .with("phoneme_format", odin.phoneme_format.as_str()),
+        )
+    }
+}
+
+/// Request limits for a single costing.
+#[derive(Clone, Debug)]
+pub struct CostingLimits {
+    /// Maximum distance in meters between locations.
+    pub max_distance: f64,
+    pub max_locations: u32,
+    /// Maximum distance in meters between sources and targets of a matrix.
+    pub max_matrix_distance: f64,
+    pub max_matrix_location_pairs: u32,
+}
+
+impl CostingLimits {
+    const fn new(
+        max_distance: f64,
+        max_locations: u32,
+        max_matrix_distance: f64,
+        max_matrix_location_pairs: u32,
+    ) -> Self {
+        Self {
+            max_distance,
+            max_locations,
+            max_matrix_distance,
+            max_matrix_location_pairs,
+        }
+    }
+}
+
+impl From<&CostingLimits> for Json {
+    fn from(limits: &CostingLimits) -> Self {
+        Json::object()
+            .with("max_distance", limits.max_distance)
+            .with("max_locations", limits.max_locations)
+            .with("max_matrix_distance", limits.max_matrix_distance)
+            .with(
+                "max_matrix_location_pairs",
+                limits.max_matrix_location_pairs,
+            )
+    }
+}
+
+/// `service_limits` section: per-costing and per-action request limits.
+#[derive(Clone, Debug)]
+pub struct ServiceLimits {
+    pub auto: CostingLimits,
+    pub bicycle: CostingLimits,
+    pub bikeshare: CostingLimits,
+    pub bus: CostingLimits,
+    pub motor_scooter: CostingLimits,
+    pub motorcycle: CostingLimits,
+    pub multimodal: CostingLimits,
+    pub pedestrian: CostingLimits,
+    /// Maximum walking distance in meters at the beginning or end of a transit route.
+    pub max_transit_walking_distance: u32,
+    /// Minimum walking distance in meters at the beginning or end of a transit route.
+    pub min_transit_walking_distance: u32,
+    pub taxi: CostingLimits,
+    pub transit: CostingLimits,
+    pub truck: CostingLimits,
+    pub isochrone_max_contours: u32,
+    /// Maximum isochrone contour time in minutes.
+    pub isochrone_max_time_contour: u32,
+    pub isochrone_max_distance: f64,
+    pub isochrone_max_locations: u32,
+    /// Maximum isochrone contour distance in kilometers.
+    pub isochrone_max_distance_contour: u32,
+    pub trace_max_distance: f64,
+    pub trace_max_gps_accuracy: f64,
+    pub trace_max_search_radius: f64,
+    pub trace_max_shape: u32,
+    pub trace_max_alternates: u32,
+    pub trace_max_alternates_shape: u32,
+    pub skadi_max_shape: u32,
+    pub skadi_min_resample: f64,
+    pub centroid_max_locations: u32,
+    pub centroid_max_distance: f64,
+    pub status_allow_verbose: bool,
+    pub max_exclude_locations: u32,
+    pub max_reachability: u32,
+    pub max_radius: u32,
+    pub max_timedep_distance: u32,
+    pub max_timedep_distance_matrix: u32,
+    pub max_alternates: u32,
+    pub max_exclude_polygons_length: u32,
+    pub max_distance_disable_hierarchy_culling: u32,
+    pub allow_hard_exclusions: bool,
+    /// Whether requests may override the hierarchy limits below.
+    pub hierarchy_limits_allow_modification: bool,
+    pub costmatrix_hierarchy_limits: HierarchyLimits,
+    pub bidirectional_astar_hierarchy_limits: HierarchyLimits,
+    pub unidirectional_astar_hierarchy_limits: HierarchyLimits,
+}
+
+impl Default for ServiceLimits {
+    fn default() -> Self {
+        Self {
+            auto: CostingLimits::new(5_000_000.0, 20, 400_000.0, 2500),
+            bicycle: CostingLimits::new(500_000.0, 50, 200_000.0, 2500),
+            bikeshare: CostingLimits::new(500_000.0, 50, 200_000.0, 2500),
+            bus: CostingLimits::new(5_000_000.0, 50, 400_000.0, 2500),
+            motor_scooter: CostingLimits::new(500_000.0, 50, 200_000.0, 2500),
+            motorcycle: CostingLimits::new(500_000.0, 50, 200_000.0, 2500),
+            multimodal: CostingLimits::new(500_000.0, 50, 0.0, 0),
+            pedestrian: CostingLimits::new(250_000.0, 50, 200_000.0, 2500),
+            max_transit_walking_distance: 10000,
+            min_transit_walking_distance: 1,
+            taxi: CostingLimits::new(5_000_000.0, 20, 400_000.0, 2500),
+            transit: CostingLimits::new(500_000.0, 50, 200_000.0, 2500),
+            truck: CostingLimits::new(5_000_000.0, 20, 400_000.0, 2500),
+            isochrone_max_contours: 4,
+            isochrone_max_time_contour: 120,
+            isochrone_max_distance: 25000.0,
+            isochrone_max_locations: 1,
+            isochrone_max_distance_contour: 200,
+            trace_max_distance: 200_000.0,
+            trace_max_gps_accuracy: 100.0,
+            trace_max_search_radius: 100.0,
+            trace_max_shape: 16000,
+            trace_max_alternates: 3,
+            trace_max_alternates_shape: 100,
+            skadi_max_shape: 750_000,
+            skadi_min_resample: 10.0,
+            centroid_max_locations: 5,
+            centroid_max_distance: 200_000.0,
+            status_allow_verbose: false,
+            max_exclude_locations: 50,
+            max_reachability: 100,
+            max_radius: 200,
+            max_timedep_distance: 500_000,
+            max_timedep_distance_matrix: 0,
+            max_alternates: 2,
+            max_exclude_polygons_length: 10000,
+            max_distance_disable_hierarchy_culling: 0,
+            allow_hard_exclusions: false,
+            hierarchy_limits_allow_modification: false,
+            costmatrix_hierarchy_limits: HierarchyLimits::new(&[(1, 400), (2, 100)], &[]),
+            bidirectional_astar_hierarchy_limits: HierarchyLimits::new(
+                &[(1, 400), (2, 100)],
+                &[(0, 1e8), (1, 20000.0), (2, 5000.0)],
+            ),
+            unidirectional_astar_hierarchy_limits: HierarchyLimits::new(
+                &[(1, 400), (2, 100)],
+                &[(0, 1e8), (1, 100000.0), (2, 5000.0)],
+            ),
+        }
+    }
+}
+
+impl From<&ServiceLimits> for Json {
+    fn from(limits: &ServiceLimits) -> Self {
+        let hierarchy_limits = |limits: &HierarchyLimits| {
+            limits.to_json("max_allowed_up_transitions", "max_expand_within_distance")
+        };
+        Json::object()
+            .with("auto", &limits.auto)
+            .with("bicycle", &limits.bicycle)
+            .with("bikeshare", &limits.bikeshare)
+            .with("bus", &limits.bus)
+            .with("motor_scooter", &limits.motor_scooter)
+            .with("motorcycle", &limits.motorcycle)
+            .with("multimodal", &limits.multimodal)
+            .with(
+                "pedestrian",
+                Json::from(&limits.pedestrian)
+                    .with(
+                        "max_transit_walking_distance",
+                        limits.max_transit_walking_distance,
+                    )
+                    .with(
+                        "min_transit_walking_distance",
+                        limits.min_transit_walking_distance,
+                    ),
+            )
+            .with("taxi", &limits.taxi)
+            .with("transit", &limits.transit)
+            .with("truck", &limits.truck)
+            .with(
+                "isochrone",
+                Json::object()
+                    .with("max_contours", limits.isochrone_max_contours)
+                    .with("max_time_contour", limits.isochrone_max_time_contour)
+                    .with("max_distance", limits.isochrone_max_distance)
+                    .with("max_locations", limits.isochrone_max_locations)
+                    .with(
+                        "max_distance_contour",
+                        limits.isochrone_max_distance_contour,
+                    ),
+            )
+            .with(
+                "trace",
+                Json::object()
+                    .with("max_distance", limits.trace_max_distance)
+                    .with("max_gps_accuracy", limits.trace_max_gps_accuracy)
+                    .with("max_search_radius", limits.trace_max_search_radius)
+                    .with("max_shape", limits.trace_max_shape)
+                    .with("max_alternates", limits.trace_max_alternates)
+                    .with("max_alternates_shape", limits.trace_max_alternates_shape),
+            )
+            .with(
+                "skadi",
+                Json::object()
+                    .with("max_shape", limits.skadi_max_shape)
+                    .with("min_resample", limits.skadi_min_resample),
+            )
+            .with(
+                "centroid",
+                Json::object()
+                    .with("max_locations", limits.centroid_max_locations)
+                    .with("max_distance", limits.centroid_max_distance),
+            )
+            .with(
+                "status",
+                Json::object().with("allow_verbose", limits.status_allow_verbose),
+            )
+            .with("max_exclude_locations", limits.max_exclude_locations)
+            .with("max_reachability", limits.max_reachability)
+            .with("max_radius", limits.max_radius)
+            .with("max_timedep_distance", limits.max_timedep_distance)
+            .with(
+                "max_timedep_distance_matrix",
+                limits.max_timedep_distance_matrix,
+            )
+            .with("max_alternates", limits.max_alternates)
+            .with(
+                "max_exclude_polygons_length",
+                limits.max_exclude_polygons_length,
+            )
+            .with(
+                "max_distance_disable_hierarchy_culling",
+                limits.max_distance_disable_hierarchy_culling,
+            )
+            .with("allow_hard_exclusions", limits.allow_hard_exclusions)
+            .with(
+                "hierarchy_limits",
+                Json::object()
+                    .with(
+                        "allow_modification",
+                        limits.hierarchy_limits_allow_modification,
+                    )
+                    .with(
+                        "costmatrix",
+                        hierarchy_limits(&limits.costmatrix_hierarchy_limits),
+                    )
+                    .with(
+                        "bidirectional_astar",
+                        hierarchy_limits(&limits.bidirectional_astar_hierarchy_limits),
+                    )
+                    .with(
+                        "unidirectional_astar",
+                        hierarchy_limits(&limits.unidirectional_astar_hierarchy_limits),
+                    ),
+            )
+    }
+}
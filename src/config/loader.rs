@@ -0,0 +1,214 @@
+use std::{collections::BTreeMap, ffi::OsString, fmt, path::PathBuf};
+
+use crate::{Config, Error};
+
+/// Loads [`Config`] from several layers, each one overriding values of the previous ones:
+/// 1. base configuration file,
+/// 2. overlay files in the order they were added, merged with [`Config::merge()`],
+/// 3. environment variables like `VALHALLA__MJOLNIR__TILE_EXTRACT`, where `__` separates path segments and
+///    names are lowercased, so the example sets `mjolnir.tile_extract`. Prefixed variables whose name or value
+///    isn't valid UTF-8 are skipped and listed in [`ConfigReport::skipped_env()`].
+///
+/// ```rust
+/// use valhalla::ConfigLoader;
+///
+/// let (config, report) = ConfigLoader::new("path/to/config.json")
+///     .overlay("path/to/production.json")
+///     .load()
+///     .unwrap();
+/// println!("{report}");
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConfigLoader {
+    base: PathBuf,
+    overlays: Vec<PathBuf>,
+    env_prefix: String,
+    /// Variables to use instead of the process environment.
+    env_vars: Option<Vec<(OsString, OsString)>>,
+}
+
+impl ConfigLoader {
+    /// Default prefix of environment variables with configuration overrides.
+    pub const DEFAULT_ENV_PREFIX: &str = "VALHALLA__";
+
+    pub fn new(base: impl Into<PathBuf>) -> Self {
+        Self {
+            base: base.into(),
+            overlays: Vec::new(),
+            env_prefix: Self::DEFAULT_ENV_PREFIX.to_string(),
+            env_vars: None,
+        }
+    }
+
+    /// Adds a configuration file that is merged on top of the base file and previously added overlays.
+    pub fn overlay(mut self, path: impl Into<PathBuf>) -> Self {
+        self.overlays.push(path.into());
+        self
+    }
+
+    /// Sets the prefix of environment variables with overrides, [`ConfigLoader::DEFAULT_ENV_PREFIX`] by default.
+    pub fn env_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.env_prefix = prefix.into();
+        self
+    }
+
+    /// Uses the given variables instead of the process environment, e.g. to make loading deterministic in tests.
+    pub fn env_vars<K: Into<OsString>, V: Into<OsString>>(
+        mut self,
+        vars: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        self.env_vars = Some(
+            vars.into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        );
+        self
+    }
+
+    /// Loads all layers into a single [`Config`] and reports which layer set each value.
+    pub fn load(&self) -> Result<(Config, ConfigReport), Error> {
+        let mut report = ConfigReport::default();
+
+        let mut config = Config::from_file(&self.base)?;
+        report.record_all(&config, &ConfigSource::Base(self.base.clone()));
+
+        for path in &self.overlays {
+            let overlay = Config::from_file(path)?;
+            // Only values that were actually merged, as empty objects leave everything in place
+            for value in config.merge_values(&overlay) {
+                report.record(value, ConfigSource::Overlay(path.clone()));
+            }
+        }
+
+        let vars: Vec<_> = match &self.env_vars {
+            Some(vars) => vars.clone(),
+            None => std::env::vars_os().collect(),
+        };
+        let mut overrides = Vec::new();
+        for (name, value) in vars {
+            if !name.to_string_lossy().starts_with(&self.env_prefix) {
+                continue;
+            }
+            match (name.into_string(), value.into_string()) {
+                (Ok(name), Ok(value)) => overrides.push((name, value)),
+                (Ok(name), Err(_)) => report.skipped_env.push(name),
+                (Err(name), _) => report.skipped_env.push(name.to_string_lossy().into_owned()),
+            }
+        }
+        report.skipped_env.sort();
+        // Process environment has no particular order, sort to apply overrides deterministically
+        overrides.sort();
+        for (name, value) in overrides {
+            let Some(path) = self.env_path(&name) else {
+                continue;
+            };
+            config.set(&path, &value);
+            report.record(path, ConfigSource::Env(name));
+        }
+
+        Ok((config, report))
+    }
+
+    /// Converts `VALHALLA__MJOLNIR__TILE_EXTRACT` into `mjolnir.tile_extract`.
+    fn env_path(&self, name: &str) -> Option<String> {
+        let segments: Vec<_> = name.strip_prefix(&self.env_prefix)?.split("__").collect();
+        if segments.iter().any(|segment| segment.is_empty()) {
+            return None;
+        }
+        Some(segments.join(".").to_lowercase())
+    }
+}
+
+/// Layer that set a configuration value, see [`ConfigLoader`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConfigSource {
+    Base(PathBuf),
+    Overlay(PathBuf),
+    /// Name of the environment variable.
+    Env(String),
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigSource::Base(path) => write!(f, "base {}", path.display()),
+            ConfigSource::Overlay(path) => write!(f, "overlay {}", path.display()),
+            ConfigSource::Env(name) => write!(f, "env {name}"),
+        }
+    }
+}
+
+/// Sources of all configuration values loaded by [`ConfigLoader`], keyed by dot-separated path.
+/// Arrays are reported as a single value because overlays replace them as a whole.
+#[derive(Clone, Debug, Default)]
+pub struct ConfigReport {
+    sources: BTreeMap<String, ConfigSource>,
+    skipped_env: Vec<String>,
+}
+
+impl ConfigReport {
+    /// Layer that set the value at the dot-separated `path`.
+    pub fn source(&self, path: &str) -> Option<&ConfigSource> {
+        self.sources.get(path)
+    }
+
+    /// All values with the layers that set them, sorted by path.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ConfigSource)> {
+        self.sources
+            .iter()
+            .map(|(path, source)| (path.as_str(), source))
+    }
+
+    /// Prefixed environment variables that were ignored because their name or value isn't valid UTF-8, sorted.
+    /// Names that aren't valid UTF-8 themselves are converted lossily.
+    pub fn skipped_env(&self) -> &[String] {
+        &self.skipped_env
+    }
+
+    fn record_all(&mut self, config: &Config, source: &ConfigSource) {
+        let mut stack = vec![String::new()];
+        while let Some(parent) = stack.pop() {
+            for entry in config.children(&parent) {
+                if entry.key.is_empty() {
+                    // Array item, the whole array is a single value
+                    self.record(parent.clone(), source.clone());
+                    break;
+                }
+                let path = if parent.is_empty() {
+                    entry.key
+                } else {
+                    format!("{parent}.{}", entry.key)
+                };
+                if entry.has_children {
+                    stack.push(path);
+                } else {
+                    self.record(path, source.clone());
+                }
+            }
+        }
+    }
+
+    fn record(&mut self, path: String, source: ConfigSource) {
+        // Value replaces everything nested under it, as well as a plain value at any of its parents
+        let nested = |parent: &str, child: &str| {
+            child.len() > parent.len()
+                && child.starts_with(parent)
+                && child.as_bytes()[parent.len()] == b'.'
+        };
+        self.sources
+            .retain(|key, _| !nested(&path, key) && !nested(key, &path));
+        self.sources.insert(path, source);
+    }
+}
+
+impl fmt::Display for ConfigReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (path, source) in &self.sources {
+            writeln!(f, "{path} = {source}")?;
+        }
+        for name in &self.skipped_env {
+            writeln!(f, "skipped env {name}: not valid UTF-8")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,325 @@
+use std::{fmt, path::PathBuf, sync::OnceLock};
+
+use crate::Config;
+
+/// Output of `valhalla_build_config` with default values, plus the keys it leaves out (tile and elevation URLs,
+/// incidents, statsd, etc). Used as the reference of known keys and their types.
+const SCHEMA: &str = include_str!("schema.json");
+
+/// Costing modes that can have their own map matching parameters in `meili.<mode>`, overriding `meili.default`.
+const MEILI_MODES: [&str; 10] = [
+    "auto",
+    "bicycle",
+    "bikeshare",
+    "bus",
+    "motor_scooter",
+    "motorcycle",
+    "multimodal",
+    "pedestrian",
+    "taxi",
+    "truck",
+];
+
+/// Keys [`crate::Actor`] reads without a default value, checked only for configs with a `loki` section.
+const ACTOR_REQUIRED: [&str; 26] = [
+    "loki.actions",
+    "loki.service_defaults.minimum_reachability",
+    "loki.service_defaults.radius",
+    "loki.service_defaults.search_cutoff",
+    "loki.service_defaults.node_snap_tolerance",
+    "loki.service_defaults.street_side_tolerance",
+    "loki.service_defaults.street_side_max_distance",
+    "loki.service_defaults.heading_tolerance",
+    "meili.default",
+    "service_limits.max_exclude_locations",
+    "service_limits.max_reachability",
+    "service_limits.max_radius",
+    "service_limits.max_timedep_distance",
+    "service_limits.max_alternates",
+    "service_limits.max_exclude_polygons_length",
+    "service_limits.skadi.max_shape",
+    "service_limits.skadi.min_resample",
+    "service_limits.trace.max_distance",
+    "service_limits.trace.max_gps_accuracy",
+    "service_limits.trace.max_search_radius",
+    "service_limits.trace.max_shape",
+    "service_limits.isochrone.max_contours",
+    "service_limits.isochrone.max_time_contour",
+    "service_limits.isochrone.max_distance",
+    "service_limits.isochrone.max_locations",
+    "service_limits.isochrone.max_distance_contour",
+];
+
+/// Problem found by [`Config::validate()`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfigIssue {
+    /// Required value is not set. `alternative` is a key that can be set instead.
+    Missing {
+        path: String,
+        alternative: Option<String>,
+    },
+    /// Value can't be read as the type Valhalla expects.
+    InvalidType {
+        path: String,
+        expected: ValueKind,
+        /// Value as stored in the config, empty for objects and arrays.
+        value: String,
+    },
+    /// File or directory the value points to doesn't exist.
+    FileNotFound { path: String, file: PathBuf },
+    /// Key is not used by Valhalla, most likely a typo.
+    UnknownKey {
+        path: String,
+        /// Known key with the closest spelling, if any is close enough.
+        suggestion: Option<String>,
+    },
+}
+
+impl ConfigIssue {
+    /// Dot-separated path of the value with the issue.
+    pub fn path(&self) -> &str {
+        match self {
+            ConfigIssue::Missing { path, .. }
+            | ConfigIssue::InvalidType { path, .. }
+            | ConfigIssue::FileNotFound { path, .. }
+            | ConfigIssue::UnknownKey { path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigIssue::Missing {
+                path,
+                alternative: Some(alternative),
+            } => write!(f, "`{path}` or `{alternative}` is required"),
+            ConfigIssue::Missing { path, .. } => write!(f, "`{path}` is required"),
+            ConfigIssue::InvalidType {
+                path,
+                expected,
+                value,
+            } => write!(f, "`{path}` must be {expected}, got {value:?}"),
+            ConfigIssue::FileNotFound { path, file } => {
+                write!(f, "`{path}` points to missing {}", file.display())
+            }
+            ConfigIssue::UnknownKey {
+                path,
+                suggestion: Some(suggestion),
+            } => write!(f, "unknown key `{path}`, did you mean `{suggestion}`?"),
+            ConfigIssue::UnknownKey { path, .. } => write!(f, "unknown key `{path}`"),
+        }
+    }
+}
+
+/// Type of a configuration value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValueKind {
+    Bool,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl ValueKind {
+    /// Infers the type of a value in the reference config, where everything is stored as strings.
+    fn infer(value: &str, has_children: bool, is_array: bool) -> Self {
+        match (has_children, is_array) {
+            (true, true) => ValueKind::Array,
+            (true, false) => ValueKind::Object,
+            _ if value == "true" || value == "false" => ValueKind::Bool,
+            _ if value.parse::<f64>().is_ok() => ValueKind::Number,
+            _ => ValueKind::String,
+        }
+    }
+
+    /// Whether a config value can be read as this type.
+    fn accepts(self, value: &str, has_children: bool, is_array: bool) -> bool {
+        match self {
+            // Empty arrays and objects are stored as empty values
+            ValueKind::Array => is_array || (!has_children && value.is_empty()),
+            ValueKind::Object => (has_children && !is_array) || (!has_children && value.is_empty()),
+            _ if has_children => false,
+            // Same values as accepted by `ptree::get<bool>()`
+            ValueKind::Bool => matches!(value, "true" | "false" | "1" | "0"),
+            ValueKind::Number => value.trim().parse::<f64>().is_ok(),
+            ValueKind::String => true,
+        }
+    }
+}
+
+impl fmt::Display for ValueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ValueKind::Bool => "a boolean",
+            ValueKind::Number => "a number",
+            ValueKind::String => "a string",
+            ValueKind::Array => "an array",
+            ValueKind::Object => "an object",
+        })
+    }
+}
+
+impl Config {
+    /// Checks the configuration before it's used by [`crate::GraphReader`] or [`crate::Actor`] and returns all
+    /// found issues, or an empty list if there are none:
+    /// - either `mjolnir.tile_extract` or `mjolnir.tile_dir` is set,
+    /// - if there is a `loki` section, the values [`crate::Actor`] can't do without are set,
+    /// - known keys have values of the expected types,
+    /// - tile, traffic and elevation paths exist,
+    /// - there are no unknown keys, suggesting the closest known key for typos.
+    /// ```rust
+    /// let config = valhalla::Config::from_file("path/to/config.json").unwrap();
+    /// for issue in config.validate() {
+    ///     eprintln!("{issue}");
+    /// }
+    /// ```
+    pub fn validate(&self) -> Vec<ConfigIssue> {
+        let mut issues = Vec::new();
+        check_keys(self, schema(), "", &mut issues);
+        check_paths(self, &mut issues);
+        check_required(self, &mut issues);
+        issues
+    }
+}
+
+/// Reference config, parsed on first use. Every `meili` mode section accepts the keys of `meili.default`.
+fn schema() -> &'static Config {
+    static PARSED: OnceLock<Config> = OnceLock::new();
+    PARSED.get_or_init(|| {
+        let mut schema = Config::from_json(SCHEMA).expect("Bundled config schema is valid JSON");
+        for entry in schema.children("meili.default") {
+            for mode in MEILI_MODES {
+                let path = format!("meili.{mode}.{}", entry.key);
+                if schema.get::<String>(&path).is_none() {
+                    schema.set(&path, &entry.value);
+                }
+            }
+        }
+        schema
+    })
+}
+
+/// Recursively compares children of `path` with the same node in the schema.
+fn check_keys(config: &Config, schema: &Config, path: &str, issues: &mut Vec<ConfigIssue>) {
+    let known = schema.children(path);
+    for entry in config.children(path) {
+        if entry.key.is_empty() {
+            break; // Array items are not checked
+        }
+        let child_path = join(path, &entry.key);
+        let Some(reference) = known.iter().find(|known| known.key == entry.key) else {
+            let suggestion = known
+                .iter()
+                .map(|known| (levenshtein(&entry.key, &known.key), &known.key))
+                .filter(|&(distance, _)| distance <= 2 && distance < entry.key.len() / 2)
+                .min()
+                .map(|(_, key)| join(path, key));
+            issues.push(ConfigIssue::UnknownKey {
+                path: child_path,
+                suggestion,
+            });
+            continue;
+        };
+
+        let is_array = |path: &str, config: &Config| {
+            config
+                .children(path)
+                .first()
+                .is_some_and(|entry| entry.key.is_empty())
+        };
+        let expected = ValueKind::infer(
+            &reference.value,
+            reference.has_children,
+            is_array(&child_path, schema),
+        );
+        if !expected.accepts(
+            &entry.value,
+            entry.has_children,
+            is_array(&child_path, config),
+        ) {
+            issues.push(ConfigIssue::InvalidType {
+                path: child_path,
+                expected,
+                value: entry.value,
+            });
+        } else if expected == ValueKind::Object && entry.has_children {
+            check_keys(config, schema, &child_path, issues);
+        }
+    }
+}
+
+/// Checks that tiles are configured and all configured files exist.
+fn check_paths(config: &Config, issues: &mut Vec<ConfigIssue>) {
+    let get_path = |path: &str| {
+        config
+            .get::<String>(path)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    };
+    let missing = |path: &str, file: PathBuf| {
+        (!file.exists()).then(|| ConfigIssue::FileNotFound {
+            path: path.to_string(),
+            file,
+        })
+    };
+
+    // Tile directory is used only if there is no tile extract
+    let tiles = match (
+        get_path("mjolnir.tile_extract"),
+        get_path("mjolnir.tile_dir"),
+    ) {
+        (Some(file), _) => missing("mjolnir.tile_extract", file),
+        (None, Some(dir)) => missing("mjolnir.tile_dir", dir),
+        (None, None) => Some(ConfigIssue::Missing {
+            path: "mjolnir.tile_extract".into(),
+            alternative: Some("mjolnir.tile_dir".into()),
+        }),
+    };
+    issues.extend(tiles);
+    for path in ["mjolnir.traffic_extract", "additional_data.elevation"] {
+        issues.extend(get_path(path).and_then(|file| missing(path, file)));
+    }
+}
+
+/// Checks that an [`crate::Actor`] config has all values read without a default.
+fn check_required(config: &Config, issues: &mut Vec<ConfigIssue>) {
+    let is_set =
+        |path: &str| config.get::<String>(path).is_some() || !config.children(path).is_empty();
+    if !is_set("loki") {
+        return;
+    }
+    for path in ACTOR_REQUIRED {
+        if !is_set(path) {
+            issues.push(ConfigIssue::Missing {
+                path: path.to_string(),
+                alternative: None,
+            });
+        }
+    }
+}
+
+fn join(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{parent}.{key}")
+    }
+}
+
+/// Number of single character insertions, deletions and substitutions to turn `a` into `b`.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
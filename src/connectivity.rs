@@ -0,0 +1,508 @@
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+};
+
+use crate::{Access, GraphId, GraphLevel, GraphReader, GraphTile, LatLon, json::Json, tile_size};
+
+/// Marks nodes not yet visited by [`strongly_connected()`].
+const UNVISITED: u32 = u32::MAX;
+
+/// Finds strongly connected components of the road graph for an access mode, e.g. islands of roads that cars can't
+/// leave or enter, which make routing between them fail.
+///
+/// A directed edge connects its nodes if its `forwardaccess()` allows any of the modes, and the opposite way if its
+/// `reverseaccess()` does. Nodes are connected to their copies on other hierarchy levels, shortcuts are skipped.
+/// ```rust
+/// use valhalla::{Access, GraphConnectivity, GraphLevel};
+///
+/// let config = valhalla::Config::from_file("path/to/config.json").unwrap();
+/// let reader = valhalla::GraphReader::new(&config).unwrap();
+/// let report = GraphConnectivity::new(&reader, Access::AUTO)
+///     .max_island_edges(50)
+///     .analyze();
+/// for island in &report.islands {
+///     println!("{} edges near {:?}", island.edges.len(), island.bbox.0);
+/// }
+/// let file = std::fs::File::create("connectivity.geojson").unwrap();
+/// report.write_tiles_geojson(GraphLevel::Local, std::io::BufWriter::new(file)).unwrap();
+/// ```
+pub struct GraphConnectivity<'a> {
+    reader: &'a GraphReader,
+    access: Access,
+    tiles: Option<Vec<GraphId>>,
+    max_island_edges: usize,
+}
+
+/// Components found by [`GraphConnectivity::analyze()`].
+#[derive(Clone, Debug, Default)]
+pub struct ConnectivityReport {
+    /// Number of components with at least one edge. Component 0 is the one with the most edges.
+    pub components: usize,
+    /// Components other than the largest one, with up to the configured number of edges, largest first.
+    pub islands: Vec<Island>,
+    /// Tiles of all levels colored by connectivity, sorted by tile ID.
+    pub tiles: Vec<TileConnectivity>,
+    /// Component of every node that has edges accessible by the modes.
+    labels: HashMap<u64, usize>,
+}
+
+/// Small strongly connected component of the graph.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Island {
+    pub component: usize,
+    pub nodes: usize,
+    /// Directed edges between nodes of the island, sorted by ID.
+    pub edges: Vec<GraphId>,
+    /// Shapes of `edges`, in the same order.
+    pub shapes: Vec<Vec<LatLon>>,
+    /// Minimum and maximum coordinates of the shapes.
+    pub bbox: (LatLon, LatLon),
+}
+
+/// Tile of the connectivity map, similar to Valhalla's `ConnectivityMap`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TileConnectivity {
+    pub tile: GraphId,
+    /// Tiles of the same level have the same color if edges accessible by the modes connect them, in any direction.
+    pub color: usize,
+    /// Minimum and maximum coordinates of the tile.
+    pub bbox: (LatLon, LatLon),
+}
+
+impl ConnectivityReport {
+    /// Component of the node, `None` if the node has no edges accessible by the modes or wasn't analyzed.
+    pub fn component(&self, node: GraphId) -> Option<usize> {
+        self.labels.get(&node.value).copied()
+    }
+
+    /// Writes islands as a GeoJSON `FeatureCollection` of multi line strings with `component`, `nodes` and `edges`
+    /// properties. Returns the number of written features.
+    pub fn write_islands_geojson(&self, mut out: impl Write) -> io::Result<usize> {
+        let features = self.islands.iter().map(|island| {
+            let lines: Vec<Json> = island
+                .shapes
+                .iter()
+                .map(|shape| coordinates(shape))
+                .collect();
+            let edges: Vec<String> = island.edges.iter().map(|id| id.to_string()).collect();
+            Json::object()
+                .with("type", "Feature")
+                .with(
+                    "geometry",
+                    Json::object()
+                        .with("type", "MultiLineString")
+                        .with("coordinates", lines),
+                )
+                .with(
+                    "properties",
+                    Json::object()
+                        .with("component", island.component as u64)
+                        .with("nodes", island.nodes as u64)
+                        .with("edges", edges),
+                )
+        });
+        write_features(features, &mut out)
+    }
+
+    /// Writes tiles of the `level` as a GeoJSON `FeatureCollection` of polygons with `tile` and `color` properties.
+    /// Returns the number of written features.
+    pub fn write_tiles_geojson(&self, level: GraphLevel, mut out: impl Write) -> io::Result<usize> {
+        let features = self
+            .tiles
+            .iter()
+            .filter(|tile| tile.tile.level() == level.repr as u32)
+            .map(|tile| {
+                let (min, max) = tile.bbox;
+                let ring = [min, LatLon(min.0, max.1), max, LatLon(max.0, min.1), min];
+                Json::object()
+                    .with("type", "Feature")
+                    .with(
+                        "geometry",
+                        Json::object()
+                            .with("type", "Polygon")
+                            .with("coordinates", vec![coordinates(&ring)]),
+                    )
+                    .with(
+                        "properties",
+                        Json::object()
+                            .with("tile", tile.tile.to_string())
+                            .with("color", tile.color as u64),
+                    )
+            });
+        write_features(features, &mut out)
+    }
+}
+
+impl<'a> GraphConnectivity<'a> {
+    /// Analyzes all tiles of the `reader` for edges that allow any of the `access` modes.
+    /// Islands have up to 100 edges by default.
+    pub fn new(reader: &'a GraphReader, access: Access) -> Self {
+        Self {
+            reader,
+            access,
+            tiles: None,
+            max_island_edges: 100,
+        }
+    }
+
+    /// Analyzes only the given tiles. Edges leaving them are ignored, so components at the borders of the selection
+    /// may be reported as islands even if they are connected to the rest of the graph.
+    pub fn tiles(mut self, tiles: impl IntoIterator<Item = GraphId>) -> Self {
+        self.tiles = Some(tiles.into_iter().map(|id| id.tile()).collect());
+        self
+    }
+
+    /// Reports components other than the largest one as islands if they have up to `edges` edges.
+    pub fn max_island_edges(mut self, edges: usize) -> Self {
+        self.max_island_edges = edges;
+        self
+    }
+
+    pub fn analyze(&self) -> ConnectivityReport {
+        let mut tile_ids = match &self.tiles {
+            Some(tiles) => tiles.clone(),
+            None => self.reader.tiles(),
+        };
+        tile_ids.sort_by_key(|id| id.value);
+        tile_ids.dedup();
+        let tiles: Vec<GraphTile> = tile_ids
+            .into_iter()
+            .filter_map(|id| self.reader.get_tile(id))
+            .collect();
+
+        // Nodes get dense indices, tile by tile
+        let mut first_node = Vec::with_capacity(tiles.len());
+        let mut tile_index = HashMap::with_capacity(tiles.len());
+        let mut node_count = 0;
+        for (index, tile) in tiles.iter().enumerate() {
+            first_node.push(node_count);
+            tile_index.insert(tile.id().value, index);
+            node_count += tile.nodes().len() as u32;
+        }
+        let node_index = |id: GraphId| {
+            let tile = *tile_index.get(&id.tile().value)?;
+            (id.id() < tiles[tile].nodes().len() as u32).then(|| (tile, first_node[tile] + id.id()))
+        };
+
+        let mut arcs = Vec::new();
+        let mut has_edges = vec![false; node_count as usize];
+        let mut colors = UnionFind::new(tiles.len());
+        for (tile_pos, tile) in tiles.iter().enumerate() {
+            for (index, node) in tile.nodes().iter().enumerate() {
+                let from = first_node[tile_pos] + index as u32;
+                for edge_index in node.edge_index()..node.edge_index() + node.edge_count() {
+                    let Some(edge) = tile.directededge(edge_index) else {
+                        continue;
+                    };
+                    if edge.is_shortcut() {
+                        continue;
+                    }
+                    let forward = edge.forwardaccess().intersects(self.access);
+                    let reverse = edge.reverseaccess().intersects(self.access);
+                    if !forward && !reverse {
+                        continue;
+                    }
+                    let Some((end_tile, to)) = node_index(edge.endnode()) else {
+                        continue;
+                    };
+                    if forward {
+                        arcs.push((from, to));
+                    }
+                    if reverse {
+                        arcs.push((to, from));
+                    }
+                    has_edges[from as usize] = true;
+                    has_edges[to as usize] = true;
+                    colors.union(tile_pos, end_tile);
+                }
+                for transition in tile.node_transitions(node) {
+                    if let Some((_, to)) = node_index(transition) {
+                        arcs.push((from, to));
+                    }
+                }
+            }
+        }
+
+        arcs.sort_unstable();
+        arcs.dedup();
+        let mut offsets = vec![0; node_count as usize + 1];
+        for &(from, _) in &arcs {
+            offsets[from as usize + 1] += 1;
+        }
+        for index in 1..offsets.len() {
+            offsets[index] += offsets[index - 1];
+        }
+        let targets: Vec<u32> = arcs.into_iter().map(|(_, to)| to).collect();
+        let labels = strongly_connected(&offsets, &targets);
+
+        // Directed edges inside each component, to order components by size and to collect islands
+        let mut component_edges: HashMap<u32, Vec<(GraphId, usize)>> = HashMap::new();
+        let mut component_nodes: HashMap<u32, usize> = HashMap::new();
+        for (tile_pos, tile) in tiles.iter().enumerate() {
+            for (index, node) in tile.nodes().iter().enumerate() {
+                let from = first_node[tile_pos] + index as u32;
+                if !has_edges[from as usize] {
+                    continue;
+                }
+                let label = labels[from as usize];
+                *component_nodes.entry(label).or_default() += 1;
+                for edge_index in node.edge_index()..node.edge_index() + node.edge_count() {
+                    let Some(edge) = tile.directededge(edge_index) else {
+                        continue;
+                    };
+                    if edge.is_shortcut() || !edge.forwardaccess().intersects(self.access) {
+                        continue;
+                    }
+                    if node_index(edge.endnode())
+                        .is_some_and(|(_, to)| labels[to as usize] == label)
+                    {
+                        component_edges
+                            .entry(label)
+                            .or_default()
+                            .push((tile.edge_id(edge_index), tile_pos));
+                    }
+                }
+            }
+        }
+        let mut components: Vec<(u32, Vec<(GraphId, usize)>)> =
+            component_edges.into_iter().collect();
+        components.sort_by_key(|(label, edges)| (std::cmp::Reverse(edges.len()), *label));
+        let numbers: HashMap<u32, usize> = components
+            .iter()
+            .enumerate()
+            .map(|(number, (label, _))| (*label, number))
+            .collect();
+
+        let mut report = ConnectivityReport {
+            components: components.len(),
+            ..Default::default()
+        };
+        for (tile_pos, tile) in tiles.iter().enumerate() {
+            for index in 0..tile.nodes().len() as u32 {
+                let label = labels[(first_node[tile_pos] + index) as usize];
+                if let Some(&number) = numbers.get(&label) {
+                    report.labels.insert(tile.node_id(index).value, number);
+                }
+            }
+        }
+
+        for (number, (label, mut edges)) in components.into_iter().enumerate().skip(1) {
+            if edges.len() > self.max_island_edges {
+                continue;
+            }
+            edges.sort_by_key(|(id, _)| id.value);
+            let shapes: Vec<Vec<LatLon>> = edges
+                .iter()
+                .filter_map(|&(id, tile_pos)| {
+                    let tile = &tiles[tile_pos];
+                    Some(tile.edgeinfo(tile.directededge(id.id())?).shape_points())
+                })
+                .collect();
+            report.islands.push(Island {
+                component: number,
+                nodes: component_nodes.get(&label).copied().unwrap_or_default(),
+                edges: edges.into_iter().map(|(id, _)| id).collect(),
+                bbox: bounds(shapes.iter().flatten()),
+                shapes,
+            });
+        }
+
+        // Colors are numbered in the order of the first tile of each color
+        let mut color_numbers = HashMap::new();
+        for (tile_pos, tile) in tiles.iter().enumerate() {
+            let root = colors.find(tile_pos);
+            let next = color_numbers.len();
+            let color = *color_numbers.entry(root).or_insert(next);
+            let header = tile.header();
+            let size = tile_size(tile.id().level());
+            report.tiles.push(TileConnectivity {
+                tile: tile.id(),
+                color,
+                bbox: (
+                    LatLon(header.base_lat, header.base_lon),
+                    LatLon(header.base_lat + size, header.base_lon + size),
+                ),
+            });
+        }
+        report
+    }
+}
+
+/// Labels strongly connected components of a graph in compressed sparse row format, where the arcs of node `n`
+/// lead to `targets[offsets[n]..offsets[n + 1]]`. Uses Tarjan's algorithm without recursion, so that large
+/// components don't overflow the stack.
+fn strongly_connected(offsets: &[usize], targets: &[u32]) -> Vec<u32> {
+    let count = offsets.len() - 1;
+    let mut tarjan = Tarjan {
+        index: vec![UNVISITED; count],
+        low: vec![0; count],
+        on_stack: vec![false; count],
+        stack: Vec::new(),
+        calls: Vec::new(),
+        next_index: 0,
+    };
+    let mut labels = vec![UNVISITED; count];
+    let mut next_label = 0;
+
+    for root in 0..count {
+        if tarjan.index[root] != UNVISITED {
+            continue;
+        }
+        tarjan.visit(root, offsets);
+
+        while let Some((node, arc)) = tarjan.calls.last_mut() {
+            let node = *node;
+            if *arc < offsets[node + 1] {
+                let target = targets[*arc] as usize;
+                *arc += 1;
+                if tarjan.index[target] == UNVISITED {
+                    tarjan.visit(target, offsets);
+                } else if tarjan.on_stack[target] {
+                    tarjan.low[node] = tarjan.low[node].min(tarjan.index[target]);
+                }
+                continue;
+            }
+
+            tarjan.calls.pop();
+            if let Some(&(parent, _)) = tarjan.calls.last() {
+                tarjan.low[parent] = tarjan.low[parent].min(tarjan.low[node]);
+            }
+            if tarjan.low[node] == tarjan.index[node] {
+                while let Some(member) = tarjan.stack.pop() {
+                    tarjan.on_stack[member] = false;
+                    labels[member] = next_label;
+                    if member == node {
+                        break;
+                    }
+                }
+                next_label += 1;
+            }
+        }
+    }
+    labels
+}
+
+/// State of [`strongly_connected()`], with the call stack of the recursive algorithm as pairs of nodes and their
+/// next arcs.
+struct Tarjan {
+    index: Vec<u32>,
+    low: Vec<u32>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    calls: Vec<(usize, usize)>,
+    next_index: u32,
+}
+
+impl Tarjan {
+    fn visit(&mut self, node: usize, offsets: &[usize]) {
+        self.index[node] = self.next_index;
+        self.low[node] = self.next_index;
+        self.next_index += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+        self.calls.push((node, offsets[node]));
+    }
+}
+
+/// Disjoint sets of tiles for coloring the connectivity map.
+struct UnionFind {
+    parents: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(count: usize) -> Self {
+        Self {
+            parents: (0..count).collect(),
+        }
+    }
+
+    fn find(&mut self, mut item: usize) -> usize {
+        while self.parents[item] != item {
+            self.parents[item] = self.parents[self.parents[item]];
+            item = self.parents[item];
+        }
+        item
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a != b {
+            self.parents[a.max(b)] = a.min(b);
+        }
+    }
+}
+
+/// Minimum and maximum coordinates of the points.
+fn bounds<'p>(points: impl Iterator<Item = &'p LatLon>) -> (LatLon, LatLon) {
+    let mut min = LatLon(f64::MAX, f64::MAX);
+    let mut max = LatLon(f64::MIN, f64::MIN);
+    for point in points {
+        min = LatLon(min.0.min(point.0), min.1.min(point.1));
+        max = LatLon(max.0.max(point.0), max.1.max(point.1));
+    }
+    (min, max)
+}
+
+/// GeoJSON coordinates in (lon, lat) order.
+fn coordinates(points: &[LatLon]) -> Json {
+    let points: Vec<Json> = points
+        .iter()
+        .map(|ll| Json::from(vec![ll.1, ll.0]))
+        .collect();
+    Json::from(points)
+}
+
+fn write_features(features: impl Iterator<Item = Json>, out: &mut impl Write) -> io::Result<usize> {
+    out.write_all(br#"{"type":"FeatureCollection","features":["#)?;
+    let mut count = 0;
+    for feature in features {
+        if count > 0 {
+            out.write_all(b",")?;
+        }
+        write!(out, "{feature}")?;
+        count += 1;
+    }
+    out.write_all(b"]}")?;
+    out.flush()?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds the compressed sparse row format from arcs sorted by their start nodes.
+    fn labels(count: usize, arcs: &[(u32, u32)]) -> Vec<u32> {
+        let mut offsets = vec![0; count + 1];
+        for &(from, _) in arcs {
+            offsets[from as usize + 1] += 1;
+        }
+        for index in 1..offsets.len() {
+            offsets[index] += offsets[index - 1];
+        }
+        let targets: Vec<u32> = arcs.iter().map(|&(_, to)| to).collect();
+        strongly_connected(&offsets, &targets)
+    }
+
+    #[test]
+    fn components() {
+        // Cycle 0 -> 1 -> 2 -> 0, one-way 2 -> 3, two-way 3 <-> 4 and isolated 5
+        let labels = labels(6, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 3)]);
+        assert_eq!(labels[0], labels[1]);
+        assert_eq!(labels[1], labels[2]);
+        assert_eq!(labels[3], labels[4]);
+        assert_ne!(labels[0], labels[3]);
+        assert_ne!(labels[5], labels[0]);
+        assert_ne!(labels[5], labels[3]);
+        assert!(labels.iter().all(|&label| label != UNVISITED));
+    }
+
+    #[test]
+    fn one_way_chain() {
+        let labels = labels(3, &[(0, 1), (1, 2)]);
+        assert_ne!(labels[0], labels[1]);
+        assert_ne!(labels[1], labels[2]);
+        assert_ne!(labels[0], labels[2]);
+    }
+}
@@ -0,0 +1,437 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{self, Write},
+};
+
+use crate::{
+    Access, EdgeUse, GraphId, GraphReader, GraphTile, LatLon, RoadClass,
+    export::{access_names, enum_name},
+    json::Json,
+    tile_size,
+};
+
+/// Compares two builds of a tileset, e.g. before promoting a new `tiles.tar`.
+///
+/// [`GraphId`]s aren't stable between builds, so directed edges are matched by their OSM way ID and the end points
+/// of their shape. Edges are matched across hierarchy levels, so an edge that moved to another level because of a
+/// changed road class is reported as changed, in the tile of its new build. Shortcuts are skipped.
+/// ```rust
+/// use valhalla::{Config, GraphDiff, GraphReader};
+///
+/// let old = GraphReader::new(&Config::from_tile_extract("old/tiles.tar").unwrap()).unwrap();
+/// let new = GraphReader::new(&Config::from_tile_extract("new/tiles.tar").unwrap()).unwrap();
+/// let report = GraphDiff::new(&old, &new).compare();
+/// for tile in &report.tiles {
+///     println!("{}: +{} -{} ~{}", tile.tile, tile.added, tile.removed, tile.changed);
+/// }
+/// let file = std::fs::File::create("diff.geojson").unwrap();
+/// report.write_geojson(std::io::BufWriter::new(file)).unwrap();
+/// ```
+pub struct GraphDiff<'a> {
+    old: &'a GraphReader,
+    new: &'a GraphReader,
+    tiles: Option<Vec<GraphId>>,
+}
+
+/// Differences found by [`GraphDiff::compare()`].
+#[derive(Clone, Debug, Default)]
+pub struct GraphDiffReport {
+    /// Added, removed and changed edges, ordered by tile.
+    pub edges: Vec<EdgeDiff>,
+    /// Summaries of tiles present in any of the builds, sorted by tile ID.
+    pub tiles: Vec<TileDiffSummary>,
+}
+
+/// Kind of an [`EdgeDiff`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdgeChange {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// Edge that differs between the builds.
+#[derive(Clone, Debug)]
+pub struct EdgeDiff {
+    pub way_id: u64,
+    /// Edge in the old build, `None` if the edge was added.
+    pub old_id: Option<GraphId>,
+    /// Edge in the new build, `None` if the edge was removed.
+    pub new_id: Option<GraphId>,
+    /// Shape of the edge in the new build, or in the old one if the edge was removed.
+    pub shape: Vec<LatLon>,
+    /// Changed attributes of an edge present in both builds.
+    pub changes: Vec<AttributeChange>,
+}
+
+/// Attribute of an edge that changed between the builds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttributeChange {
+    Speed {
+        old: u32,
+        new: u32,
+    },
+    Length {
+        old: u32,
+        new: u32,
+    },
+    ForwardAccess {
+        old: Access,
+        new: Access,
+    },
+    ReverseAccess {
+        old: Access,
+        new: Access,
+    },
+    RoadClass {
+        old: RoadClass,
+        new: RoadClass,
+    },
+    Use {
+        old: EdgeUse,
+        new: EdgeUse,
+    },
+    /// Intermediate points of the shape changed, while its end points are the same.
+    Shape,
+}
+
+/// Numbers of edges of a tile by their [`EdgeChange`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TileDiffSummary {
+    pub tile: GraphId,
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+    pub unchanged: usize,
+}
+
+impl EdgeDiff {
+    pub fn kind(&self) -> EdgeChange {
+        match (self.old_id, self.new_id) {
+            (None, _) => EdgeChange::Added,
+            (_, None) => EdgeChange::Removed,
+            _ => EdgeChange::Changed,
+        }
+    }
+}
+
+impl<'a> GraphDiff<'a> {
+    /// Compares all tiles of both builds by default.
+    pub fn new(old: &'a GraphReader, new: &'a GraphReader) -> Self {
+        Self {
+            old,
+            new,
+            tiles: None,
+        }
+    }
+
+    /// Compares only the given tiles. Edges that moved to a level whose tile isn't given are reported as removed or
+    /// added.
+    pub fn tiles(mut self, tiles: impl IntoIterator<Item = GraphId>) -> Self {
+        self.tiles = Some(tiles.into_iter().map(|id| id.tile()).collect());
+        self
+    }
+
+    pub fn compare(&self) -> GraphDiffReport {
+        let mut tiles = match &self.tiles {
+            Some(tiles) => tiles.clone(),
+            None => self
+                .old
+                .tiles()
+                .into_iter()
+                .chain(self.new.tiles())
+                .collect(),
+        };
+        tiles.sort_by_key(|id| id.value);
+        tiles.dedup();
+
+        let mut groups: BTreeMap<u32, Vec<GraphId>> = BTreeMap::new();
+        for tile_id in tiles {
+            groups.entry(base_tile(tile_id)).or_default().push(tile_id);
+        }
+        let mut summaries = BTreeMap::new();
+        let mut diffs = BTreeMap::new();
+        for group in groups.values() {
+            self.compare_tiles(group, &mut summaries, &mut diffs);
+        }
+
+        let mut report = GraphDiffReport::default();
+        for (tile, summary) in summaries {
+            report.tiles.push(summary);
+            report.edges.extend(diffs.remove(&tile).unwrap_or_default());
+        }
+        report
+    }
+
+    /// Compares edges of tiles covering the same level 0 tile, so that edges are matched across levels.
+    fn compare_tiles(
+        &self,
+        tiles: &[GraphId],
+        summaries: &mut BTreeMap<u64, TileDiffSummary>,
+        diffs: &mut BTreeMap<u64, Vec<EdgeDiff>>,
+    ) {
+        for &tile_id in tiles {
+            summaries.insert(
+                tile_id.value,
+                TileDiffSummary {
+                    tile: tile_id,
+                    added: 0,
+                    removed: 0,
+                    changed: 0,
+                    unchanged: 0,
+                },
+            );
+        }
+        let records = |reader: &GraphReader| {
+            let mut records: HashMap<EdgeKey, Vec<EdgeRecord>> = HashMap::new();
+            for tile in tiles.iter().filter_map(|&tile_id| reader.get_tile(tile_id)) {
+                for record in edge_records(&tile) {
+                    records.entry(record.key).or_default().push(record);
+                }
+            }
+            records
+        };
+        let mut old_edges = records(self.old);
+        let mut new_edges: Vec<_> = records(self.new).into_values().flatten().collect();
+        new_edges.sort_by_key(|edge| edge.id.value);
+
+        let mut changed = Vec::new();
+        let mut added = Vec::new();
+        for new in new_edges {
+            let key = new.key;
+            let Some(old) = old_edges.get_mut(&key).and_then(|edges| edges.pop()) else {
+                added.push(new);
+                continue;
+            };
+            let summary = summaries
+                .get_mut(&new.id.tile().value)
+                .expect("Tile is compared");
+            let changes = old.changes(&new);
+            if changes.is_empty() {
+                summary.unchanged += 1;
+                continue;
+            }
+            summary.changed += 1;
+            changed.push(EdgeDiff {
+                way_id: key.way_id,
+                old_id: Some(old.id),
+                new_id: Some(new.id),
+                shape: new.shape,
+                changes,
+            });
+        }
+        let mut removed: Vec<_> = old_edges.into_values().flatten().collect();
+        removed.sort_by_key(|edge| edge.id.value);
+
+        // Changed edges are listed first, then removed and added ones, within the tile of the edge
+        let removed = removed.into_iter().map(|old| EdgeDiff {
+            way_id: old.key.way_id,
+            old_id: Some(old.id),
+            new_id: None,
+            shape: old.shape,
+            changes: Vec::new(),
+        });
+        let added = added.into_iter().map(|new| EdgeDiff {
+            way_id: new.key.way_id,
+            old_id: None,
+            new_id: Some(new.id),
+            shape: new.shape,
+            changes: Vec::new(),
+        });
+        for diff in changed.into_iter().chain(removed).chain(added) {
+            let tile = diff
+                .new_id
+                .or(diff.old_id)
+                .expect("Edge is in any build")
+                .tile();
+            let summary = summaries.get_mut(&tile.value).expect("Tile is compared");
+            match diff.kind() {
+                EdgeChange::Added => summary.added += 1,
+                EdgeChange::Removed => summary.removed += 1,
+                EdgeChange::Changed => {}
+            }
+            diffs.entry(tile.value).or_default().push(diff);
+        }
+    }
+}
+
+/// Level 0 tile that contains the tile, as tiles of lower levels are subdivisions of it.
+fn base_tile(tile: GraphId) -> u32 {
+    let size = tile_size(tile.level());
+    let columns = (360.0 / size) as u32;
+    let scale = (tile_size(0) / size) as u32;
+    let (row, column) = (tile.tileid() / columns, tile.tileid() % columns);
+    (row / scale) * (columns / scale) + column / scale
+}
+
+impl GraphDiffReport {
+    /// Writes differing edges as a GeoJSON `FeatureCollection` of line strings with `change`, `way_id`, `old_id`,
+    /// `new_id` and `changes` properties, where `changes` lists changed attributes like `speed: 50 -> 30`.
+    /// Returns the number of written features.
+    pub fn write_geojson(&self, mut out: impl Write) -> io::Result<usize> {
+        out.write_all(br#"{"type":"FeatureCollection","features":["#)?;
+        for (index, edge) in self.edges.iter().enumerate() {
+            let coordinates: Vec<Json> = edge
+                .shape
+                .iter()
+                .map(|ll| Json::from(vec![ll.1, ll.0]))
+                .collect();
+            let changes: Vec<String> = edge.changes.iter().map(change_text).collect();
+            let properties = Json::object()
+                .with("change", enum_name(edge.kind()).to_lowercase())
+                .with("way_id", edge.way_id)
+                .with_opt("old_id", edge.old_id.map(|id| id.to_string()))
+                .with_opt("new_id", edge.new_id.map(|id| id.to_string()))
+                .with("changes", changes);
+            let json = Json::object()
+                .with("type", "Feature")
+                .with(
+                    "geometry",
+                    Json::object()
+                        .with("type", "LineString")
+                        .with("coordinates", coordinates),
+                )
+                .with("properties", properties);
+            if index > 0 {
+                out.write_all(b",")?;
+            }
+            write!(out, "{json}")?;
+        }
+        out.write_all(b"]}")?;
+        out.flush()?;
+        Ok(self.edges.len())
+    }
+}
+
+/// Describes the change like `speed: 50 -> 30`.
+fn change_text(change: &AttributeChange) -> String {
+    match change {
+        AttributeChange::Speed { old, new } => format!("speed: {old} -> {new}"),
+        AttributeChange::Length { old, new } => format!("length: {old} -> {new}"),
+        AttributeChange::ForwardAccess { old, new } => {
+            format!(
+                "forward_access: {} -> {}",
+                access_names(*old),
+                access_names(*new)
+            )
+        }
+        AttributeChange::ReverseAccess { old, new } => {
+            format!(
+                "reverse_access: {} -> {}",
+                access_names(*old),
+                access_names(*new)
+            )
+        }
+        AttributeChange::RoadClass { old, new } => {
+            format!("road_class: {} -> {}", enum_name(old), enum_name(new))
+        }
+        AttributeChange::Use { old, new } => {
+            format!("use: {} -> {}", enum_name(old), enum_name(new))
+        }
+        AttributeChange::Shape => "shape".to_string(),
+    }
+}
+
+/// Identifies the same directed edge in different builds.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct EdgeKey {
+    way_id: u64,
+    /// First and last points of the shape in polyline6 precision.
+    start: (i64, i64),
+    end: (i64, i64),
+}
+
+/// Compared attributes of an edge.
+struct EdgeRecord {
+    id: GraphId,
+    key: EdgeKey,
+    shape: Vec<LatLon>,
+    speed: u32,
+    length: u32,
+    forward_access: Access,
+    reverse_access: Access,
+    road_class: RoadClass,
+    edge_use: EdgeUse,
+}
+
+impl EdgeRecord {
+    fn changes(&self, new: &EdgeRecord) -> Vec<AttributeChange> {
+        let mut changes = Vec::new();
+        if self.speed != new.speed {
+            changes.push(AttributeChange::Speed {
+                old: self.speed,
+                new: new.speed,
+            });
+        }
+        if self.length != new.length {
+            changes.push(AttributeChange::Length {
+                old: self.length,
+                new: new.length,
+            });
+        }
+        if self.forward_access != new.forward_access {
+            changes.push(AttributeChange::ForwardAccess {
+                old: self.forward_access,
+                new: new.forward_access,
+            });
+        }
+        if self.reverse_access != new.reverse_access {
+            changes.push(AttributeChange::ReverseAccess {
+                old: self.reverse_access,
+                new: new.reverse_access,
+            });
+        }
+        if self.road_class != new.road_class {
+            changes.push(AttributeChange::RoadClass {
+                old: self.road_class,
+                new: new.road_class,
+            });
+        }
+        if self.edge_use != new.edge_use {
+            changes.push(AttributeChange::Use {
+                old: self.edge_use,
+                new: new.edge_use,
+            });
+        }
+        if self.shape != new.shape {
+            changes.push(AttributeChange::Shape);
+        }
+        changes
+    }
+}
+
+/// Non-shortcut edges of the tile with OSM way IDs.
+fn edge_records(tile: &GraphTile) -> Vec<EdgeRecord> {
+    let fixed = |ll: LatLon| ((ll.0 * 1e6).round() as i64, (ll.1 * 1e6).round() as i64);
+    let mut records = Vec::new();
+    for (index, edge) in tile.directededges().iter().enumerate() {
+        if edge.is_shortcut() {
+            continue;
+        }
+        let info = tile.edgeinfo(edge);
+        let shape = info.shape_points();
+        let (Some(&first), Some(&last)) = (shape.first(), shape.last()) else {
+            continue;
+        };
+        if info.way_id == 0 {
+            continue;
+        }
+        let key = EdgeKey {
+            way_id: info.way_id,
+            start: fixed(first),
+            end: fixed(last),
+        };
+        records.push(EdgeRecord {
+            id: tile.edge_id(index as u32),
+            key,
+            shape,
+            speed: edge.speed(),
+            length: edge.length(),
+            forward_access: edge.forwardaccess(),
+            reverse_access: edge.reverseaccess(),
+            road_class: edge.classification(),
+            edge_use: edge.use_type(),
+        });
+    }
+    records
+}
@@ -0,0 +1,536 @@
+use std::{
+    fmt,
+    io::{self, Write},
+    str::FromStr,
+};
+
+use crate::{
+    Access, Error, GraphId, GraphLevel, GraphReader, GraphTile, LatLon, json::Json, tile_size,
+};
+
+mod flatgeobuf;
+
+/// Output format of [`GraphExport::write()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Single GeoJSON `FeatureCollection`.
+    GeoJson,
+    /// Newline-delimited GeoJSON, one `Feature` per line.
+    GeoJsonSeq,
+    /// CSV with geometry in the `wkt` column.
+    Csv,
+    /// FlatGeobuf without the spatial index. Unlike other formats, it isn't streamed: the header needs the feature
+    /// count and extent, so all encoded features are kept in memory until the export ends, roughly as much as the
+    /// output file.
+    FlatGeobuf,
+}
+
+impl FromStr for ExportFormat {
+    type Err = Error;
+
+    /// Parses format names and file extensions: `geojson`, `geojsonseq`/`ndjson`, `csv`, `fgb`/`flatgeobuf`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "geojson" | "json" => Ok(ExportFormat::GeoJson),
+            "geojsonseq" | "ndjson" | "geojsonl" => Ok(ExportFormat::GeoJsonSeq),
+            "csv" => Ok(ExportFormat::Csv),
+            "fgb" | "flatgeobuf" => Ok(ExportFormat::FlatGeobuf),
+            _ => Err(Error(format!("Unknown export format: {s}").into())),
+        }
+    }
+}
+
+/// Kind of graph objects written by [`GraphExport::write()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportLayer {
+    /// Directed edges as line strings with `id`, `way_id`, `length`, `speed`, `speed_limit`, `forward_access`,
+    /// `reverse_access`, `use`, `road_class` and `shortcut` properties.
+    Edges,
+    /// Nodes as points with `id`, `type` and `access` properties.
+    Nodes,
+}
+
+impl FromStr for ExportLayer {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "edges" => Ok(ExportLayer::Edges),
+            "nodes" => Ok(ExportLayer::Nodes),
+            _ => Err(Error(format!("Unknown export layer: {s}").into())),
+        }
+    }
+}
+
+/// Exports graph edges or nodes of the selected tiles to GeoJSON, CSV or FlatGeobuf for inspection in GIS tools.
+/// ```rust
+/// use valhalla::{ExportFormat, ExportLayer, GraphExport, LatLon};
+///
+/// let config = valhalla::Config::from_file("path/to/config.json").unwrap();
+/// let reader = valhalla::GraphReader::new(&config).unwrap();
+/// let file = std::fs::File::create("edges.fgb").unwrap();
+/// let count = GraphExport::new(&reader)
+///     .bbox(LatLon(42.50, 1.51), LatLon(42.51, 1.53))
+///     .skip_shortcuts()
+///     .write(ExportLayer::Edges, ExportFormat::FlatGeobuf, std::io::BufWriter::new(file))
+///     .unwrap();
+/// ```
+pub struct GraphExport<'a> {
+    reader: &'a GraphReader,
+    tiles: Option<Vec<GraphId>>,
+    bbox: Option<(LatLon, LatLon)>,
+    skip_shortcuts: bool,
+}
+
+impl<'a> GraphExport<'a> {
+    /// Exports all tiles of the `reader` by default.
+    pub fn new(reader: &'a GraphReader) -> Self {
+        Self {
+            reader,
+            tiles: None,
+            bbox: None,
+            skip_shortcuts: false,
+        }
+    }
+
+    /// Exports only the given tiles.
+    pub fn tiles(mut self, tiles: impl IntoIterator<Item = GraphId>) -> Self {
+        self.tiles = Some(tiles.into_iter().map(|id| id.tile()).collect());
+        self
+    }
+
+    /// Exports only objects within the bounding box, on all hierarchy levels.
+    /// Edges are exported if any part of their shape is within the box, even without a shape point inside it.
+    /// Edges are stored in the tile of their start node, so tiles next to the box are scanned as well, and only
+    /// edges that are longer than a tile and start further away are missed.
+    pub fn bbox(mut self, min: LatLon, max: LatLon) -> Self {
+        self.bbox = Some((min, max));
+        self
+    }
+
+    /// Skips shortcut edges, which duplicate sequences of regular edges on higher levels.
+    pub fn skip_shortcuts(mut self) -> Self {
+        self.skip_shortcuts = true;
+        self
+    }
+
+    /// Writes the `layer` in the `format` and returns the number of written features.
+    pub fn write(
+        &self,
+        layer: ExportLayer,
+        format: ExportFormat,
+        mut out: impl Write,
+    ) -> io::Result<usize> {
+        let columns = match layer {
+            ExportLayer::Edges => EDGE_COLUMNS,
+            ExportLayer::Nodes => NODE_COLUMNS,
+        };
+        let mut writer: Box<dyn FeatureWriter + '_> = match format {
+            ExportFormat::GeoJson => Box::new(GeoJsonWriter {
+                out: &mut out,
+                columns,
+                seq: false,
+                count: 0,
+            }),
+            ExportFormat::GeoJsonSeq => Box::new(GeoJsonWriter {
+                out: &mut out,
+                columns,
+                seq: true,
+                count: 0,
+            }),
+            ExportFormat::Csv => Box::new(CsvWriter {
+                out: &mut out,
+                columns,
+            }),
+            ExportFormat::FlatGeobuf => Box::new(flatgeobuf::Writer::new(&mut out, layer, columns)),
+        };
+
+        writer.begin()?;
+        let mut count = 0;
+        for id in self.selected_tiles() {
+            let Some(tile) = self.reader.get_tile(id) else {
+                continue;
+            };
+            let features = match layer {
+                ExportLayer::Edges => self.edges(&tile),
+                ExportLayer::Nodes => self.nodes(&tile),
+            };
+            for feature in features {
+                writer.feature(&feature)?;
+                count += 1;
+            }
+        }
+        writer.end()?;
+        drop(writer);
+        out.flush()?;
+        Ok(count)
+    }
+
+    fn selected_tiles(&self) -> Vec<GraphId> {
+        let mut tiles = match (&self.tiles, self.bbox) {
+            (Some(tiles), _) => tiles.clone(),
+            (None, Some((min, max))) => {
+                [GraphLevel::Highway, GraphLevel::Arterial, GraphLevel::Local]
+                    .into_iter()
+                    .flat_map(|level| {
+                        let margin = tile_size(level.repr as u32);
+                        let min = LatLon(min.0 - margin, min.1 - margin);
+                        let max = LatLon(max.0 + margin, max.1 + margin);
+                        self.reader.tiles_in_bbox(min, max, level)
+                    })
+                    .collect()
+            }
+            (None, None) => self.reader.tiles(),
+        };
+        tiles.sort_by_key(|id| id.value);
+        tiles.dedup();
+        tiles
+    }
+
+    fn in_bbox(&self, ll: LatLon) -> bool {
+        self.bbox
+            .is_none_or(|(min, max)| point_in_bbox(ll, min, max))
+    }
+
+    fn edges(&self, tile: &GraphTile) -> Vec<Feature> {
+        let mut features = Vec::new();
+        for (index, edge) in tile.directededges().iter().enumerate() {
+            if self.skip_shortcuts && edge.is_shortcut() {
+                continue;
+            }
+            let info = tile.edgeinfo(edge);
+            let shape = info.shape_points();
+            let crosses_bbox = match (self.bbox, shape.as_slice()) {
+                (None, _) => true,
+                (Some((min, max)), [ll]) => point_in_bbox(*ll, min, max),
+                (Some((min, max)), shape) => shape
+                    .windows(2)
+                    .any(|segment| segment_in_bbox(segment[0], segment[1], min, max)),
+            };
+            if !crosses_bbox {
+                continue;
+            }
+            features.push(Feature {
+                geometry: Geometry::LineString(shape),
+                properties: vec![
+                    Value::String(tile.edge_id(index as u32).to_string()),
+                    Value::U64(info.way_id),
+                    Value::U32(edge.length()),
+                    Value::U32(edge.speed()),
+                    Value::U32(info.speed_limit.into()),
+                    Value::String(access_names(edge.forwardaccess())),
+                    Value::String(access_names(edge.reverseaccess())),
+                    Value::String(enum_name(edge.use_type())),
+                    Value::String(enum_name(edge.classification())),
+                    Value::Bool(edge.is_shortcut()),
+                ],
+            });
+        }
+        features
+    }
+
+    fn nodes(&self, tile: &GraphTile) -> Vec<Feature> {
+        let mut features = Vec::new();
+        for (index, node) in tile.nodes().iter().enumerate() {
+            let ll = tile.node_latlon(node);
+            if !self.in_bbox(ll) {
+                continue;
+            }
+            features.push(Feature {
+                geometry: Geometry::Point(ll),
+                properties: vec![
+                    Value::String(tile.node_id(index as u32).to_string()),
+                    Value::String(enum_name(node.node_type())),
+                    Value::String(access_names(Access::from_bits_retain(node.access()))),
+                ],
+            });
+        }
+        features
+    }
+}
+
+fn point_in_bbox(ll: LatLon, min: LatLon, max: LatLon) -> bool {
+    (min.0..=max.0).contains(&ll.0) && (min.1..=max.1).contains(&ll.1)
+}
+
+/// Whether any part of the segment from `a` to `b` is within the box, using Liang-Barsky clipping.
+fn segment_in_bbox(a: LatLon, b: LatLon, min: LatLon, max: LatLon) -> bool {
+    let (mut enter, mut exit) = (0.0f64, 1.0f64);
+    for (start, delta, low, high) in [
+        (a.0, b.0 - a.0, min.0, max.0),
+        (a.1, b.1 - a.1, min.1, max.1),
+    ] {
+        if delta == 0.0 {
+            if !(low..=high).contains(&start) {
+                return false;
+            }
+            continue;
+        }
+        let (t0, t1) = ((low - start) / delta, (high - start) / delta);
+        enter = enter.max(t0.min(t1));
+        exit = exit.min(t0.max(t1));
+        if enter > exit {
+            return false;
+        }
+    }
+    true
+}
+
+/// Type of a feature property, matching FlatGeobuf column types.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ColumnType {
+    Bool,
+    U32,
+    U64,
+    String,
+}
+
+type Columns = &'static [(&'static str, ColumnType)];
+
+const EDGE_COLUMNS: Columns = &[
+    ("id", ColumnType::String),
+    ("way_id", ColumnType::U64),
+    ("length", ColumnType::U32),
+    ("speed", ColumnType::U32),
+    ("speed_limit", ColumnType::U32),
+    ("forward_access", ColumnType::String),
+    ("reverse_access", ColumnType::String),
+    ("use", ColumnType::String),
+    ("road_class", ColumnType::String),
+    ("shortcut", ColumnType::Bool),
+];
+
+const NODE_COLUMNS: Columns = &[
+    ("id", ColumnType::String),
+    ("type", ColumnType::String),
+    ("access", ColumnType::String),
+];
+
+enum Value {
+    Bool(bool),
+    U32(u32),
+    U64(u64),
+    String(String),
+}
+
+impl From<&Value> for Json {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::Bool(value) => Json::from(*value),
+            Value::U32(value) => Json::from(*value),
+            Value::U64(value) => Json::from(*value),
+            Value::String(value) => Json::from(value.as_str()),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Bool(value) => write!(f, "{value}"),
+            Value::U32(value) => write!(f, "{value}"),
+            Value::U64(value) => write!(f, "{value}"),
+            Value::String(value) => f.write_str(value),
+        }
+    }
+}
+
+enum Geometry {
+    Point(LatLon),
+    LineString(Vec<LatLon>),
+}
+
+impl Geometry {
+    fn points(&self) -> &[LatLon] {
+        match self {
+            Geometry::Point(ll) => std::slice::from_ref(ll),
+            Geometry::LineString(points) => points,
+        }
+    }
+}
+
+struct Feature {
+    geometry: Geometry,
+    /// Values in the order of the layer columns.
+    properties: Vec<Value>,
+}
+
+trait FeatureWriter {
+    fn begin(&mut self) -> io::Result<()>;
+    fn feature(&mut self, feature: &Feature) -> io::Result<()>;
+    fn end(&mut self) -> io::Result<()>;
+}
+
+struct GeoJsonWriter<W> {
+    out: W,
+    columns: Columns,
+    /// Newline-delimited features instead of a `FeatureCollection`.
+    seq: bool,
+    count: usize,
+}
+
+impl<W: Write> FeatureWriter for GeoJsonWriter<W> {
+    fn begin(&mut self) -> io::Result<()> {
+        if !self.seq {
+            self.out
+                .write_all(br#"{"type":"FeatureCollection","features":["#)?;
+        }
+        Ok(())
+    }
+
+    fn feature(&mut self, feature: &Feature) -> io::Result<()> {
+        let position = |ll: &LatLon| Json::from(vec![ll.1, ll.0]);
+        let geometry = match &feature.geometry {
+            Geometry::Point(ll) => Json::object()
+                .with("type", "Point")
+                .with("coordinates", position(ll)),
+            Geometry::LineString(points) => Json::object().with("type", "LineString").with(
+                "coordinates",
+                points.iter().map(position).collect::<Vec<_>>(),
+            ),
+        };
+        let properties = self
+            .columns
+            .iter()
+            .zip(&feature.properties)
+            .fold(Json::object(), |json, ((name, _), value)| {
+                json.with(name, value)
+            });
+        let json = Json::object()
+            .with("type", "Feature")
+            .with("geometry", geometry)
+            .with("properties", properties);
+
+        if self.seq {
+            writeln!(self.out, "{json}")?;
+        } else if self.count == 0 {
+            write!(self.out, "{json}")?;
+        } else {
+            write!(self.out, ",{json}")?;
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    fn end(&mut self) -> io::Result<()> {
+        if !self.seq {
+            self.out.write_all(b"]}")?;
+        }
+        Ok(())
+    }
+}
+
+struct CsvWriter<W> {
+    out: W,
+    columns: Columns,
+}
+
+impl<W: Write> FeatureWriter for CsvWriter<W> {
+    fn begin(&mut self) -> io::Result<()> {
+        write!(self.out, "wkt")?;
+        for (name, _) in self.columns {
+            write!(self.out, ",{name}")?;
+        }
+        writeln!(self.out)
+    }
+
+    fn feature(&mut self, feature: &Feature) -> io::Result<()> {
+        let coordinates: Vec<_> = feature
+            .geometry
+            .points()
+            .iter()
+            .map(|ll| format!("{} {}", ll.1, ll.0))
+            .collect();
+        let wkt = match feature.geometry {
+            Geometry::Point(_) => format!("POINT ({})", coordinates.join(", ")),
+            Geometry::LineString(_) => format!("LINESTRING ({})", coordinates.join(", ")),
+        };
+        write!(self.out, "{}", csv_field(&wkt))?;
+        for value in &feature.properties {
+            write!(self.out, ",{}", csv_field(&value.to_string()))?;
+        }
+        writeln!(self.out)
+    }
+
+    fn end(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Quotes the field if it contains separators, quotes or line breaks.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Access modes like `AUTO | PEDESTRIAN`.
+pub(crate) fn access_names(access: Access) -> String {
+    let mut names = String::new();
+    let _ = bitflags::parser::to_writer(&access, &mut names);
+    names
+}
+
+/// Name of a Valhalla enum value without the `k` prefix, e.g. `Motorway` for `RoadClass::kMotorway`.
+pub(crate) fn enum_name(value: impl fmt::Debug) -> String {
+    let name = format!("{value:?}");
+    match name.strip_prefix('k') {
+        Some(stripped) => stripped.to_string(),
+        None => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segment_bbox_intersection() {
+        let (min, max) = (LatLon(0.0, 0.0), LatLon(1.0, 1.0));
+        // Inside, crossing without vertices inside, touching a corner and outside
+        assert!(segment_in_bbox(
+            LatLon(0.2, 0.2),
+            LatLon(0.8, 0.8),
+            min,
+            max
+        ));
+        assert!(segment_in_bbox(
+            LatLon(-1.0, 0.5),
+            LatLon(2.0, 0.5),
+            min,
+            max
+        ));
+        assert!(segment_in_bbox(
+            LatLon(-1.0, -1.0),
+            LatLon(2.0, 2.0),
+            min,
+            max
+        ));
+        assert!(segment_in_bbox(
+            LatLon(0.0, 2.0),
+            LatLon(2.0, 0.0),
+            min,
+            max
+        ));
+        assert!(!segment_in_bbox(
+            LatLon(1.5, 0.0),
+            LatLon(0.0, 1.5),
+            LatLon(0.0, 0.0),
+            LatLon(0.5, 0.5)
+        ));
+        assert!(!segment_in_bbox(
+            LatLon(2.0, -1.0),
+            LatLon(2.0, 2.0),
+            min,
+            max
+        ));
+        assert!(!segment_in_bbox(
+            LatLon(-1.0, 2.0),
+            LatLon(0.5, 3.0),
+            min,
+            max
+        ));
+    }
+}
@@ -0,0 +1,507 @@
+//! FlatGeobuf writer, see <https://flatgeobuf.org>.
+//!
+//! The format needs the number of features and the extent in the header, so features are buffered until
+//! [`FeatureWriter::end()`]. The optional spatial index is not written.
+
+use std::io::{self, Write};
+
+use super::{ColumnType, Columns, ExportLayer, Feature, FeatureWriter, Geometry, Value};
+
+const MAGIC: &[u8] = b"fgb\x03fgb\x00";
+
+pub(super) struct Writer<W> {
+    out: W,
+    layer: ExportLayer,
+    columns: Columns,
+    features: Vec<Vec<u8>>,
+    /// `[min_x, min_y, max_x, max_y]` of all features.
+    envelope: [f64; 4],
+}
+
+impl<W: Write> Writer<W> {
+    pub(super) fn new(out: W, layer: ExportLayer, columns: Columns) -> Self {
+        Self {
+            out,
+            layer,
+            columns,
+            features: Vec::new(),
+            envelope: [
+                f64::INFINITY,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                f64::NEG_INFINITY,
+            ],
+        }
+    }
+
+    fn header(&self) -> Vec<u8> {
+        let (name, geometry_type) = match self.layer {
+            ExportLayer::Edges => ("edges", GEOMETRY_LINE_STRING),
+            ExportLayer::Nodes => ("nodes", GEOMETRY_POINT),
+        };
+        let columns = self
+            .columns
+            .iter()
+            .map(|&(name, column_type)| {
+                Table(vec![
+                    (0, Field::String(name)),
+                    (1, Field::U8(column_type.code())),
+                ])
+            })
+            .collect();
+        let mut header = vec![
+            (0, Field::String(name)),
+            (2, Field::U8(geometry_type)),
+            (7, Field::Tables(columns)),
+            (8, Field::U64(self.features.len() as u64)),
+            // Explicit zero, as absent value means the default node size of 16 with an index
+            (9, Field::U16(0)),
+            (10, Field::Table(Table(vec![(1, Field::I32(4326))]))),
+        ];
+        if !self.features.is_empty() {
+            header.push((1, Field::F64s(self.envelope.to_vec())));
+        }
+        Builder::finish(Table(header))
+    }
+}
+
+impl<W: Write> FeatureWriter for Writer<W> {
+    fn begin(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn feature(&mut self, feature: &Feature) -> io::Result<()> {
+        let points = feature.geometry.points();
+        let mut xy = Vec::with_capacity(points.len() * 2);
+        for ll in points {
+            xy.extend([ll.1, ll.0]);
+            self.envelope[0] = self.envelope[0].min(ll.1);
+            self.envelope[1] = self.envelope[1].min(ll.0);
+            self.envelope[2] = self.envelope[2].max(ll.1);
+            self.envelope[3] = self.envelope[3].max(ll.0);
+        }
+        debug_assert!(matches!(
+            (&feature.geometry, self.layer),
+            (Geometry::LineString(_), ExportLayer::Edges)
+                | (Geometry::Point(_), ExportLayer::Nodes)
+        ));
+
+        let mut properties = Vec::new();
+        for (index, (value, (_, column_type))) in
+            feature.properties.iter().zip(self.columns).enumerate()
+        {
+            properties.extend((index as u16).to_le_bytes());
+            match (value, column_type) {
+                (Value::Bool(value), ColumnType::Bool) => properties.push(u8::from(*value)),
+                (Value::U32(value), ColumnType::U32) => properties.extend(value.to_le_bytes()),
+                (Value::U64(value), ColumnType::U64) => properties.extend(value.to_le_bytes()),
+                (Value::String(value), ColumnType::String) => {
+                    properties.extend((value.len() as u32).to_le_bytes());
+                    properties.extend(value.as_bytes());
+                }
+                _ => unreachable!("Property value doesn't match the column type"),
+            }
+        }
+
+        self.features.push(Builder::finish(Table(vec![
+            (0, Field::Table(Table(vec![(1, Field::F64s(xy))]))),
+            (1, Field::Bytes(properties)),
+        ])));
+        Ok(())
+    }
+
+    fn end(&mut self) -> io::Result<()> {
+        self.out.write_all(MAGIC)?;
+        let header = self.header();
+        self.out.write_all(&(header.len() as u32).to_le_bytes())?;
+        self.out.write_all(&header)?;
+        for feature in &self.features {
+            self.out.write_all(&(feature.len() as u32).to_le_bytes())?;
+            self.out.write_all(feature)?;
+        }
+        Ok(())
+    }
+}
+
+const GEOMETRY_POINT: u8 = 1;
+const GEOMETRY_LINE_STRING: u8 = 2;
+
+impl ColumnType {
+    /// Value of the `ColumnType` enum in the FlatGeobuf schema.
+    fn code(self) -> u8 {
+        match self {
+            ColumnType::Bool => 2,
+            ColumnType::U32 => 6,
+            ColumnType::U64 => 8,
+            ColumnType::String => 11,
+        }
+    }
+}
+
+/// FlatBuffers table as a list of `(field index, value)` pairs.
+struct Table(Vec<(u16, Field)>);
+
+enum Field {
+    U8(u8),
+    U16(u16),
+    I32(i32),
+    U64(u64),
+    String(&'static str),
+    Bytes(Vec<u8>),
+    F64s(Vec<f64>),
+    Table(Table),
+    Tables(Vec<Table>),
+}
+
+impl Field {
+    /// Size and alignment of the value stored inline in the table, offsets for everything else.
+    fn inline_size(&self) -> usize {
+        match self {
+            Field::U8(_) => 1,
+            Field::U16(_) => 2,
+            Field::U64(_) => 8,
+            _ => 4,
+        }
+    }
+}
+
+/// Minimal FlatBuffers serializer that writes every table before its children, so all offsets point forward
+/// like the format requires, and vtables right before their tables.
+struct Builder {
+    buf: Vec<u8>,
+}
+
+impl Builder {
+    /// Serializes the root table into a buffer starting with the root offset.
+    fn finish(root: Table) -> Vec<u8> {
+        let mut builder = Builder { buf: vec![0; 4] };
+        let position = builder.table(root);
+        builder.patch(0, position);
+        builder.buf
+    }
+
+    fn table(&mut self, table: Table) -> usize {
+        let Table(mut fields) = table;
+        // Largest values first to avoid padding between fields
+        fields.sort_by_key(|(_, field)| std::cmp::Reverse(field.inline_size()));
+        let alignment = fields
+            .iter()
+            .map(|(_, field)| field.inline_size())
+            .max()
+            .unwrap_or(4)
+            .max(4);
+
+        // Layout of the table, starting with the vtable offset
+        let mut offsets = Vec::with_capacity(fields.len());
+        let mut size: usize = 4;
+        for (_, field) in &fields {
+            let field_size = field.inline_size();
+            size = size.next_multiple_of(field_size);
+            offsets.push(size);
+            size += field_size;
+        }
+
+        // Vtable goes right before the table, so the table has to be aligned after it
+        let slots = fields
+            .iter()
+            .map(|&(index, _)| index as usize + 1)
+            .max()
+            .unwrap_or(0);
+        let vtable_size = 4 + 2 * slots;
+        self.align(2);
+        while (self.buf.len() + vtable_size) % alignment != 0 {
+            self.buf.push(0);
+        }
+        let vtable = self.buf.len();
+        let mut slot_offsets = vec![0u16; slots];
+        for ((index, _), offset) in fields.iter().zip(&offsets) {
+            slot_offsets[*index as usize] = *offset as u16;
+        }
+        self.buf.extend((vtable_size as u16).to_le_bytes());
+        self.buf.extend((size as u16).to_le_bytes());
+        for offset in slot_offsets {
+            self.buf.extend(offset.to_le_bytes());
+        }
+
+        let start = self.buf.len();
+        self.buf.resize(start + size, 0);
+        self.buf[start..start + 4].copy_from_slice(&((start - vtable) as i32).to_le_bytes());
+        let mut children = Vec::new();
+        for ((_, field), offset) in fields.into_iter().zip(offsets) {
+            let position = start + offset;
+            match field {
+                Field::U8(value) => self.buf[position] = value,
+                Field::U16(value) => self.write_at(position, &value.to_le_bytes()),
+                Field::I32(value) => self.write_at(position, &value.to_le_bytes()),
+                Field::U64(value) => self.write_at(position, &value.to_le_bytes()),
+                child => children.push((position, child)),
+            }
+        }
+
+        for (position, child) in children {
+            let target = self.child(child);
+            self.patch(position, target);
+        }
+        start
+    }
+
+    /// Writes a non-inline value and returns its position.
+    fn child(&mut self, field: Field) -> usize {
+        match field {
+            Field::String(value) => {
+                let position = self.vector(value.len(), 4);
+                self.buf.extend(value.as_bytes());
+                self.buf.push(0);
+                position
+            }
+            Field::Bytes(value) => {
+                let position = self.vector(value.len(), 4);
+                self.buf.extend(value);
+                position
+            }
+            Field::F64s(values) => {
+                let position = self.vector(values.len(), 8);
+                for value in values {
+                    self.buf.extend(value.to_le_bytes());
+                }
+                position
+            }
+            Field::Table(table) => self.table(table),
+            Field::Tables(tables) => {
+                let position = self.vector(tables.len(), 4);
+                let first = self.buf.len();
+                self.buf.resize(first + 4 * tables.len(), 0);
+                for (i, table) in tables.into_iter().enumerate() {
+                    let target = self.table(table);
+                    self.patch(first + 4 * i, target);
+                }
+                position
+            }
+            Field::U8(_) | Field::U16(_) | Field::I32(_) | Field::U64(_) => {
+                unreachable!("Scalars are stored inline")
+            }
+        }
+    }
+
+    /// Writes the vector length so that the elements after it are aligned.
+    fn vector(&mut self, len: usize, alignment: usize) -> usize {
+        while (self.buf.len() + 4) % alignment != 0 {
+            self.buf.push(0);
+        }
+        let position = self.buf.len();
+        self.buf.extend((len as u32).to_le_bytes());
+        position
+    }
+
+    fn align(&mut self, alignment: usize) {
+        while self.buf.len() % alignment != 0 {
+            self.buf.push(0);
+        }
+    }
+
+    fn write_at(&mut self, position: usize, bytes: &[u8]) {
+        self.buf[position..position + bytes.len()].copy_from_slice(bytes);
+    }
+
+    /// Stores the offset from `position` to `target` at `position`.
+    fn patch(&mut self, position: usize, target: usize) {
+        self.write_at(position, &((target - position) as u32).to_le_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LatLon;
+
+    /// Reads FlatBuffers tables written by [`Builder`].
+    #[derive(Clone, Copy)]
+    struct TableRef<'a> {
+        buf: &'a [u8],
+        position: usize,
+    }
+
+    impl<'a> TableRef<'a> {
+        fn root(buf: &'a [u8]) -> Self {
+            Self {
+                buf,
+                position: read_u32(buf, 0) as usize,
+            }
+        }
+
+        /// Position of the field value, `None` if the field is absent.
+        fn field(&self, index: usize) -> Option<usize> {
+            let soffset = i32::from_le_bytes(
+                self.buf[self.position..self.position + 4]
+                    .try_into()
+                    .unwrap(),
+            );
+            let vtable = (self.position as i64 - i64::from(soffset)) as usize;
+            let vtable_size = read_u16(self.buf, vtable) as usize;
+            let slot = 4 + 2 * index;
+            if slot >= vtable_size {
+                return None;
+            }
+            let offset = read_u16(self.buf, vtable + slot) as usize;
+            (offset != 0).then_some(self.position + offset)
+        }
+
+        fn u8(&self, index: usize) -> u8 {
+            self.field(index).map_or(0, |position| self.buf[position])
+        }
+
+        fn u16(&self, index: usize) -> Option<u16> {
+            self.field(index)
+                .map(|position| read_u16(self.buf, position))
+        }
+
+        fn u64(&self, index: usize) -> u64 {
+            self.field(index).map_or(0, |position| {
+                u64::from_le_bytes(self.buf[position..position + 8].try_into().unwrap())
+            })
+        }
+
+        /// Position of the referenced object.
+        fn target(&self, index: usize) -> usize {
+            let position = self.field(index).unwrap();
+            position + read_u32(self.buf, position) as usize
+        }
+
+        fn table(&self, index: usize) -> TableRef<'a> {
+            TableRef {
+                buf: self.buf,
+                position: self.target(index),
+            }
+        }
+
+        fn bytes(&self, index: usize) -> &'a [u8] {
+            let position = self.target(index);
+            let len = read_u32(self.buf, position) as usize;
+            &self.buf[position + 4..position + 4 + len]
+        }
+
+        fn string(&self, index: usize) -> &'a str {
+            std::str::from_utf8(self.bytes(index)).unwrap()
+        }
+
+        fn f64s(&self, index: usize) -> Vec<f64> {
+            let position = self.target(index);
+            let len = read_u32(self.buf, position) as usize;
+            (0..len)
+                .map(|i| {
+                    let start = position + 4 + 8 * i;
+                    f64::from_le_bytes(self.buf[start..start + 8].try_into().unwrap())
+                })
+                .collect()
+        }
+
+        fn tables(&self, index: usize) -> Vec<TableRef<'a>> {
+            let position = self.target(index);
+            let len = read_u32(self.buf, position) as usize;
+            (0..len)
+                .map(|i| {
+                    let offset = position + 4 + 4 * i;
+                    TableRef {
+                        buf: self.buf,
+                        position: offset + read_u32(self.buf, offset) as usize,
+                    }
+                })
+                .collect()
+        }
+    }
+
+    fn read_u16(buf: &[u8], position: usize) -> u16 {
+        u16::from_le_bytes(buf[position..position + 2].try_into().unwrap())
+    }
+
+    fn read_u32(buf: &[u8], position: usize) -> u32 {
+        u32::from_le_bytes(buf[position..position + 4].try_into().unwrap())
+    }
+
+    /// Splits the buffer into a size-prefixed FlatBuffer and the rest.
+    fn size_prefixed(buf: &[u8]) -> (&[u8], &[u8]) {
+        let len = read_u32(buf, 0) as usize;
+        (&buf[4..4 + len], &buf[4 + len..])
+    }
+
+    #[test]
+    fn round_trip() {
+        const COLUMNS: Columns = &[
+            ("id", ColumnType::String),
+            ("length", ColumnType::U32),
+            ("way_id", ColumnType::U64),
+            ("shortcut", ColumnType::Bool),
+        ];
+        let mut out = Vec::new();
+        let mut writer = Writer::new(&mut out, ExportLayer::Edges, COLUMNS);
+        writer.begin().unwrap();
+        writer
+            .feature(&Feature {
+                geometry: Geometry::LineString(vec![LatLon(42.5, 1.5), LatLon(42.6, 1.4)]),
+                properties: vec![
+                    Value::String("2/762485/7".to_string()),
+                    Value::U32(120),
+                    Value::U64(1 << 40),
+                    Value::Bool(true),
+                ],
+            })
+            .unwrap();
+        writer
+            .feature(&Feature {
+                geometry: Geometry::LineString(vec![LatLon(42.4, 1.6), LatLon(42.5, 1.5)]),
+                properties: vec![
+                    Value::String("2/762485/8".to_string()),
+                    Value::U32(7),
+                    Value::U64(2),
+                    Value::Bool(false),
+                ],
+            })
+            .unwrap();
+        writer.end().unwrap();
+
+        assert_eq!(&out[..8], MAGIC);
+        let (header, features) = size_prefixed(&out[8..]);
+        let header = TableRef::root(header);
+        assert_eq!(header.string(0), "edges");
+        assert_eq!(header.f64s(1), [1.4, 42.4, 1.6, 42.6]);
+        assert_eq!(header.u8(2), GEOMETRY_LINE_STRING);
+        let columns: Vec<(&str, u8)> = header
+            .tables(7)
+            .iter()
+            .map(|column| (column.string(0), column.u8(1)))
+            .collect();
+        assert_eq!(
+            columns,
+            [("id", 11), ("length", 6), ("way_id", 8), ("shortcut", 2)]
+        );
+        assert_eq!(header.u64(8), 2);
+        assert_eq!(header.u16(9), Some(0));
+        assert_eq!(
+            header.table(10).field(1).map(|p| read_u32(header.buf, p)),
+            Some(4326)
+        );
+
+        let (feature, rest) = size_prefixed(features);
+        let feature = TableRef::root(feature);
+        assert_eq!(feature.table(0).f64s(1), [1.5, 42.5, 1.4, 42.6]);
+        let properties = feature.bytes(1);
+        let mut expected = Vec::new();
+        expected.extend(0u16.to_le_bytes());
+        expected.extend(10u32.to_le_bytes());
+        expected.extend(b"2/762485/7");
+        expected.extend(1u16.to_le_bytes());
+        expected.extend(120u32.to_le_bytes());
+        expected.extend(2u16.to_le_bytes());
+        expected.extend((1u64 << 40).to_le_bytes());
+        expected.extend(3u16.to_le_bytes());
+        expected.push(1);
+        assert_eq!(properties, expected);
+
+        let (feature, rest) = size_prefixed(rest);
+        assert_eq!(
+            TableRef::root(feature).table(0).f64s(1),
+            [1.6, 42.4, 1.5, 42.5]
+        );
+        assert!(rest.is_empty());
+    }
+}
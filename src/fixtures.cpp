@@ -0,0 +1,80 @@
+#include "fixtures.hpp"
+
+#include <algorithm>
+#include <iterator>
+#include <string>
+
+#include <valhalla/baldr/graphtileheader.h>
+#include <valhalla/baldr/tilehierarchy.h>
+#include <valhalla/baldr/transitdeparture.h>
+#include <valhalla/baldr/transitroute.h>
+#include <valhalla/baldr/transitschedule.h>
+#include <valhalla/baldr/transitstop.h>
+#include <valhalla/baldr/transittransfer.h>
+
+namespace baldr = valhalla::baldr;
+
+rust::Vec<uint8_t> transit_test_tile(GraphId id) {
+  // Names are offsets into the text list, which starts with an empty name like in tiles built by Valhalla
+  std::string textlist(1, '\0');
+  auto name = [&](const std::string& text) {
+    const auto offset = static_cast<uint32_t>(textlist.size());
+    textlist += text;
+    textlist += '\0';
+    return offset;
+  };
+
+  const baldr::TransitDeparture departures[] = {
+    baldr::TransitDeparture(7, 100, 0, 3, name("Sant Julià"), 8 * 3600, 120, 0, true, false),
+    baldr::TransitDeparture(7, 101, 0, 3, name("Encamp"), 9 * 3600, 12 * 3600, 900, 150, 1, false, true),
+  };
+  const baldr::TransitStop stops[] = {
+    baldr::TransitStop(name("s-sp91-placa"), name("Plaça"), false, 3),
+    baldr::TransitStop(name("s-sp91-estacio"), name("Estació"), true, 1),
+  };
+  const baldr::TransitRoute routes[] = {
+    baldr::TransitRoute(baldr::TransitType::kBus, name("r-sp91-l1"), name("o-sp91-bus"), name("Bus Andorra"),
+                        name("https://bus.example"), 0x0000ff, 0xffffff, name("L1"), name("Línia 1"),
+                        name("Andorra la Vella to Encamp")),
+  };
+  const baldr::TransitSchedule schedules[] = {
+    baldr::TransitSchedule(0b1011, 0b0111110, 3),
+    baldr::TransitSchedule(0b1, 0b1000001, 0),
+  };
+  const baldr::TransitTransfer transfers[] = {
+    baldr::TransitTransfer(0, 1, baldr::TransferType::kMinTime, 240),
+  };
+
+  baldr::GraphTileHeader header;
+  header.set_graphid(id.Tile_Base());
+  header.set_base_ll(baldr::TileHierarchy::get_tiling(id.level()).Base(id.tileid()));
+  header.set_departurecount(std::size(departures));
+  header.set_stopcount(std::size(stops));
+  header.set_routecount(std::size(routes));
+  header.set_schedulecount(std::size(schedules));
+  header.set_transfercount(std::size(transfers));
+
+  // Transit records are the only fixed-size records, followed by the text list and no other variable-size data
+  const size_t records = sizeof(header) + sizeof(departures) + sizeof(stops) + sizeof(routes) + sizeof(schedules) +
+                         sizeof(transfers);
+  header.set_complex_restriction_forward_offset(records);
+  header.set_complex_restriction_reverse_offset(records);
+  header.set_edgeinfo_offset(records);
+  header.set_textlist_offset(records);
+  header.set_lane_connectivity_offset(records + textlist.size());
+  header.set_end_offset(records + textlist.size());
+
+  rust::Vec<uint8_t> result;
+  auto append = [&](const void* data, size_t size) {
+    const auto* bytes = static_cast<const uint8_t*>(data);
+    std::copy(bytes, bytes + size, std::back_inserter(result));
+  };
+  append(&header, sizeof(header));
+  append(departures, sizeof(departures));
+  append(stops, sizeof(stops));
+  append(routes, sizeof(routes));
+  append(schedules, sizeof(schedules));
+  append(transfers, sizeof(transfers));
+  append(textlist.data(), textlist.size());
+  return result;
+}
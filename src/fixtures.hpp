@@ -0,0 +1,11 @@
+#pragma once
+
+#include <valhalla/baldr/graphid.h>
+
+#include "cxx.h"
+
+// `GraphId` is shared with `lib.rs` bridge where it is bound to `valhalla::baldr::GraphId`.
+using GraphId = valhalla::baldr::GraphId;
+
+/// Transit level tile data with a few records of every transit kind and no roads, for tests of transit helpers
+rust::Vec<uint8_t> transit_test_tile(GraphId id);
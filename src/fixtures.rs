@@ -0,0 +1,12 @@
+/// Synthetic tiles for tests of tile sections that the Andorra extract doesn't have.
+/// Compiled only with the `test-fixtures` feature to keep them out of the library.
+#[cxx::bridge]
+pub(crate) mod ffi {
+    unsafe extern "C++" {
+        include!("valhalla/src/fixtures.hpp");
+
+        type GraphId = crate::GraphId;
+
+        fn transit_test_tile(id: GraphId) -> Vec<u8>;
+    }
+}
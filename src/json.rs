@@ -0,0 +1,156 @@
+use std::{fmt, path::Path};
+
+/// Minimal JSON document model, enough to generate Valhalla configs without a serialization framework.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Json {
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    /// Object with keys in insertion order.
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Empty JSON object to be filled with [`Json::with()`].
+    pub(crate) fn object() -> Self {
+        Json::Object(Vec::new())
+    }
+
+    /// Appends a `key` to the object. Does nothing for non-object values.
+    pub(crate) fn with(mut self, key: &str, value: impl Into<Json>) -> Self {
+        if let Json::Object(fields) = &mut self {
+            fields.push((key.to_string(), value.into()));
+        }
+        self
+    }
+
+    /// Same as [`Json::with()`], but skips the `key` entirely if `value` is `None`.
+    pub(crate) fn with_opt<T: Into<Json>>(self, key: &str, value: Option<T>) -> Self {
+        match value {
+            Some(value) => self.with(key, value),
+            None => self,
+        }
+    }
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Self {
+        Json::Bool(value)
+    }
+}
+
+impl From<f64> for Json {
+    fn from(value: f64) -> Self {
+        Json::Number(value)
+    }
+}
+
+impl From<u32> for Json {
+    fn from(value: u32) -> Self {
+        Json::Number(value as f64)
+    }
+}
+
+impl From<u64> for Json {
+    fn from(value: u64) -> Self {
+        Json::Number(value as f64)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Json::String(value.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(value: String) -> Self {
+        Json::String(value)
+    }
+}
+
+/// Valhalla reads paths from the config as UTF-8 strings, so non-UTF-8 parts are replaced.
+impl From<&Path> for Json {
+    fn from(value: &Path) -> Self {
+        Json::String(value.to_string_lossy().into_owned())
+    }
+}
+
+impl<T: Into<Json>> From<Vec<T>> for Json {
+    fn from(values: Vec<T>) -> Self {
+        Json::Array(values.into_iter().map(Into::into).collect())
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Bool(value) => write!(f, "{value}"),
+            Json::Number(value) if value.is_finite() => write!(f, "{value}"),
+            Json::Number(_) => f.write_str("null"), // JSON has no representation for NaN and infinities
+            Json::String(value) => write_string(f, value),
+            Json::Array(values) => {
+                f.write_str("[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{value}")?;
+                }
+                f.write_str("]")
+            }
+            Json::Object(fields) => {
+                f.write_str("{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{value}")?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in value.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{c}")?,
+        }
+    }
+    f.write_str("\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_string() {
+        let json = Json::object()
+            .with("path", Path::new(r#"C:\tiles "v2"/tiles.tar"#))
+            .with("size", 1000000000u64)
+            .with("sigma_z", 4.07)
+            .with("verbose", false)
+            .with("actions", vec!["locate", "route"])
+            .with_opt("tile_dir", None::<&str>)
+            .with("empty", Json::object())
+            .with("nan", f64::NAN)
+            .with("control", "\u{1}\n");
+        assert_eq!(
+            json.to_string(),
+            r#"{"path":"C:\\tiles \"v2\"/tiles.tar","size":1000000000,"sigma_z":4.07,"verbose":false,"actions":["locate","route"],"empty":{},"nan":null,"control":"\u0001\n"}"#
+        );
+        assert_eq!(Json::Array(vec![]).to_string(), "[]");
+    }
+}
@@ -0,0 +1,43 @@
+use crate::ffi;
+
+/// Lanes of an edge that can be reached from lanes of an incoming way, see [`crate::GraphTile::lane_connectivity()`].
+/// Lanes are numbered from 1, starting with the leftmost lane, like in the `connectivity` OSM relation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LaneConnectivity {
+    /// OSM Way ID of the incoming way.
+    pub from_way_id: u64,
+    /// Lanes of the incoming way.
+    pub from_lanes: Vec<u8>,
+    /// Lanes of the edge, in the same order as `from_lanes`.
+    pub to_lanes: Vec<u8>,
+}
+
+impl From<&ffi::LaneConnectivityEntry> for LaneConnectivity {
+    fn from(entry: &ffi::LaneConnectivityEntry) -> Self {
+        Self {
+            from_way_id: entry.from_way_id,
+            from_lanes: parse_lanes(&entry.from_lanes),
+            to_lanes: parse_lanes(&entry.to_lanes),
+        }
+    }
+}
+
+/// Parses lanes stored as text like `1|2`.
+fn parse_lanes(lanes: &str) -> Vec<u8> {
+    lanes
+        .split('|')
+        .filter_map(|lane| lane.trim().parse().ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lanes() {
+        assert_eq!(parse_lanes("1|2|3"), vec![1, 2, 3]);
+        assert_eq!(parse_lanes("2"), vec![2]);
+        assert_eq!(parse_lanes(""), Vec::<u8>::new());
+    }
+}
@@ -1,24 +1,90 @@
 use std::{
     fmt,
     hash::{Hash, Hasher},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use bitflags::bitflags;
 
+use loader::{TileBuffer, TileSource, load_tile};
+
+mod access;
 mod actor;
+mod cache;
 mod config;
-
+mod connectivity;
+mod diff;
+mod export;
+#[cfg(all(test, feature = "test-fixtures"))]
+mod fixtures;
+mod json;
+mod lanes;
+mod loader;
+mod openlr;
+#[cfg(feature = "rayon")]
+mod par;
+mod reload;
+mod stats;
+mod verify;
+mod ways;
+
+pub use access::{AccessRestriction, RestrictionValue};
 pub use actor::Actor;
+pub use actor::ExpansionEdge;
+pub use actor::MatchCandidate;
+pub use actor::MatchSegment;
+pub use actor::MatchedPath;
+pub use actor::MatchedPoint;
 pub use actor::Response;
 pub use actor::proto;
+pub use cache::{CacheStats, CachedGraphReader};
 pub use config::Config;
+pub use config::ConfigEntry;
+pub use config::{
+    ConfigBuilder, CostingLimits, HierarchyLimits, LokiConfig, LokiServiceDefaults, MeiliConfig,
+    MeiliModeParameters, MeiliParameters, MjolnirConfig, OdinConfig, ServiceLimits, ThorConfig,
+};
+pub use config::{ConfigIssue, ValueKind};
+pub use config::{ConfigLoader, ConfigReport, ConfigSource};
+pub use connectivity::{ConnectivityReport, GraphConnectivity, Island, TileConnectivity};
+pub use diff::{
+    AttributeChange, EdgeChange, EdgeDiff, GraphDiff, GraphDiffReport, TileDiffSummary,
+};
+pub use export::{ExportFormat, ExportLayer, GraphExport};
+pub use ffi::AdminInfo;
+pub use ffi::ComplexRestriction;
 pub use ffi::DirectedEdge;
 pub use ffi::EdgeInfo;
 pub use ffi::EdgeUse;
 pub use ffi::GraphId;
 pub use ffi::GraphLevel;
 pub use ffi::NodeInfo;
+pub use ffi::NodeType;
+pub use ffi::RestrictionType;
+pub use ffi::RoadClass;
+pub use ffi::Sign;
+pub use ffi::SignType;
+pub use ffi::TileHeader;
+pub use ffi::TimeDomain;
 pub use ffi::TimeZoneInfo;
+pub use ffi::TransitDeparture;
+pub use ffi::TransitRoute;
+pub use ffi::TransitSchedule;
+pub use ffi::TransitStop;
+pub use ffi::TransitTransfer;
+pub use ffi::TransitType;
+pub use lanes::LaneConnectivity;
+pub use loader::{TileDir, TileLoader};
+pub use openlr::{
+    EdgeSpan, FormOfWay, LineLocation, LineLocationReference, LocationReferencePoint, OpenLrDecoder,
+};
+#[cfg(feature = "rayon")]
+pub use par::{EdgeRef, GraphFilter, NodeRef};
+pub use reload::{ReloadOutcome, ReloadWatcher, ReloadableActor, ReloadableGraphReader};
+pub use stats::GraphStats;
+pub use verify::{TileIssue, VerifyReport};
+pub use ways::WayIndex;
 
 #[cxx::bridge]
 mod ffi {
@@ -38,6 +104,7 @@ mod ffi {
     }
 
     // Edge use type. Indicates specialized uses.
+    #[derive(Debug, Hash)]
     #[namespace = "valhalla::baldr"]
     #[cxx_name = "Use"]
     #[repr(u8)]
@@ -94,6 +161,173 @@ mod ffi {
         kTransitConnection = 54,  // Connection between road network and transit egress
     }
 
+    /// Road class or importance of an edge, from the most important to the least.
+    #[derive(Debug, Hash)]
+    #[namespace = "valhalla::baldr"]
+    #[repr(u8)]
+    enum RoadClass {
+        kMotorway = 0,
+        kTrunk = 1,
+        kPrimary = 2,
+        kSecondary = 3,
+        kTertiary = 4,
+        kUnclassified = 5,
+        kResidential = 6,
+        kServiceOther = 7,
+    }
+
+    /// Type of the node, e.g. a regular intersection or a barrier.
+    #[derive(Debug)]
+    #[namespace = "valhalla::baldr"]
+    #[repr(u8)]
+    enum NodeType {
+        kStreetIntersection = 0,      // Regular intersection of 2 or more ways
+        kGate = 1,                    // Gate or rising bollard
+        kBollard = 2,                 // Bollard (fixed obstruction)
+        kTollBooth = 3,               // Toll booth / fare collection
+        kTransitEgress = 4,           // Transit egress
+        kTransitStation = 5,          // Transit station
+        kMultiUseTransitPlatform = 6, // Multi-use transit platform (rail and bus)
+        kBikeShare = 7,               // Bike share location
+        kParking = 8,                 // Parking location
+        kMotorWayJunction = 9,        // Highway = motorway_junction
+        kBorderControl = 10,          // Border control
+        kTollGantry = 11,             // Toll gantry
+        kSumpBuster = 12,             // Sump Buster
+        kBuildingEntrance = 13,       // Building entrance
+        kElevator = 14,               // Elevator
+    }
+
+    /// Type of a turn restriction.
+    #[derive(Debug)]
+    #[namespace = "valhalla::baldr"]
+    #[repr(u8)]
+    enum RestrictionType {
+        kNoLeftTurn = 0,
+        kNoRightTurn = 1,
+        kNoStraightOn = 2,
+        kNoUTurn = 3,
+        kOnlyRightTurn = 4,
+        kOnlyLeftTurn = 5,
+        kOnlyStraightOn = 6,
+        kNoEntry = 7,
+        kNoExit = 8,
+        kNoTurn = 9,
+        kNoProbable = 10,   // Restriction that is likely but not confirmed
+        kOnlyProbable = 11, // Mandatory turn that is likely but not confirmed
+    }
+
+    /// Kind of an access restriction, which defines how its value is interpreted.
+    #[derive(Debug)]
+    #[namespace = "valhalla::baldr"]
+    #[repr(u8)]
+    enum AccessType {
+        kHazmat = 0,
+        kMaxHeight = 1,
+        kMaxWidth = 2,
+        kMaxLength = 3,
+        kMaxWeight = 4,
+        kMaxAxleLoad = 5,
+        kTimedAllowed = 6,
+        kTimedDenied = 7,
+        kDestinationAllowed = 8,
+        kMaxAxles = 9,
+    }
+
+    /// Raw access restriction of an edge, converted to [`crate::AccessRestriction`].
+    struct AccessRestrictionEntry {
+        access_type: AccessType,
+        modes: u16,
+        value: u64,
+        except_destination: bool,
+    }
+
+    /// Type of a sign, see [`crate::Sign`].
+    #[derive(Debug)]
+    #[repr(u8)]
+    enum SignType {
+        kExitNumber = 0,
+        kExitBranch = 1,   // Route numbers or names of the roads the exit leads to
+        kExitToward = 2,   // Destinations the exit leads to
+        kExitName = 3,     // Name of the exit, e.g. a local name of an interchange
+        kGuideBranch = 4,  // Route numbers or names of the roads on a guide sign, not at an exit
+        kGuideToward = 5,  // Destinations on a guide sign, not at an exit
+        kJunctionName = 6, // Name of a named intersection, stored on the node
+        kGuidanceViewJunction = 7, // Guidance view image at junctions
+        kGuidanceViewSignboard = 8, // Guidance view image of a signboard
+        kTollName = 9,
+        kLinguistic = 255, // Pronunciation of another sign, never returned on its own
+    }
+
+    /// Exit or guide sign of an edge, or name of a named intersection.
+    #[derive(Clone, Debug)]
+    struct Sign {
+        sign_type: SignType,
+        text: String,
+        /// Whether the text is a route number, e.g. "A1", rather than a name.
+        is_route_num: bool,
+        /// Whether the text is tagged, e.g. with a tag describing the kind of the name.
+        is_tagged: bool,
+        /// Whether pronunciation is available, see [`crate::Sign::pronunciation()`].
+        has_linguistic: bool,
+        /// Phonetic alphabet of the pronunciation, `baldr::PronunciationAlphabet` value.
+        phonetic_alphabet: u8,
+        /// Language of the text, `baldr::Language` value.
+        language: u8,
+        pronunciation: String,
+    }
+
+    /// Raw lane connectivity record of an edge, converted to [`crate::LaneConnectivity`].
+    struct LaneConnectivityEntry {
+        from_way_id: u64,
+        from_lanes: String,
+        to_lanes: String,
+    }
+
+    /// Recurring time period when a restriction applies, e.g. "Mo-Fr 07:00-09:00".
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    struct TimeDomain {
+        /// Whether `begin_day_dow`/`end_day_dow` are days of week (1 = Sunday) in `begin_week`/`end_week`
+        /// of the month, rather than days of month.
+        nth_dow: bool,
+        /// Days of week the period applies to as a bit mask, Sunday = 1 to Saturday = 64. Zero means every day.
+        dow: u8,
+        /// Month from 1 to 12, 0 if not set.
+        begin_month: u8,
+        begin_day_dow: u8,
+        begin_week: u8,
+        begin_hrs: u8,
+        begin_mins: u8,
+        end_month: u8,
+        end_day_dow: u8,
+        end_week: u8,
+        end_hrs: u8,
+        end_mins: u8,
+    }
+
+    /// Turn restriction over a sequence of edges, e.g. no U-turn via a short edge between dual carriageways.
+    #[derive(Clone, Debug)]
+    struct ComplexRestriction {
+        /// Edge the restricted path starts with.
+        from: GraphId,
+        /// Edge the restricted path ends with.
+        to: GraphId,
+        /// Edges between `from` and `to` in order, empty for restrictions via a single node.
+        vias: Vec<GraphId>,
+        restriction_type: RestrictionType,
+        /// Access modes the restriction applies to. Bit mask using [`crate::Access`] constants.
+        modes: u16,
+        /// Whether the restriction applies only during `time`, see [`crate::ComplexRestriction::time_domain()`].
+        has_time: bool,
+        time: TimeDomain,
+    }
+
+    /// Coordinates of a node, converted to [`crate::LatLon`].
+    struct NodeLatLon {
+        lat: f64,
+        lon: f64,
+    }
+
     /// Directed edge within the graph.
     struct DirectedEdge {
         // With this definition and cxx's magic it becomes possible to do pointer arithmetic properly,
@@ -142,6 +376,148 @@ mod ffi {
         len: usize,
     }
 
+    /// Country and state of a node, used e.g. for driving side and border crossings.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    struct AdminInfo {
+        /// ISO 3166-1 alpha-2 code of the country, e.g. "AD". Empty if not known.
+        country_iso: String,
+        /// Name of the country.
+        country_text: String,
+        /// ISO 3166-2 code of the state within the country. Empty if not known.
+        state_iso: String,
+        /// Name of the state.
+        state_text: String,
+    }
+
+    /// Vehicle type of a transit route.
+    #[derive(Debug)]
+    #[namespace = "valhalla::baldr"]
+    #[repr(u8)]
+    enum TransitType {
+        kTram = 0,
+        kMetro = 1,
+        kRail = 2,
+        kBus = 3,
+        kFerry = 4,
+        kCableCar = 5,
+        kGondola = 6,
+        kFunicular = 7,
+    }
+
+    /// Transit stop or station of a tile, linked from nodes of transit level tiles.
+    #[derive(Clone, Debug)]
+    struct TransitStop {
+        /// Onestop ID of the stop, e.g. "s-u281z9rjvu-alexanderplatz".
+        onestop_id: String,
+        name: String,
+        /// Whether the stop was generated by Valhalla rather than read from the feed.
+        generated: bool,
+        /// Directions the stop can be traversed in, `baldr::Traversability` value.
+        traversability: u8,
+    }
+
+    /// Departure of a trip from a stop along a transit line.
+    #[derive(Clone, Debug)]
+    struct TransitDeparture {
+        /// Transit line, matching [`DirectedEdge`] line IDs of transit edges.
+        line_id: u32,
+        /// Index of the [`TransitRoute`] within the tile.
+        route_index: u32,
+        trip_id: u32,
+        block_id: u32,
+        /// Index of the [`TransitSchedule`] within the tile.
+        schedule_index: u32,
+        headsign: String,
+        /// Departure time in seconds from midnight.
+        departure_time: u32,
+        /// Time to the next stop in seconds.
+        elapsed_time: u32,
+        /// Whether the departure repeats every `frequency` seconds until `end_time`, rather than happens once.
+        frequency_based: bool,
+        end_time: u32,
+        frequency: u32,
+        wheelchair_accessible: bool,
+        bicycle_accessible: bool,
+    }
+
+    /// Transit route of a tile with its operator.
+    #[derive(Clone, Debug)]
+    struct TransitRoute {
+        route_type: TransitType,
+        onestop_id: String,
+        operator_onestop_id: String,
+        operator_name: String,
+        operator_website: String,
+        /// Route color as 0xRRGGBB.
+        color: u32,
+        /// Route text color as 0xRRGGBB.
+        text_color: u32,
+        short_name: String,
+        long_name: String,
+        description: String,
+    }
+
+    /// Days when transit departures referencing the schedule are operated.
+    #[derive(Clone, Copy, Debug)]
+    struct TransitSchedule {
+        /// Bit mask of days from the tile creation date, bit 0 is the creation date.
+        days: u64,
+        /// Days of week as a bit mask, Sunday = 1 to Saturday = 64.
+        days_of_week: u8,
+        /// Last day of `days` the schedule is valid for.
+        end_day: u32,
+    }
+
+    /// Transfer between transit stops of a tile.
+    #[derive(Clone, Copy, Debug)]
+    struct TransitTransfer {
+        /// Index of the [`TransitStop`] the transfer starts at.
+        from_stop_index: u32,
+        /// Index of the [`TransitStop`] the transfer ends at.
+        to_stop_index: u32,
+        /// Type of the transfer, `baldr::TransferType` value.
+        transfer_type: u8,
+        /// Minimum time needed for the transfer in seconds.
+        min_time: u32,
+    }
+
+    /// Header of a graph tile with its metadata and numbers of records.
+    #[derive(Clone, Debug)]
+    struct TileHeader {
+        /// Valhalla version the tile was built with, e.g. "3.5.1".
+        version: String,
+        /// See [`crate::GraphReader::dataset_id()`].
+        dataset_id: u64,
+        /// Days since 2014-01-01 when the tile was built, see [`crate::TileHeader::created()`].
+        date_created: u32,
+        /// South-west corner of the tile.
+        base_lat: f64,
+        base_lon: f64,
+        /// Relative road density within the tile, from 0 to 15.
+        density: u32,
+        /// Whether the tile has elevation data for edges.
+        has_elevation: bool,
+        /// Checksum of the tile data.
+        checksum: u64,
+        node_count: u32,
+        directed_edge_count: u32,
+        /// Number of node transitions to other hierarchy levels.
+        transition_count: u32,
+        sign_count: u32,
+        turn_lane_count: u32,
+        access_restriction_count: u32,
+        admin_count: u32,
+        departure_count: u32,
+        stop_count: u32,
+        route_count: u32,
+        schedule_count: u32,
+        transfer_count: u32,
+        /// Offset of the edge info section in the tile data, see [`crate::GraphTile::edgeinfo()`].
+        edgeinfo_offset: u32,
+        /// Offset of the text list section in the tile data, which follows the edge info section.
+        textlist_offset: u32,
+    }
+
     /// Information about the timezone, such as name and offset from UTC.
     #[derive(Clone)]
     struct TimeZoneInfo {
@@ -151,6 +527,27 @@ mod ffi {
         offset_seconds: i32,
     }
 
+    /// Problem with the raw data of a tile, found by [`crate::GraphReader::verify()`].
+    struct TileLayoutIssue {
+        /// Whether the problem is in the traffic tile rather than the graph tile.
+        traffic: bool,
+        message: String,
+    }
+
+    /// Position of a tile within an in-memory tar extract.
+    struct TileEntry {
+        id: GraphId,
+        offset: usize,
+        size: usize,
+    }
+
+    extern "Rust" {
+        type TileSource;
+        type TileBuffer;
+        fn load_tile(source: &TileSource, tile: u64) -> Box<TileBuffer>;
+        fn data(self: &TileBuffer) -> &[u8];
+    }
+
     unsafe extern "C++" {
         include!("valhalla/src/libvalhalla.hpp");
 
@@ -175,6 +572,14 @@ mod ffi {
 
         type TileSet;
         fn new_tileset(config: &ptree) -> Result<SharedPtr<TileSet>>;
+        fn new_tileset_from_memory(
+            buffer: Box<TileBuffer>,
+            entries: &[TileEntry],
+        ) -> SharedPtr<TileSet>;
+        fn new_tileset_from_loader(
+            source: Box<TileSource>,
+            tiles: &[GraphId],
+        ) -> SharedPtr<TileSet>;
         fn tiles(self: &TileSet) -> Vec<GraphId>;
         fn tiles_in_bbox(
             self: &TileSet,
@@ -185,13 +590,16 @@ mod ffi {
             level: GraphLevel,
         ) -> Vec<GraphId>;
         fn get_tile(self: &TileSet, id: GraphId) -> SharedPtr<GraphTile>;
+        fn check_tile(self: &TileSet, id: GraphId) -> Vec<TileLayoutIssue>;
         fn dataset_id(self: &TileSet) -> u64;
 
         type GraphTile;
         fn id(self: &GraphTile) -> GraphId;
+        fn tile_size(tile: &GraphTile) -> usize;
         fn directededges(tile: &GraphTile) -> DirectedEdgeSlice;
         fn directededge(self: &GraphTile, index: usize) -> Result<*const DirectedEdge>;
         fn edgeinfo(tile: &GraphTile, de: &DirectedEdge) -> EdgeInfo;
+        fn edgeinfo_offset(de: &DirectedEdge) -> u32;
         fn nodes(tile: &GraphTile) -> NodeInfoSlice;
         fn node(self: &GraphTile, index: usize) -> Result<*const NodeInfo>;
         unsafe fn IsClosed(self: &GraphTile, de: *const DirectedEdge) -> bool;
@@ -211,6 +619,23 @@ mod ffi {
         #[cxx_name = "Use"]
         type EdgeUse;
 
+        #[namespace = "valhalla::baldr"]
+        type RoadClass;
+
+        #[namespace = "valhalla::baldr"]
+        type NodeType;
+
+        #[namespace = "valhalla::baldr"]
+        type RestrictionType;
+
+        #[namespace = "valhalla::baldr"]
+        type AccessType;
+
+        #[namespace = "valhalla::baldr"]
+        type TransitType;
+
+        type SignType;
+
         #[namespace = "valhalla::baldr"]
         type DirectedEdge;
         /// End node of the directed edge. [`DirectedEdge::leaves_tile()`] returns true if the end node is in a different tile.
@@ -220,6 +645,8 @@ mod ffi {
         fn use_type(self: &DirectedEdge) -> EdgeUse;
         /// Returns the length of the edge in meters.
         fn length(self: &DirectedEdge) -> u32;
+        /// Road class of the edge, e.g. motorway or residential.
+        fn classification(self: &DirectedEdge) -> RoadClass;
         /// Access modes in the forward direction. Bit mask using [`crate::Access`] constants.
         #[rust_name = "forwardaccess_u32"]
         fn forwardaccess(self: &DirectedEdge) -> u32;
@@ -238,6 +665,32 @@ mod ffi {
         fn is_shortcut(self: &DirectedEdge) -> bool;
         /// Does this directed edge end in a different tile.
         fn leaves_tile(self: &DirectedEdge) -> bool;
+        /// Is this edge part of a roundabout.
+        fn roundabout(self: &DirectedEdge) -> bool;
+        /// Index of the edge among the outgoing edges of its start node, used by [`DirectedEdge::restrictions()`].
+        fn localedgeidx(self: &DirectedEdge) -> u32;
+        /// Index of the opposing edge among the outgoing edges of [`DirectedEdge::endnode()`].
+        fn opp_index(self: &DirectedEdge) -> u32;
+        /// Simple turn restrictions at the end node that apply to all vehicles at all times. Bit mask of
+        /// [`DirectedEdge::localedgeidx()`] values of outgoing edges that can't be entered from this edge.
+        fn restrictions(self: &DirectedEdge) -> u32;
+        /// Number of lanes of the edge.
+        fn lanecount(self: &DirectedEdge) -> u32;
+        /// Whether the edge has turn lane indicators, see [`crate::GraphTile::turn_lanes()`].
+        fn turnlanes(self: &DirectedEdge) -> bool;
+        /// Whether the edge has lane connectivity, see [`crate::GraphTile::lane_connectivity()`].
+        fn laneconnectivity(self: &DirectedEdge) -> bool;
+        /// Whether the edge has exit or guide signs, see [`crate::GraphTile::signs()`].
+        fn sign(self: &DirectedEdge) -> bool;
+        /// Access modes that have [`crate::AccessRestriction`]s on this edge.
+        #[rust_name = "access_restriction_u32"]
+        fn access_restriction(self: &DirectedEdge) -> u32;
+        /// Access modes for which this edge starts a [`crate::ComplexRestriction`].
+        #[rust_name = "start_restriction_u32"]
+        fn start_restriction(self: &DirectedEdge) -> u32;
+        /// Access modes for which this edge ends a [`crate::ComplexRestriction`].
+        #[rust_name = "end_restriction_u32"]
+        fn end_restriction(self: &DirectedEdge) -> u32;
 
         #[namespace = "valhalla::baldr"]
         type NodeInfo;
@@ -246,6 +699,35 @@ mod ffi {
         /// Time zone index of the node. Corresponding [`crate::TimeZoneInfo`] can be retrieved
         /// using [`crate::TimeZoneInfo::from_id()`].
         fn timezone(self: &NodeInfo) -> u32;
+        /// Type of the node, e.g. a regular intersection or a barrier.
+        #[cxx_name = "type"]
+        fn node_type(self: &NodeInfo) -> NodeType;
+        /// Index of the first outgoing directed edge of the node within the node's tile.
+        fn edge_index(self: &NodeInfo) -> u32;
+        /// Number of outgoing directed edges of the node, starting at [`NodeInfo::edge_index()`].
+        fn edge_count(self: &NodeInfo) -> u32;
+        /// Index of the [`crate::AdminInfo`] of the node within the node's tile.
+        fn admin_index(self: &NodeInfo) -> u32;
+        /// Whether the node is a named intersection, see [`crate::GraphTile::node_signs()`].
+        fn named_intersection(self: &NodeInfo) -> bool;
+        fn node_latlon(tile: &GraphTile, node: &NodeInfo) -> NodeLatLon;
+        fn node_transitions(tile: &GraphTile, node: &NodeInfo) -> Vec<GraphId>;
+        fn complex_restrictions(tile: &GraphTile) -> Vec<ComplexRestriction>;
+        fn access_restrictions(tile: &GraphTile, de: &DirectedEdge) -> Vec<AccessRestrictionEntry>;
+        fn decode_time_domain(value: u64) -> TimeDomain;
+        fn edge_signs(tile: &GraphTile, de: &DirectedEdge) -> Vec<Sign>;
+        fn node_admin(tile: &GraphTile, node: &NodeInfo) -> Vec<AdminInfo>;
+        fn edge_admin(tile: &GraphTile, de: &DirectedEdge) -> Vec<AdminInfo>;
+        fn admins(tile: &GraphTile) -> Vec<AdminInfo>;
+        fn tile_header(tile: &GraphTile) -> TileHeader;
+        fn transit_stops(tile: &GraphTile) -> Vec<TransitStop>;
+        fn transit_departures(tile: &GraphTile) -> Result<Vec<TransitDeparture>>;
+        fn transit_routes(tile: &GraphTile) -> Vec<TransitRoute>;
+        fn transit_schedules(tile: &GraphTile) -> Vec<TransitSchedule>;
+        fn transit_transfers(tile: &GraphTile) -> Result<Vec<TransitTransfer>>;
+        fn turn_lanes(tile: &GraphTile, de: &DirectedEdge) -> Vec<u16>;
+        fn lane_connectivity(tile: &GraphTile, de: &DirectedEdge) -> Vec<LaneConnectivityEntry>;
+        fn node_signs(tile: &GraphTile, node: &NodeInfo) -> Vec<Sign>;
 
         fn from_id(id: u32, unix_timestamp: u64) -> Result<TimeZoneInfo>;
     }
@@ -306,6 +788,56 @@ impl GraphId {
     pub fn from_parts(level: u32, tileid: u32, id: u32) -> Option<Self> {
         ffi::from_parts(level, tileid, id).ok()
     }
+
+    /// Relative path of the tile file in a tile directory or tar extract, e.g. `2/000/838/852.gph`.
+    /// Tile IDs are zero-padded to the number of digits required by the level and split into groups of 3.
+    pub fn file_suffix(&self) -> String {
+        let tileid = format!(
+            "{:0width$}",
+            self.tileid(),
+            width = tileid_digits(self.level())
+        );
+        let groups: Vec<_> = tileid
+            .as_bytes()
+            .chunks(3)
+            .map(String::from_utf8_lossy)
+            .collect();
+        format!("{}/{}.gph", self.level(), groups.join("/"))
+    }
+
+    /// Parses the tile base GraphId from a tile file path like `2/000/838/852.gph`, ignoring leading directories.
+    pub fn from_file_suffix(path: &str) -> Option<Self> {
+        let mut parts = path.strip_suffix(".gph")?.rsplit('/');
+        let mut groups = Vec::new();
+        let level = loop {
+            let part = parts.next()?;
+            if groups.len() < 3 && part.len() == 3 && part.bytes().all(|b| b.is_ascii_digit()) {
+                groups.push(part);
+            } else {
+                break part.parse::<u32>().ok()?;
+            }
+        };
+        if groups.len() * 3 != tileid_digits(level) {
+            return None;
+        }
+        groups.reverse();
+        Self::from_parts(level, groups.concat().parse().ok()?, 0)
+    }
+}
+
+/// Number of digits in tile IDs of the level: 1 degree tiles of level 1 have up to 64800 IDs, 0.25 degree tiles
+/// of level 2 (and transit level 3 that shares them) up to 1036800.
+fn tileid_digits(level: u32) -> usize {
+    if level < 2 { 6 } else { 9 }
+}
+
+/// Size of tiles of the level in degrees.
+fn tile_size(level: u32) -> f64 {
+    match level {
+        0 => 4.0,
+        1 => 1.0,
+        _ => 0.25,
+    }
 }
 
 /// Represents errors returned by the Valhalla C++ API.
@@ -328,7 +860,7 @@ impl From<cxx::Exception> for Error {
 
 bitflags! {
     /// Access bit field constants. Access in directed edge allows 12 bits.
-    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     pub struct Access: u16 {
         const AUTO = 1;
         const PEDESTRIAN = 2;
@@ -366,6 +898,27 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// Turn indicators of a lane, as painted arrows or `turn:lanes` OSM tags.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct TurnLanes: u16 {
+        /// Lane without indicators.
+        const EMPTY = 0;
+        /// Lane explicitly tagged with no indicators.
+        const NONE = 1;
+        const THROUGH = 2;
+        const SHARP_LEFT = 4;
+        const LEFT = 8;
+        const SLIGHT_LEFT = 16;
+        const SLIGHT_RIGHT = 32;
+        const RIGHT = 64;
+        const SHARP_RIGHT = 128;
+        const REVERSE = 256;
+        const MERGE_TO_LEFT = 512;
+        const MERGE_TO_RIGHT = 1024;
+    }
+}
+
 /// Coordinate in (lat, lon) format.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct LatLon(pub f64, pub f64);
@@ -402,6 +955,36 @@ impl GraphReader {
         Ok(Self(ffi::new_tileset(config.inner())?))
     }
 
+    /// Creates a GraphReader from a tiles tar extract that is already in memory, e.g. downloaded from an
+    /// object storage or embedded into the binary. Tiles reference the buffer directly without copying.
+    /// ```rust
+    /// let data = std::fs::read("path/to/tiles.tar").unwrap_or_default();
+    /// let reader = valhalla::GraphReader::from_bytes(data.into());
+    /// ```
+    pub fn from_bytes(data: Arc<[u8]>) -> Result<Self, Error> {
+        let entries = loader::tar_entries(&data)?;
+        if entries.is_empty() {
+            return Err(Error("No tiles in tile extract".into()));
+        }
+        Ok(Self(ffi::new_tileset_from_memory(
+            Box::new(TileBuffer::new(data)),
+            &entries,
+        )))
+    }
+
+    /// Creates a GraphReader that loads tiles on demand from the given [`TileLoader`].
+    /// The list of available tiles is requested from the loader only once.
+    /// ```rust
+    /// let reader = valhalla::GraphReader::from_loader(valhalla::TileDir::new("path/to/tiles"));
+    /// ```
+    pub fn from_loader(loader: impl TileLoader) -> Self {
+        let tiles = loader.tiles();
+        Self(ffi::new_tileset_from_loader(
+            Box::new(TileSource(Box::new(loader))),
+            &tiles,
+        ))
+    }
+
     /// Graph tile object at given GraphId if it exists in the tileset.
     pub fn get_tile(&self, id: GraphId) -> Option<GraphTile> {
         GraphTile::new(self.0.get_tile(id))
@@ -412,6 +995,69 @@ impl GraphReader {
         self.0.dataset_id()
     }
 
+    /// Edges that can't be entered from the given edge because of simple turn restrictions at its end node,
+    /// see [`DirectedEdge::restrictions()`].
+    pub fn restricted_turns(&self, edge_id: GraphId) -> Vec<GraphId> {
+        let Some(tile) = self.get_tile(edge_id.tile()) else {
+            return Vec::new();
+        };
+        let Some(edge) = tile.directededge(edge_id.id()) else {
+            return Vec::new();
+        };
+        let mask = edge.restrictions();
+        if mask == 0 {
+            return Vec::new();
+        }
+
+        // Outgoing edges on other hierarchy levels share local indices with the edges of the node
+        let end_node = edge.endnode();
+        let mut restricted = Vec::new();
+        let Some(node_tile) = self.get_tile(end_node.tile()) else {
+            return restricted;
+        };
+        let Some(node) = node_tile.node(end_node.id()) else {
+            return restricted;
+        };
+        let mut nodes = vec![end_node];
+        nodes.extend(node_tile.node_transitions(node));
+        for node_id in nodes {
+            let Some(tile) = self.get_tile(node_id.tile()) else {
+                continue;
+            };
+            let Some(node) = tile.node(node_id.id()) else {
+                continue;
+            };
+            for index in node.edge_index()..node.edge_index() + node.edge_count() {
+                let Some(out) = tile.directededge(index) else {
+                    continue;
+                };
+                if !out.is_shortcut() && mask.checked_shr(out.localedgeidx()).unwrap_or(0) & 1 != 0
+                {
+                    restricted.push(tile.edge_id(index));
+                }
+            }
+        }
+        restricted
+    }
+
+    /// Aggregates numbers of nodes and edges and lengths of roads over all tiles, see [`GraphStats`].
+    pub fn stats(&self) -> GraphStats {
+        let mut stats = GraphStats::default();
+        for tile_id in self.tiles() {
+            if let Some(tile) = self.get_tile(tile_id) {
+                stats.add_tile(&tile);
+            }
+        }
+        stats
+    }
+
+    /// Checks integrity of all tiles: sizes and offsets in tile headers, end nodes of directed edges including
+    /// those in other tiles, opposing edges, edge shapes and numbers of edges in traffic tiles. Tiles with broken
+    /// layout are reported without reading their contents.
+    pub fn verify(&self) -> VerifyReport {
+        verify::verify(self)
+    }
+
     /// List all tiles in the tileset.
     pub fn tiles(&self) -> Vec<GraphId> {
         self.0.tiles()
@@ -452,6 +1098,26 @@ impl GraphTile {
         self.tile.id()
     }
 
+    /// Metadata and numbers of records of the tile.
+    pub fn header(&self) -> TileHeader {
+        ffi::tile_header(&self.tile)
+    }
+
+    /// GraphId of the directed edge at `index` in [`GraphTile::directededges()`].
+    pub fn edge_id(&self, index: u32) -> GraphId {
+        GraphId::new(self.id().tile().value | (u64::from(index) << 25))
+    }
+
+    /// GraphId of the node at `index` in [`GraphTile::nodes()`].
+    pub fn node_id(&self, index: u32) -> GraphId {
+        self.edge_id(index) // Nodes and edges share the same id layout
+    }
+
+    /// Size of the tile data in bytes, not including live traffic.
+    pub fn size(&self) -> usize {
+        ffi::tile_size(&self.tile)
+    }
+
     /// Slice of all directed edges in the current tile.
     pub fn directededges(&self) -> &[ffi::DirectedEdge] {
         let slice = ffi::directededges(&self.tile);
@@ -501,6 +1167,120 @@ impl GraphTile {
         }
     }
 
+    /// Coordinates of the node. Nodes store them relative to the tile's base coordinates.
+    pub fn node_latlon(&self, node: &ffi::NodeInfo) -> LatLon {
+        let ll = ffi::node_latlon(&self.tile, node);
+        LatLon(ll.lat, ll.lon)
+    }
+
+    /// Ids of the same node on other hierarchy levels, where the graph continues with edges of other road classes.
+    pub fn node_transitions(&self, node: &ffi::NodeInfo) -> Vec<GraphId> {
+        ffi::node_transitions(&self.tile, node)
+    }
+
+    /// Complex restrictions that end with an edge of this tile. Every restriction of the graph is listed once,
+    /// in the tile of its [`ComplexRestriction::to`] edge.
+    pub fn complex_restrictions(&self) -> impl Iterator<Item = ComplexRestriction> {
+        ffi::complex_restrictions(&self.tile).into_iter()
+    }
+
+    /// Restrictions like maximum height or weight and time-dependent access of the edge from this tile. Empty for
+    /// edges of other tiles.
+    pub fn access_restrictions(&self, de: &ffi::DirectedEdge) -> Vec<AccessRestriction> {
+        if de.access_restriction_u32() == 0 {
+            return Vec::new();
+        }
+        ffi::access_restrictions(&self.tile, de)
+            .iter()
+            .map(AccessRestriction::from)
+            .collect()
+    }
+
+    /// Exit and guide signs of the edge from this tile, e.g. exit number and destinations. Empty for edges of
+    /// other tiles.
+    pub fn signs(&self, de: &ffi::DirectedEdge) -> Vec<Sign> {
+        if !de.sign() {
+            return Vec::new();
+        }
+        ffi::edge_signs(&self.tile, de)
+    }
+
+    /// Signs of the node from this tile, i.e. the name of a named intersection. Empty for nodes of other tiles.
+    pub fn node_signs(&self, node: &ffi::NodeInfo) -> Vec<Sign> {
+        if !node.named_intersection() {
+            return Vec::new();
+        }
+        ffi::node_signs(&self.tile, node)
+    }
+
+    /// Turn indicators of every lane of the edge from this tile, from the leftmost lane to the rightmost. Empty for
+    /// edges of other tiles.
+    pub fn turn_lanes(&self, de: &ffi::DirectedEdge) -> Vec<TurnLanes> {
+        if !de.turnlanes() {
+            return Vec::new();
+        }
+        ffi::turn_lanes(&self.tile, de)
+            .into_iter()
+            .map(TurnLanes::from_bits_retain)
+            .collect()
+    }
+
+    /// Connectivity of the lanes of the edge from this tile to lanes of the incoming ways. Empty for edges of other
+    /// tiles.
+    pub fn lane_connectivity(&self, de: &ffi::DirectedEdge) -> Vec<LaneConnectivity> {
+        if !de.laneconnectivity() {
+            return Vec::new();
+        }
+        ffi::lane_connectivity(&self.tile, de)
+            .iter()
+            .map(LaneConnectivity::from)
+            .collect()
+    }
+
+    /// Country and state of the node from this tile. `None` for nodes of other tiles or if the tile has no such
+    /// admin record.
+    pub fn admin(&self, node: &ffi::NodeInfo) -> Option<AdminInfo> {
+        ffi::node_admin(&self.tile, node).into_iter().next()
+    }
+
+    /// Country and state of the edge from this tile. Edges don't store an admin record, so it's the one of their
+    /// start node. `None` for edges of other tiles or if the tile has no such admin record.
+    pub fn edge_admin(&self, de: &ffi::DirectedEdge) -> Option<AdminInfo> {
+        ffi::edge_admin(&self.tile, de).into_iter().next()
+    }
+
+    /// All countries and states of this tile, indexed by [`NodeInfo::admin_index()`].
+    pub fn admins(&self) -> impl Iterator<Item = AdminInfo> {
+        ffi::admins(&self.tile).into_iter()
+    }
+
+    /// Transit stops of this tile. Only tiles of the transit level have transit data.
+    pub fn transit_stops(&self) -> impl Iterator<Item = TransitStop> {
+        ffi::transit_stops(&self.tile).into_iter()
+    }
+
+    /// Transit departures of this tile, sorted by line ID and departure time. Fails if the departures aren't
+    /// where the tile header places them, as Valhalla has no accessor to check them against.
+    pub fn transit_departures(&self) -> Result<Vec<TransitDeparture>, Error> {
+        Ok(ffi::transit_departures(&self.tile)?)
+    }
+
+    /// Transit routes of this tile, referenced by [`TransitDeparture::route_index`].
+    pub fn transit_routes(&self) -> impl Iterator<Item = TransitRoute> {
+        ffi::transit_routes(&self.tile).into_iter()
+    }
+
+    /// Transit schedules of this tile, referenced by [`TransitDeparture::schedule_index`].
+    pub fn transit_schedules(&self) -> impl Iterator<Item = TransitSchedule> {
+        ffi::transit_schedules(&self.tile).into_iter()
+    }
+
+    /// Transfers between transit stops of this tile. Fails if the transfers aren't where the tile header places
+    /// them, as Valhalla has no accessor to check them against.
+    pub fn transit_transfers(&self) -> Result<Vec<TransitTransfer>, Error> {
+        Ok(ffi::transit_transfers(&self.tile)?)
+    }
+
     /// Dynamic (cold) information about the edge, such as OSM Way ID, speed limit, shape, elevation, etc.
     pub fn edgeinfo(&self, de: &ffi::DirectedEdge) -> ffi::EdgeInfo {
         ffi::edgeinfo(&self.tile, de)
@@ -552,6 +1332,42 @@ impl GraphTile {
     }
 }
 
+impl EdgeInfo {
+    /// Decoded [`EdgeInfo::shape`] in the direction of the edge.
+    pub fn shape_points(&self) -> Vec<LatLon> {
+        decode_polyline6(&self.shape)
+    }
+}
+
+/// Decodes polyline with 6 digits precision, as used by Valhalla.
+fn decode_polyline6(encoded: &str) -> Vec<LatLon> {
+    let mut points = Vec::new();
+    let mut bytes = encoded.bytes();
+    let mut next = || -> Option<i64> {
+        let (mut result, mut shift) = (0i64, 0);
+        loop {
+            let byte = i64::from(bytes.next()?) - 63;
+            result |= (byte & 0x1f) << shift;
+            shift += 5;
+            if byte < 0x20 {
+                break;
+            }
+        }
+        Some(if result & 1 != 0 {
+            !(result >> 1)
+        } else {
+            result >> 1
+        })
+    };
+    let (mut lat, mut lon) = (0i64, 0i64);
+    while let (Some(dlat), Some(dlon)) = (next(), next()) {
+        lat += dlat;
+        lon += dlon;
+        points.push(LatLon(lat as f64 / 1e6, lon as f64 / 1e6));
+    }
+    points
+}
+
 impl DirectedEdge {
     /// Access modes in the forward direction. Bit mask using [`Access`] constants.
     #[inline(always)]
@@ -564,6 +1380,52 @@ impl DirectedEdge {
     pub fn reverseaccess(&self) -> Access {
         Access::from_bits_retain(self.reverseaccess_u32() as u16)
     }
+
+    /// Access modes that have [`AccessRestriction`]s on this edge, see [`GraphTile::access_restrictions()`].
+    #[inline(always)]
+    pub fn access_restriction(&self) -> Access {
+        Access::from_bits_retain(self.access_restriction_u32() as u16)
+    }
+
+    /// Access modes for which this edge starts a [`ComplexRestriction`].
+    #[inline(always)]
+    pub fn start_restriction(&self) -> Access {
+        Access::from_bits_retain(self.start_restriction_u32() as u16)
+    }
+
+    /// Access modes for which this edge ends a [`ComplexRestriction`].
+    #[inline(always)]
+    pub fn end_restriction(&self) -> Access {
+        Access::from_bits_retain(self.end_restriction_u32() as u16)
+    }
+}
+
+impl ComplexRestriction {
+    /// Access modes the restriction applies to.
+    pub fn access(&self) -> Access {
+        Access::from_bits_retain(self.modes)
+    }
+
+    /// Time period when the restriction applies, or `None` if it always applies.
+    pub fn time_domain(&self) -> Option<TimeDomain> {
+        self.has_time.then_some(self.time)
+    }
+}
+
+impl Sign {
+    /// Pronunciation of the text, or `None` if not available.
+    pub fn pronunciation(&self) -> Option<&str> {
+        self.has_linguistic.then_some(self.pronunciation.as_str())
+    }
+}
+
+impl TileHeader {
+    /// Time when the tile was built, with the precision of a day.
+    pub fn created(&self) -> SystemTime {
+        // 2014-01-01, the pivot date of Valhalla's date encodings
+        const PIVOT_DATE: u64 = 1_388_534_400;
+        UNIX_EPOCH + Duration::from_secs(PIVOT_DATE + u64::from(self.date_created) * 86_400)
+    }
 }
 
 impl TimeZoneInfo {
@@ -577,6 +1439,19 @@ impl TimeZoneInfo {
 mod tests {
     use super::*;
 
+    #[test]
+    fn polyline6() {
+        assert_eq!(
+            decode_polyline6("_izlhA~rlgdF_{geC~ywl@_kwzCn`{nI"),
+            vec![
+                LatLon(38.5, -120.2),
+                LatLon(40.7, -120.95),
+                LatLon(43.252, -126.453)
+            ]
+        );
+        assert_eq!(decode_polyline6(""), vec![]);
+    }
+
     #[test]
     fn graph_id() {
         let id = GraphId::new(5411833275938);
@@ -605,5 +1480,123 @@ mod tests {
         assert_eq!(default_id.id(), 2097151);
 
         assert_eq!(GraphId::from_parts(8, id.tileid(), 0), None);
+
+        assert_eq!(base.file_suffix(), "2/000/838/852.gph");
+        assert_eq!(GraphId::from_file_suffix("2/000/838/852.gph"), Some(base));
+        assert_eq!(
+            GraphId::from_file_suffix("tiles/2/000/838/852.gph"),
+            Some(base)
+        );
+        let highway = GraphId::from_parts(0, 3015, 0).unwrap();
+        assert_eq!(highway.file_suffix(), "0/003/015.gph");
+        assert_eq!(GraphId::from_file_suffix("0/003/015.gph"), Some(highway));
+        assert_eq!(GraphId::from_file_suffix("2/003/015.gph"), None);
+        assert_eq!(GraphId::from_file_suffix("0/003/015.bin"), None);
+        assert_eq!(GraphId::from_file_suffix("003/015.gph"), None);
+    }
+
+    /// Serves a single tile from memory.
+    #[cfg(feature = "test-fixtures")]
+    struct SingleTile(GraphId, Arc<[u8]>);
+
+    #[cfg(feature = "test-fixtures")]
+    impl TileLoader for SingleTile {
+        fn load(&self, id: GraphId) -> Option<Arc<[u8]>> {
+            (id == self.0).then(|| self.1.clone())
+        }
+
+        fn tiles(&self) -> Vec<GraphId> {
+            vec![self.0]
+        }
+    }
+
+    #[cfg(feature = "test-fixtures")]
+    #[test]
+    fn transit() {
+        let id = GraphId::from_parts(3, 762485, 0).unwrap();
+        let data = crate::fixtures::ffi::transit_test_tile(id);
+        let reader = GraphReader::from_loader(SingleTile(id, data.into()));
+        let report = reader.verify();
+        assert!(report.is_ok(), "{:?}", report.issues);
+        let tile = reader.get_tile(id).unwrap();
+        let header = tile.header();
+        assert_eq!(
+            (
+                header.stop_count,
+                header.departure_count,
+                header.route_count
+            ),
+            (2, 2, 1)
+        );
+        assert_eq!((header.schedule_count, header.transfer_count), (2, 1));
+
+        let stops: Vec<_> = tile.transit_stops().collect();
+        assert_eq!(stops.len(), 2);
+        assert_eq!(stops[0].onestop_id, "s-sp91-placa");
+        assert_eq!(stops[0].name, "Plaça");
+        assert!(!stops[0].generated);
+        assert_eq!(stops[0].traversability, 3);
+        assert_eq!(stops[1].name, "Estació");
+        assert!(stops[1].generated);
+
+        let routes: Vec<_> = tile.transit_routes().collect();
+        assert_eq!(routes.len(), 1);
+        assert_eq!(routes[0].route_type, TransitType::kBus);
+        assert_eq!(routes[0].onestop_id, "r-sp91-l1");
+        assert_eq!(routes[0].operator_onestop_id, "o-sp91-bus");
+        assert_eq!(routes[0].operator_name, "Bus Andorra");
+        assert_eq!(routes[0].operator_website, "https://bus.example");
+        assert_eq!(
+            (routes[0].color, routes[0].text_color),
+            (0x0000ff, 0xffffff)
+        );
+        assert_eq!(routes[0].short_name, "L1");
+        assert_eq!(routes[0].long_name, "Línia 1");
+        assert_eq!(routes[0].description, "Andorra la Vella to Encamp");
+
+        let departures = tile.transit_departures().unwrap();
+        assert_eq!(departures.len(), 2);
+        let (fixed, frequent) = (&departures[0], &departures[1]);
+        assert_eq!((fixed.line_id, fixed.trip_id, fixed.block_id), (7, 100, 3));
+        assert_eq!((fixed.route_index, fixed.schedule_index), (0, 0));
+        assert_eq!(fixed.headsign, "Sant Julià");
+        assert_eq!((fixed.departure_time, fixed.elapsed_time), (8 * 3600, 120));
+        assert!(!fixed.frequency_based);
+        assert!(fixed.wheelchair_accessible && !fixed.bicycle_accessible);
+        assert_eq!((frequent.trip_id, frequent.schedule_index), (101, 1));
+        assert_eq!(frequent.headsign, "Encamp");
+        assert!(frequent.frequency_based);
+        assert_eq!(
+            (
+                frequent.departure_time,
+                frequent.end_time,
+                frequent.frequency
+            ),
+            (9 * 3600, 12 * 3600, 900)
+        );
+        assert_eq!(frequent.elapsed_time, 150);
+        assert!(!frequent.wheelchair_accessible && frequent.bicycle_accessible);
+
+        let schedules: Vec<_> = tile.transit_schedules().collect();
+        assert_eq!(schedules.len(), 2);
+        assert_eq!(
+            (
+                schedules[0].days,
+                schedules[0].days_of_week,
+                schedules[0].end_day
+            ),
+            (0b1011, 0b0111110, 3)
+        );
+        assert_eq!(schedules[1].days_of_week, 0b1000001);
+
+        let transfers = tile.transit_transfers().unwrap();
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(
+            (transfers[0].from_stop_index, transfers[0].to_stop_index),
+            (0, 1)
+        );
+        // `baldr::TransferType::kMinTime`
+        assert_eq!(transfers[0].transfer_type, 2);
+        assert_eq!(transfers[0].min_time, 240);
     }
 }
@@ -1,8 +1,17 @@
 #include "libvalhalla.hpp"
 #include "valhalla/src/lib.rs.h"
 
+#include <algorithm>
+#include <functional>
+#include <iterator>
+#include <optional>
+
 #include <valhalla/baldr/datetime.h>
 #include <valhalla/baldr/graphreader.h>
+#include <valhalla/baldr/predictedspeeds.h>
+#include <valhalla/baldr/timedomain.h>
+#include <valhalla/baldr/traffictile.h>
+#include <valhalla/baldr/transittransfer.h>
 #include <valhalla/midgard/encoded.h>
 
 namespace baldr = valhalla::baldr;
@@ -11,14 +20,247 @@ namespace midgard = valhalla::midgard;
 namespace {
 
 struct GraphMemory : public baldr::GraphMemory {
-  const std::shared_ptr<midgard::tar> tar_;
+  /// Keeps the memory alive while the tile is in use
+  const std::shared_ptr<const void> owner_;
 
-  GraphMemory(std::shared_ptr<midgard::tar> tar, std::pair<char*, size_t> position) : tar_(std::move(tar)) {
+  GraphMemory(std::shared_ptr<const void> owner, std::pair<char*, size_t> position) : owner_(std::move(owner)) {
     data = position.first;
     size = position.second;
   }
 };
 
+/// Converts Rust `Box` into `std::shared_ptr`, so it can be shared between tiles.
+template <typename T>
+std::shared_ptr<const T> share(rust::Box<T> box) {
+  return std::shared_ptr<const T>(box.into_raw(), [](const T* ptr) { rust::Box<T>::from_raw(const_cast<T*>(ptr)); });
+}
+
+/// Position of the tile data within a Rust buffer.
+std::pair<char*, size_t> position(const TileBuffer& buffer, size_t offset, size_t size) {
+  // GraphTile never modifies the memory, it's only declared as mutable
+  return {const_cast<char*>(reinterpret_cast<const char*>(buffer.data().data())) + offset, size};
+}
+
+/// Offsets of the fixed-size record sections that follow the tile header, in the order `baldr::GraphTile` reads
+/// them. Variable-size data, like complex restrictions and edge info, starts right after `end`.
+struct TileSections {
+  size_t nodes;
+  size_t transitions;
+  size_t directededges;
+  size_t ext_directededges;
+  size_t access_restrictions;
+  size_t departures;
+  size_t stops;
+  size_t routes;
+  size_t schedules;
+  size_t transfers;
+  size_t signs;
+  size_t turnlanes;
+  size_t admins;
+  size_t end;
+};
+
+TileSections tile_sections(const baldr::GraphTileHeader& header) {
+  size_t offset = sizeof(baldr::GraphTileHeader);
+  auto next = [&](size_t count, size_t record_size) {
+    const size_t start = offset;
+    offset += count * record_size;
+    return start;
+  };
+  TileSections sections{};
+  sections.nodes = next(header.nodecount(), sizeof(baldr::NodeInfo));
+  sections.transitions = next(header.transitioncount(), sizeof(baldr::NodeTransition));
+  sections.directededges = next(header.directededgecount(), sizeof(baldr::DirectedEdge));
+  sections.ext_directededges =
+      next(header.has_ext_directededge() ? header.directededgecount() : 0, sizeof(baldr::DirectedEdgeExt));
+  sections.access_restrictions = next(header.access_restriction_count(), sizeof(baldr::AccessRestriction));
+  sections.departures = next(header.departurecount(), sizeof(baldr::TransitDeparture));
+  sections.stops = next(header.stopcount(), sizeof(baldr::TransitStop));
+  sections.routes = next(header.routecount(), sizeof(baldr::TransitRoute));
+  sections.schedules = next(header.schedulecount(), sizeof(baldr::TransitSchedule));
+  sections.transfers = next(header.transfercount(), sizeof(baldr::TransitTransfer));
+  sections.signs = next(header.signcount(), sizeof(baldr::Sign));
+  sections.turnlanes = next(header.turnlane_count(), sizeof(baldr::TurnLanes));
+  sections.admins = next(header.admincount(), sizeof(baldr::Admin));
+  sections.end = offset;
+  return sections;
+}
+
+/// Records of a section that `baldr::GraphTile` doesn't expose as a list, or `nullptr` if the computed layout
+/// doesn't match the tile. Records of neighbour sections that `tile` exposes, if any, must start right at `start`
+/// or right after the section.
+template <typename T>
+const T* tile_section(const GraphTile& tile, size_t offset, size_t count, const void* start, const void* next) {
+  const auto* header = tile.header();
+  const auto* base = reinterpret_cast<const char*>(header);
+  const size_t end = offset + count * sizeof(T);
+  if (end > header->complex_restriction_forward_offset() || end > header->end_offset() ||
+      (start != nullptr && static_cast<const char*>(start) != base + offset) ||
+      (next != nullptr && static_cast<const char*>(next) != base + end)) {
+    return nullptr;
+  }
+  return reinterpret_cast<const T*>(base + offset);
+}
+
+/// Checks that the header of the tile data describes records that fit into the data, so `baldr::GraphTile` can
+/// read it safely.
+void check_layout(baldr::GraphId base, const char* data, size_t size, rust::Vec<TileLayoutIssue>& issues) {
+  auto report = [&](const std::string& message) {
+    issues.push_back(TileLayoutIssue{.traffic = false, .message = message});
+  };
+  if (size < sizeof(baldr::GraphTileHeader)) {
+    report("tile has " + std::to_string(size) + " bytes, less than the header");
+    return;
+  }
+  const auto* header = reinterpret_cast<const baldr::GraphTileHeader*>(data);
+  if (header->graphid().value != base.value) {
+    report("header has tile id " + std::to_string(header->graphid().tileid()) + " on level " +
+           std::to_string(header->graphid().level()));
+  }
+  if (header->end_offset() != size) {
+    report("header size " + std::to_string(header->end_offset()) + " doesn't match " + std::to_string(size) +
+           " bytes of the tile");
+  }
+
+  // Fixed-size records from nodes to admins are followed by variable-size sections in this order
+  const size_t end = std::min<size_t>(header->end_offset(), size);
+  const std::pair<const char*, size_t> boundaries[] = {
+    {"end of records", tile_sections(*header).end},
+    {"forward complex restrictions offset", header->complex_restriction_forward_offset()},
+    {"reverse complex restrictions offset", header->complex_restriction_reverse_offset()},
+    {"edge info offset", header->edgeinfo_offset()},
+    {"text list offset", header->textlist_offset()},
+    {"lane connectivity offset", header->lane_connectivity_offset()},
+    {"end of tile", end},
+  };
+  for (size_t i = 1; i < std::size(boundaries); ++i) {
+    const auto& [name, offset] = boundaries[i - 1];
+    const auto& [next_name, next_offset] = boundaries[i];
+    if (offset > next_offset) {
+      report(std::string(name) + " " + std::to_string(offset) + " is past " + next_name + " " +
+             std::to_string(next_offset));
+    }
+  }
+
+  // Predicted speeds are optional, with profile offsets of all edges followed by the profiles
+  if (header->predictedspeeds_count() > 0) {
+    const size_t speeds_end = size_t(header->predictedspeeds_offset()) +
+                              header->directededgecount() * sizeof(uint32_t) +
+                              header->predictedspeeds_count() * baldr::kCoefficientCount * sizeof(int16_t);
+    if (speeds_end > end) {
+      report("predicted speeds at offset " + std::to_string(header->predictedspeeds_offset()) + " don't fit into " +
+             std::to_string(end) + " bytes");
+    }
+  }
+}
+
+/// Checks that the traffic tile has a speed record for every directed edge of the graph tile.
+void check_traffic_layout(const char* graph_data, const char* data, size_t size, rust::Vec<TileLayoutIssue>& issues) {
+  auto report = [&](const std::string& message) {
+    issues.push_back(TileLayoutIssue{.traffic = true, .message = message});
+  };
+  if (size < sizeof(baldr::TrafficTileHeader)) {
+    report("traffic tile has " + std::to_string(size) + " bytes, less than the header");
+    return;
+  }
+  const auto* header = reinterpret_cast<const baldr::TrafficTileHeader*>(data);
+  const auto edge_count = reinterpret_cast<const baldr::GraphTileHeader*>(graph_data)->directededgecount();
+  if (header->directed_edge_count != edge_count) {
+    report("traffic tile has " + std::to_string(header->directed_edge_count) + " edges instead of " +
+           std::to_string(edge_count));
+  }
+  if (size < sizeof(baldr::TrafficTileHeader) + header->directed_edge_count * sizeof(baldr::TrafficSpeed)) {
+    report("traffic tile has " + std::to_string(size) + " bytes, less than needed for its edges");
+  }
+}
+
+/// Converts bit fields shared by `baldr::TimeDomain` and `baldr::ComplexRestriction`.
+/// Only the type of days is named differently, so it's passed separately.
+template <typename T>
+TimeDomain to_time_domain(const T& domain, bool nth_dow) {
+  return TimeDomain{
+    .nth_dow = nth_dow,
+    .dow = static_cast<uint8_t>(domain.dow()),
+    .begin_month = static_cast<uint8_t>(domain.begin_month()),
+    .begin_day_dow = static_cast<uint8_t>(domain.begin_day_dow()),
+    .begin_week = static_cast<uint8_t>(domain.begin_week()),
+    .begin_hrs = static_cast<uint8_t>(domain.begin_hrs()),
+    .begin_mins = static_cast<uint8_t>(domain.begin_mins()),
+    .end_month = static_cast<uint8_t>(domain.end_month()),
+    .end_day_dow = static_cast<uint8_t>(domain.end_day_dow()),
+    .end_week = static_cast<uint8_t>(domain.end_week()),
+    .end_hrs = static_cast<uint8_t>(domain.end_hrs()),
+    .end_mins = static_cast<uint8_t>(domain.end_mins()),
+  };
+}
+
+AdminInfo to_admin_info(const baldr::AdminInfo& admin) {
+  return AdminInfo{
+    .country_iso = admin.country_iso(),
+    .country_text = admin.country_text(),
+    .state_iso = admin.state_iso(),
+    .state_text = admin.state_text(),
+  };
+}
+
+/// Index of the directed edge in the tile, or `std::nullopt` if the edge belongs to another tile.
+std::optional<uint32_t> edge_index(const GraphTile& tile, const baldr::DirectedEdge& de) {
+  const uint32_t count = tile.header()->directededgecount();
+  if (count == 0) {
+    return std::nullopt;
+  }
+  const auto* first = tile.directededge(0);
+  const std::less<const baldr::DirectedEdge*> before;
+  if (before(&de, first) || !before(&de, first + count)) {
+    return std::nullopt;
+  }
+  return static_cast<uint32_t>(&de - first);
+}
+
+/// Index of the node in the tile, or `std::nullopt` if the node belongs to another tile.
+std::optional<uint32_t> node_index(const GraphTile& tile, const baldr::NodeInfo& node) {
+  const uint32_t count = tile.header()->nodecount();
+  if (count == 0) {
+    return std::nullopt;
+  }
+  const auto* first = tile.node(0);
+  const std::less<const baldr::NodeInfo*> before;
+  if (before(&node, first) || !before(&node, first + count)) {
+    return std::nullopt;
+  }
+  return static_cast<uint32_t>(&node - first);
+}
+
+/// Collects signs of an edge or a node with the pronunciations of their texts.
+rust::Vec<Sign> collect_signs(const GraphTile& tile, uint32_t index, bool on_node) {
+  std::unordered_map<uint8_t, std::tuple<uint8_t, uint8_t, std::string>> linguistics;
+  const auto signs = tile.GetSigns(index, linguistics, on_node);
+
+  rust::Vec<Sign> result;
+  result.reserve(signs.size());
+  for (size_t i = 0; i < signs.size(); ++i) {
+    Sign sign{
+      .sign_type = signs[i].type(),
+      .text = signs[i].text(),
+      .is_route_num = signs[i].is_route_num(),
+      .is_tagged = signs[i].is_tagged(),
+      .has_linguistic = false,
+      .phonetic_alphabet = 0,
+      .language = 0,
+      .pronunciation = {},
+    };
+    const auto linguistic = linguistics.find(static_cast<uint8_t>(i));
+    if (linguistic != linguistics.end()) {
+      sign.has_linguistic = true;
+      sign.phonetic_alphabet = std::get<0>(linguistic->second);
+      sign.language = std::get<1>(linguistic->second);
+      sign.pronunciation = std::get<2>(linguistic->second);
+    }
+    result.push_back(std::move(sign));
+  }
+  return result;
+}
+
 }  // namespace
 
 TileSet::~TileSet() {}
@@ -44,6 +286,25 @@ std::shared_ptr<TileSet> new_tileset(const boost::property_tree::ptree& pt) {
   return std::make_shared<TileSet>(std::move(tile_set));
 }
 
+std::shared_ptr<TileSet> new_tileset_from_memory(rust::Box<TileBuffer> buffer, rust::Slice<const TileEntry> entries) {
+  auto tile_set = std::make_shared<TileSet>();
+  for (const auto& entry : entries) {
+    tile_set->tiles_[entry.id.Tile_Base()] = position(*buffer, entry.offset, entry.size);
+  }
+  tile_set->memory_ = share(std::move(buffer));
+  return tile_set;
+}
+
+std::shared_ptr<TileSet> new_tileset_from_loader(rust::Box<TileSource> source,
+                                                 rust::Slice<const baldr::GraphId> tiles) {
+  auto tile_set = std::make_shared<TileSet>();
+  for (const auto& id : tiles) {
+    tile_set->tiles_[id.Tile_Base()] = {nullptr, 0};
+  }
+  tile_set->loader_ = share(std::move(source));
+  return tile_set;
+}
+
 rust::Vec<baldr::GraphId> TileSet::tiles() const {
   rust::vec<baldr::GraphId> result;
   result.reserve(tiles_.size());
@@ -79,21 +340,64 @@ baldr::graph_tile_ptr TileSet::get_tile(baldr::GraphId id) const {
     return nullptr;
   }
 
+  if (loader_) {
+    auto buffer = load_tile(*loader_, base.value);
+    const auto size = buffer->data().size();
+    if (size == 0) {
+      return nullptr;
+    }
+    auto memory = position(*buffer, 0, size);
+    return baldr::GraphTile::Create(base, std::make_unique<GraphMemory>(share(std::move(buffer)), memory), nullptr);
+  }
+
   // Optionally get the traffic tile if it exists
   auto traffic_it = traffic_tiles_.find(base);
   auto traffic =
       traffic_it != traffic_tiles_.end() ? std::make_unique<GraphMemory>(traffic_tar_, traffic_it->second) : nullptr;
 
   // This initializes the tile from mmap
-  return baldr::GraphTile::Create(base, std::make_unique<GraphMemory>(tar_, tile_it->second), std::move(traffic));
+  auto memory = memory_ ? memory_ : std::shared_ptr<const void>(tar_);
+  return baldr::GraphTile::Create(base, std::make_unique<GraphMemory>(std::move(memory), tile_it->second),
+                                  std::move(traffic));
+}
+
+rust::Vec<TileLayoutIssue> TileSet::check_tile(baldr::GraphId id) const {
+  rust::Vec<TileLayoutIssue> issues;
+  const auto base = id.Tile_Base();
+  auto tile_it = tiles_.find(base);
+  if (tile_it == tiles_.end()) {
+    issues.push_back(TileLayoutIssue{.traffic = false, .message = "tile is not in the tileset"});
+    return issues;
+  }
+
+  if (loader_) {
+    auto buffer = load_tile(*loader_, base.value);
+    const auto size = buffer->data().size();
+    if (size == 0) {
+      issues.push_back(TileLayoutIssue{.traffic = false, .message = "tile can't be loaded"});
+      return issues;
+    }
+    check_layout(base, position(*buffer, 0, size).first, size, issues);
+    return issues;
+  }
+
+  const auto [data, size] = tile_it->second;
+  check_layout(base, data, size, issues);
+  auto traffic_it = traffic_tiles_.find(base);
+  if (issues.empty() && traffic_it != traffic_tiles_.end()) {
+    check_traffic_layout(data, traffic_it->second.first, traffic_it->second.second, issues);
+  }
+  return issues;
 }
 
 uint64_t TileSet::dataset_id() const {
-  if (auto it = tiles_.begin(); it != tiles_.end()) {
-    return get_tile(baldr::GraphId(it->first))->header()->dataset_id();
-  } else {
-    return 0;
+  // Tiles listed by a loader might fail to load
+  for (const auto& tile : tiles_) {
+    if (auto graph_tile = get_tile(baldr::GraphId(tile.first))) {
+      return graph_tile->header()->dataset_id();
+    }
   }
+  return 0;
 }
 
 DirectedEdgeSlice directededges(const GraphTile& tile) {
@@ -112,6 +416,278 @@ NodeInfoSlice nodes(const GraphTile& tile) {
   };
 }
 
+NodeLatLon node_latlon(const GraphTile& tile, const valhalla::baldr::NodeInfo& node) {
+  const auto ll = node.latlng(tile.header()->base_ll());
+  return NodeLatLon{
+    .lat = ll.lat(),
+    .lon = ll.lng(),
+  };
+}
+
+rust::Vec<baldr::GraphId> node_transitions(const GraphTile& tile, const valhalla::baldr::NodeInfo& node) {
+  rust::Vec<baldr::GraphId> result;
+  result.reserve(node.transition_count());
+  for (uint32_t i = 0; i < node.transition_count(); ++i) {
+    result.push_back(tile.transition(node.transition_index() + i)->endnode());
+  }
+  return result;
+}
+
+rust::Vec<ComplexRestriction> complex_restrictions(const GraphTile& tile) {
+  rust::Vec<ComplexRestriction> result;
+  const baldr::GraphId tile_id = tile.id();
+  for (uint32_t i = 0; i < tile.header()->directededgecount(); ++i) {
+    if (tile.directededge(i)->end_restriction() == 0) {
+      continue;
+    }
+    // Forward list is keyed by the last edge of the restriction
+    const baldr::GraphId edge_id(tile_id.tileid(), tile_id.level(), i);
+    for (const auto* cr : tile.GetRestrictions(true, edge_id, baldr::kAllAccess)) {
+      rust::Vec<baldr::GraphId> vias;
+      cr->WalkVias([&vias](const baldr::GraphId* via) {
+        vias.push_back(*via);
+        return baldr::WalkingVia::KeepWalking;
+      });
+      result.push_back(ComplexRestriction{
+        .from = cr->from_graphid(),
+        .to = cr->to_graphid(),
+        .vias = std::move(vias),
+        .restriction_type = cr->type(),
+        .modes = static_cast<uint16_t>(cr->modes()),
+        .has_time = cr->has_dt(),
+        .time = to_time_domain(*cr, cr->dt_type()),
+      });
+    }
+  }
+  return result;
+}
+
+rust::Vec<AccessRestrictionEntry> access_restrictions(const GraphTile& tile, const valhalla::baldr::DirectedEdge& de) {
+  rust::Vec<AccessRestrictionEntry> result;
+  const auto index = edge_index(tile, de);
+  if (!index) {
+    return result;
+  }
+  for (const auto& restriction : tile.GetAccessRestrictions(*index, baldr::kAllAccess)) {
+    result.push_back(AccessRestrictionEntry{
+      .access_type = restriction.type(),
+      .modes = static_cast<uint16_t>(restriction.modes()),
+      .value = restriction.value(),
+      .except_destination = restriction.except_destination(),
+    });
+  }
+  return result;
+}
+
+TimeDomain decode_time_domain(uint64_t value) {
+  const baldr::TimeDomain domain(value);
+  return to_time_domain(domain, domain.type());
+}
+
+rust::Vec<Sign> edge_signs(const GraphTile& tile, const valhalla::baldr::DirectedEdge& de) {
+  const auto index = edge_index(tile, de);
+  return index ? collect_signs(tile, *index, false) : rust::Vec<Sign>{};
+}
+
+rust::Vec<Sign> node_signs(const GraphTile& tile, const valhalla::baldr::NodeInfo& node) {
+  const auto index = node_index(tile, node);
+  return index ? collect_signs(tile, *index, true) : rust::Vec<Sign>{};
+}
+
+rust::Vec<AdminInfo> node_admin(const GraphTile& tile, const valhalla::baldr::NodeInfo& node) {
+  rust::Vec<AdminInfo> result;
+  if (node_index(tile, node) && node.admin_index() < tile.header()->admincount()) {
+    result.push_back(to_admin_info(tile.admininfo(node.admin_index())));
+  }
+  return result;
+}
+
+rust::Vec<AdminInfo> edge_admin(const GraphTile& tile, const valhalla::baldr::DirectedEdge& de) {
+  const auto index = edge_index(tile, de);
+  if (!index || tile.header()->nodecount() == 0) {
+    return {};
+  }
+  // Outgoing edges are stored node by node, so the start node is the last one whose first edge isn't after this one
+  const auto* first = tile.node(0);
+  const auto* last = first + tile.header()->nodecount();
+  const auto* next = std::upper_bound(first, last, *index, [](uint32_t edge, const baldr::NodeInfo& node) {
+    return edge < node.edge_index();
+  });
+  return next == first ? rust::Vec<AdminInfo>{} : node_admin(tile, *(next - 1));
+}
+
+rust::Vec<AdminInfo> admins(const GraphTile& tile) {
+  rust::Vec<AdminInfo> result;
+  result.reserve(tile.header()->admincount());
+  for (uint32_t index = 0; index < tile.header()->admincount(); ++index) {
+    result.push_back(to_admin_info(tile.admininfo(index)));
+  }
+  return result;
+}
+
+TileHeader tile_header(const GraphTile& tile) {
+  const auto* header = tile.header();
+  return TileHeader{
+    .version = header->version(),
+    .dataset_id = header->dataset_id(),
+    .date_created = header->date_created(),
+    .base_lat = header->base_ll().lat(),
+    .base_lon = header->base_ll().lng(),
+    .density = header->density(),
+    .has_elevation = header->has_elevation(),
+    .checksum = header->checksum(),
+    .node_count = header->nodecount(),
+    .directed_edge_count = header->directededgecount(),
+    .transition_count = header->transitioncount(),
+    .sign_count = header->signcount(),
+    .turn_lane_count = header->turnlane_count(),
+    .access_restriction_count = header->access_restriction_count(),
+    .admin_count = header->admincount(),
+    .departure_count = header->departurecount(),
+    .stop_count = header->stopcount(),
+    .route_count = header->routecount(),
+    .schedule_count = header->schedulecount(),
+    .transfer_count = header->transfercount(),
+    .edgeinfo_offset = header->edgeinfo_offset(),
+    .textlist_offset = header->textlist_offset(),
+  };
+}
+
+rust::Vec<TransitStop> transit_stops(const GraphTile& tile) {
+  rust::Vec<TransitStop> result;
+  for (uint32_t index = 0; index < tile.header()->stopcount(); ++index) {
+    const auto* stop = tile.GetTransitStop(index);
+    result.push_back(TransitStop{
+      .onestop_id = tile.GetName(stop->one_stop_offset()),
+      .name = tile.GetName(stop->name_offset()),
+      .generated = stop->generated(),
+      .traversability = static_cast<uint8_t>(stop->traversability()),
+    });
+  }
+  return result;
+}
+
+rust::Vec<TransitDeparture> transit_departures(const GraphTile& tile) {
+  rust::Vec<TransitDeparture> result;
+  const uint32_t count = tile.header()->departurecount();
+  if (count == 0) {
+    return result;
+  }
+  // `GraphTile` looks departures up only by line and trip, so they are located by the header, right before stops
+  const auto* departures =
+      tile_section<baldr::TransitDeparture>(tile, tile_sections(*tile.header()).departures, count, nullptr,
+                                            tile.header()->stopcount() > 0 ? tile.GetTransitStop(0) : nullptr);
+  if (departures == nullptr) {
+    throw std::runtime_error("Transit departures aren't where the tile header places them");
+  }
+  for (uint32_t index = 0; index < count; ++index) {
+    const auto& departure = departures[index];
+    result.push_back(TransitDeparture{
+      .line_id = departure.lineid(),
+      .route_index = departure.routeindex(),
+      .trip_id = departure.tripid(),
+      .block_id = departure.blockid(),
+      .schedule_index = departure.schedule_index(),
+      .headsign = tile.GetName(departure.headsign_offset()),
+      .departure_time = departure.departure_time(),
+      .elapsed_time = departure.elapsed_time(),
+      .frequency_based = departure.type() == baldr::kFrequencySchedule,
+      .end_time = departure.end_time(),
+      .frequency = departure.frequency(),
+      .wheelchair_accessible = departure.wheelchair_accessible(),
+      .bicycle_accessible = departure.bicycle_accessible(),
+    });
+  }
+  return result;
+}
+
+rust::Vec<TransitRoute> transit_routes(const GraphTile& tile) {
+  rust::Vec<TransitRoute> result;
+  for (uint32_t index = 0; index < tile.header()->routecount(); ++index) {
+    const auto* route = tile.GetTransitRoute(index);
+    result.push_back(TransitRoute{
+      .route_type = route->route_type(),
+      .onestop_id = tile.GetName(route->one_stop_offset()),
+      .operator_onestop_id = tile.GetName(route->op_by_onestop_id_offset()),
+      .operator_name = tile.GetName(route->op_by_name_offset()),
+      .operator_website = tile.GetName(route->op_by_website_offset()),
+      .color = route->route_color(),
+      .text_color = route->route_text_color(),
+      .short_name = tile.GetName(route->short_name_offset()),
+      .long_name = tile.GetName(route->long_name_offset()),
+      .description = tile.GetName(route->desc_offset()),
+    });
+  }
+  return result;
+}
+
+rust::Vec<TransitSchedule> transit_schedules(const GraphTile& tile) {
+  rust::Vec<TransitSchedule> result;
+  for (uint32_t index = 0; index < tile.header()->schedulecount(); ++index) {
+    const auto* schedule = tile.GetTransitSchedule(index);
+    result.push_back(TransitSchedule{
+      .days = schedule->days(),
+      .days_of_week = static_cast<uint8_t>(schedule->days_of_week()),
+      .end_day = schedule->end_day(),
+    });
+  }
+  return result;
+}
+
+rust::Vec<TransitTransfer> transit_transfers(const GraphTile& tile) {
+  rust::Vec<TransitTransfer> result;
+  const uint32_t count = tile.header()->transfercount();
+  if (count == 0) {
+    return result;
+  }
+  // `GraphTile` looks transfers up only by stops, so they are located by the header, right after schedules
+  const uint32_t schedules = tile.header()->schedulecount();
+  const auto* transfers = tile_section<baldr::TransitTransfer>(
+      tile, tile_sections(*tile.header()).transfers, count,
+      schedules > 0 ? tile.GetTransitSchedule(schedules - 1) + 1 : nullptr, nullptr);
+  if (transfers == nullptr) {
+    throw std::runtime_error("Transit transfers aren't where the tile header places them");
+  }
+  for (uint32_t index = 0; index < count; ++index) {
+    const auto& transfer = transfers[index];
+    result.push_back(TransitTransfer{
+      .from_stop_index = transfer.from_stopid(),
+      .to_stop_index = transfer.to_stopid(),
+      .transfer_type = static_cast<uint8_t>(transfer.type()),
+      .min_time = transfer.mintime(),
+    });
+  }
+  return result;
+}
+
+rust::Vec<uint16_t> turn_lanes(const GraphTile& tile, const valhalla::baldr::DirectedEdge& de) {
+  rust::Vec<uint16_t> result;
+  const auto index = edge_index(tile, de);
+  if (!index) {
+    return result;
+  }
+  for (const auto mask : tile.turnlanes(*index)) {
+    result.push_back(mask);
+  }
+  return result;
+}
+
+rust::Vec<LaneConnectivityEntry> lane_connectivity(const GraphTile& tile, const valhalla::baldr::DirectedEdge& de) {
+  rust::Vec<LaneConnectivityEntry> result;
+  const auto index = edge_index(tile, de);
+  if (!index) {
+    return result;
+  }
+  for (const auto& connectivity : tile.GetLaneConnectivity(*index)) {
+    result.push_back(LaneConnectivityEntry{
+      .from_way_id = connectivity.from(),
+      .from_lanes = connectivity.from_lanes(),
+      .to_lanes = connectivity.to_lanes(),
+    });
+  }
+  return result;
+}
+
 EdgeInfo edgeinfo(const GraphTile& tile, const valhalla::baldr::DirectedEdge& de) {
   const auto edge_info = tile.edgeinfo(&de);
 
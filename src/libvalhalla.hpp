@@ -9,10 +9,27 @@ namespace valhalla::midgard {
 struct tar;
 }
 
+struct AccessRestrictionEntry;
+struct AdminInfo;
+struct ComplexRestriction;
 struct DirectedEdgeSlice;
 struct EdgeInfo;
+struct LaneConnectivityEntry;
 struct NodeInfoSlice;
+struct NodeLatLon;
+struct Sign;
+struct TileBuffer;
+struct TileEntry;
+struct TileLayoutIssue;
+struct TileSource;
+struct TimeDomain;
+struct TileHeader;
 struct TimeZoneInfo;
+struct TransitDeparture;
+struct TransitRoute;
+struct TransitSchedule;
+struct TransitStop;
+struct TransitTransfer;
 
 enum class GraphLevel : uint8_t {
   Highway = 0,
@@ -21,7 +38,8 @@ enum class GraphLevel : uint8_t {
 };
 
 /// Exposed internal [`valhalla::baldr::GraphReader::tile_extract_t`], used to
-/// access exact graph and traffic tiles. Create it using [`new_tileset()`].
+/// access exact graph and traffic tiles. Create it using [`new_tileset()`], [`new_tileset_from_memory()`] or
+/// [`new_tileset_from_loader()`].
 struct TileSet {
   /// Explicitly define destructor as otherwise compiler will fail with
   /// std::unique_ptr due to forward declarations for `midgard::tar`
@@ -31,17 +49,30 @@ struct TileSet {
   std::unordered_map<uint64_t, std::pair<char*, size_t>> traffic_tiles_;
   std::shared_ptr<valhalla::midgard::tar> tar_;
   std::shared_ptr<valhalla::midgard::tar> traffic_tar_;
+  /// Owner of the memory `tiles_` point to if it's not `tar_`, e.g. an in-memory extract passed from Rust.
+  std::shared_ptr<const void> memory_;
+  /// Loads tiles on demand if set, `tiles_` only lists available tiles then.
+  std::shared_ptr<const TileSource> loader_;
 
   rust::Vec<valhalla::baldr::GraphId> tiles() const;
   rust::Vec<valhalla::baldr::GraphId> tiles_in_bbox(float min_lat, float min_lon, float max_lat, float max_lon,
                                                     GraphLevel level) const;
   valhalla::baldr::graph_tile_ptr get_tile(valhalla::baldr::GraphId id) const;
+  /// Checks the raw tile data before it's read by `baldr::GraphTile`, which trusts it.
+  rust::Vec<TileLayoutIssue> check_tile(valhalla::baldr::GraphId id) const;
   uint64_t dataset_id() const;
 };
 
 /// Creates a new [`TileSet`] instance based on a Valhalla's config.
 std::shared_ptr<TileSet> new_tileset(const boost::property_tree::ptree& config);
 
+/// Creates a new [`TileSet`] from a tar extract in memory, with tile positions parsed in Rust.
+std::shared_ptr<TileSet> new_tileset_from_memory(rust::Box<TileBuffer> buffer, rust::Slice<const TileEntry> entries);
+
+/// Creates a new [`TileSet`] that loads the listed tiles on demand from Rust.
+std::shared_ptr<TileSet> new_tileset_from_loader(rust::Box<TileSource> source,
+                                                 rust::Slice<const valhalla::baldr::GraphId> tiles);
+
 /// Helper function as cxx unable to call constructors with arguments.
 inline valhalla::baldr::GraphId from_parts(uint32_t level, uint32_t tileid, uint32_t id) {
   return valhalla::baldr::GraphId(tileid, level, id);
@@ -51,15 +82,72 @@ inline valhalla::baldr::GraphId from_parts(uint32_t level, uint32_t tileid, uint
 /// `std::shared_ptr<const GraphTile>` and `cxx` doesn't support `const` in `SharedPtr`.
 using GraphTile = const valhalla::baldr::GraphTile;
 
+/// Alias to bind the nested `valhalla::baldr::Sign::Type` enum, as `cxx` namespaces can't refer to a class.
+using SignType = valhalla::baldr::Sign::Type;
+
 /// Helper function that allows to iterate over a slice of directed edges of that tile in Rust
 DirectedEdgeSlice directededges(const GraphTile& tile);
 
 /// Helper function that allows to iterate over a slice of nodes of that tile in Rust
 NodeInfoSlice nodes(const GraphTile& tile);
 
+/// Helper function to get absolute node coordinates, as they are stored relative to the tile base
+NodeLatLon node_latlon(const GraphTile& tile, const valhalla::baldr::NodeInfo& node);
+
+/// Helper function that resolves node transitions into ids of the same node on other hierarchy levels
+rust::Vec<valhalla::baldr::GraphId> node_transitions(const GraphTile& tile, const valhalla::baldr::NodeInfo& node);
+
+/// Helper function that collects complex restrictions ending in this tile, with via edges resolved
+rust::Vec<ComplexRestriction> complex_restrictions(const GraphTile& tile);
+
+/// Helper function that returns access restrictions of the edge from its tile
+rust::Vec<AccessRestrictionEntry> access_restrictions(const GraphTile& tile, const valhalla::baldr::DirectedEdge& de);
+
+/// Helper function that unpacks `baldr::TimeDomain` bit fields
+TimeDomain decode_time_domain(uint64_t value);
+
+/// Helper function that returns exit and guide signs of the edge from its tile
+rust::Vec<Sign> edge_signs(const GraphTile& tile, const valhalla::baldr::DirectedEdge& de);
+
+/// Helper function that returns signs of the node from its tile
+rust::Vec<Sign> node_signs(const GraphTile& tile, const valhalla::baldr::NodeInfo& node);
+
+/// Helper functions that return the admin record of the node or the start node of the edge, if it's from this tile
+rust::Vec<AdminInfo> node_admin(const GraphTile& tile, const valhalla::baldr::NodeInfo& node);
+rust::Vec<AdminInfo> edge_admin(const GraphTile& tile, const valhalla::baldr::DirectedEdge& de);
+
+/// Helper function that returns all admin records of the tile
+rust::Vec<AdminInfo> admins(const GraphTile& tile);
+
+/// Helper function that copies the tile header as `cxx` can't bind `baldr::GraphTileHeader` bit fields
+TileHeader tile_header(const GraphTile& tile);
+
+/// Helper functions that return transit records of the tile
+rust::Vec<TransitStop> transit_stops(const GraphTile& tile);
+rust::Vec<TransitDeparture> transit_departures(const GraphTile& tile);
+rust::Vec<TransitRoute> transit_routes(const GraphTile& tile);
+rust::Vec<TransitSchedule> transit_schedules(const GraphTile& tile);
+rust::Vec<TransitTransfer> transit_transfers(const GraphTile& tile);
+
+/// Helper function that returns turn lane masks of the edge from its tile
+rust::Vec<uint16_t> turn_lanes(const GraphTile& tile, const valhalla::baldr::DirectedEdge& de);
+
+/// Helper function that returns lane connectivity of the edge from its tile
+rust::Vec<LaneConnectivityEntry> lane_connectivity(const GraphTile& tile, const valhalla::baldr::DirectedEdge& de);
+
 /// Helper function that workarounds the inability to use `baldr::EdgeInfo` in Rust
 EdgeInfo edgeinfo(const GraphTile& tile, const valhalla::baldr::DirectedEdge& de);
 
+/// Offset of the edge info record within the edge info section of the tile, as `cxx` can't bind bit fields
+inline uint32_t edgeinfo_offset(const valhalla::baldr::DirectedEdge& de) {
+  return de.edgeinfo_offset();
+}
+
+/// Size of the tile data in bytes
+inline size_t tile_size(const GraphTile& tile) {
+  return tile.header()->end_offset();
+}
+
 /// Helper method that returns 0 if the edge is closed, 255 if live speed in unknown and speed in km/h otherwise
 uint8_t live_speed(const GraphTile& tile, const valhalla::baldr::DirectedEdge& de);
 
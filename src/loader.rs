@@ -0,0 +1,265 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{Error, GraphId, ffi};
+
+/// Source of raw graph tiles for [`crate::GraphReader::from_loader()`], e.g. an object storage or a cache.
+///
+/// Tiles are loaded on every [`crate::GraphReader::get_tile()`] call, so implementations are expected to cache
+/// them if loading is expensive.
+pub trait TileLoader: Send + Sync + 'static {
+    /// Contents of the tile file with the given tile base ID, see [`GraphId::file_suffix()`].
+    /// Returns `None` if the tile doesn't exist.
+    fn load(&self, id: GraphId) -> Option<Arc<[u8]>>;
+
+    /// Tile base IDs of all available tiles.
+    fn tiles(&self) -> Vec<GraphId>;
+}
+
+/// [`TileLoader`] that reads tiles from a directory with the same layout as `mjolnir.tile_dir`,
+/// e.g. `2/000/838/852.gph`.
+#[derive(Clone, Debug)]
+pub struct TileDir {
+    root: PathBuf,
+}
+
+impl TileDir {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Unpacks the tiles of a tile extract into `root`, e.g. to switch from `mjolnir.tile_extract` to
+    /// `mjolnir.tile_dir`, and returns a loader for them. Other files of the extract are skipped.
+    pub fn unpack(extract: impl AsRef<Path>, root: impl Into<PathBuf>) -> Result<Self, Error> {
+        let extract = extract.as_ref();
+        let data = fs::read(extract)
+            .map_err(|err| Error(format!("Failed to read {}: {err}", extract.display()).into()))?;
+        let dir = Self::new(root);
+        for entry in tar_entries(&data)? {
+            let path = dir.root.join(entry.id.file_suffix());
+            let write = || {
+                fs::create_dir_all(path.parent().unwrap_or(&dir.root))?;
+                fs::write(&path, &data[entry.offset..entry.offset + entry.size])
+            };
+            write().map_err(|err| {
+                Error(format!("Failed to write {}: {err}", path.display()).into())
+            })?;
+        }
+        Ok(dir)
+    }
+}
+
+impl TileLoader for TileDir {
+    fn load(&self, id: GraphId) -> Option<Arc<[u8]>> {
+        fs::read(self.root.join(id.file_suffix()))
+            .ok()
+            .map(Arc::from)
+    }
+
+    fn tiles(&self) -> Vec<GraphId> {
+        let mut tiles = Vec::new();
+        let mut dirs = vec![self.root.clone()];
+        while let Some(dir) = dirs.pop() {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    dirs.push(path);
+                } else if let Some(id) = relative_suffix(&self.root, &path) {
+                    tiles.push(id);
+                }
+            }
+        }
+        tiles
+    }
+}
+
+fn relative_suffix(root: &Path, path: &Path) -> Option<GraphId> {
+    GraphId::from_file_suffix(path.strip_prefix(root).ok()?.to_str()?)
+}
+
+/// Boxed [`TileLoader`] passed to C++ to load tiles on demand.
+pub(crate) struct TileSource(pub(crate) Box<dyn TileLoader>);
+
+/// Tile memory passed to C++, empty if the tile doesn't exist.
+pub(crate) struct TileBuffer(Option<Arc<[u8]>>);
+
+impl TileBuffer {
+    pub(crate) fn new(data: Arc<[u8]>) -> Self {
+        Self(Some(data))
+    }
+
+    pub(crate) fn data(&self) -> &[u8] {
+        self.0.as_deref().unwrap_or_default()
+    }
+}
+
+/// Tile is passed as a raw GraphId value, as the bridge can't pass shared structs to Rust functions.
+pub(crate) fn load_tile(source: &TileSource, tile: u64) -> Box<TileBuffer> {
+    Box::new(TileBuffer(source.0.load(GraphId::new(tile))))
+}
+
+/// Size of tar headers and the alignment of file contents.
+const BLOCK_SIZE: usize = 512;
+
+/// Finds positions of all `.gph` tiles in a tar archive. Names longer than the ustar header fits are read from
+/// GNU long name entries and PAX extended headers.
+pub(crate) fn tar_entries(data: &[u8]) -> Result<Vec<ffi::TileEntry>, Error> {
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    // Name of the next entry, set by a GNU long name entry or a PAX extended header
+    let mut next_name = None;
+    while offset + BLOCK_SIZE <= data.len() {
+        let header = &data[offset..offset + BLOCK_SIZE];
+        if header.iter().all(|&b| b == 0) {
+            break; // End of archive
+        }
+        let size = parse_octal(&header[124..136])
+            .ok_or_else(|| Error(format!("Invalid tar entry size at offset {offset}").into()))?;
+        let start = offset + BLOCK_SIZE;
+        if start + size > data.len() {
+            return Err(Error(
+                format!("Tar entry at offset {offset} is truncated").into(),
+            ));
+        }
+
+        let content = &data[start..start + size];
+        match header[156] {
+            b'L' => {
+                let name = c_string(content).ok_or_else(|| {
+                    Error(format!("Invalid GNU long name at offset {offset}").into())
+                })?;
+                next_name = Some(name);
+            }
+            b'x' => {
+                let records = pax_records(content).ok_or_else(|| {
+                    Error(format!("Invalid PAX extended header at offset {offset}").into())
+                })?;
+                if let Some((_, path)) = records.into_iter().rev().find(|(key, _)| *key == "path") {
+                    next_name = Some(path.to_string());
+                }
+            }
+            typeflag => {
+                let name = next_name.take().or_else(|| entry_name(header));
+                // Only regular files with names like `2/000/838/852.gph` are tiles
+                let is_file = matches!(typeflag, b'0' | 0);
+                if let Some(id) = name
+                    .filter(|_| is_file)
+                    .and_then(|name| GraphId::from_file_suffix(&name))
+                {
+                    entries.push(ffi::TileEntry {
+                        id,
+                        offset: start,
+                        size,
+                    });
+                }
+            }
+        }
+        offset = start + size.div_ceil(BLOCK_SIZE) * BLOCK_SIZE;
+    }
+    Ok(entries)
+}
+
+/// Name of the tar entry, including the ustar prefix if there is one.
+fn entry_name(header: &[u8]) -> Option<String> {
+    let field = |bytes: &[u8]| {
+        let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+        std::str::from_utf8(&bytes[..end]).ok().map(str::to_string)
+    };
+    let name = field(&header[0..100])?;
+    if &header[257..262] == b"ustar" {
+        let prefix = field(&header[345..500])?;
+        if !prefix.is_empty() {
+            return Some(format!("{prefix}/{name}"));
+        }
+    }
+    Some(name)
+}
+
+/// Contents of a GNU long name entry: the name, terminated by a NUL.
+fn c_string(content: &[u8]) -> Option<String> {
+    let end = content
+        .iter()
+        .position(|&b| b == 0)
+        .unwrap_or(content.len());
+    String::from_utf8(content[..end].to_vec()).ok()
+}
+
+/// Key-value records of a PAX extended header, each one formatted as `<length> <key>=<value>\n`, where the length
+/// includes the whole record.
+fn pax_records(mut content: &[u8]) -> Option<Vec<(&str, &str)>> {
+    let mut records = Vec::new();
+    while !content.is_empty() {
+        let space = content.iter().position(|&b| b == b' ')?;
+        let len: usize = std::str::from_utf8(&content[..space]).ok()?.parse().ok()?;
+        if len <= space || len > content.len() {
+            return None;
+        }
+        let record = std::str::from_utf8(&content[space + 1..len]).ok()?;
+        records.push(record.strip_suffix('\n')?.split_once('=')?);
+        content = &content[len..];
+    }
+    Some(records)
+}
+
+fn parse_octal(field: &[u8]) -> Option<usize> {
+    let digits = std::str::from_utf8(field)
+        .ok()?
+        .trim_matches(|c: char| c == '\0' || c == ' ');
+    usize::from_str_radix(digits, 8).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tar entry with the given header name and type, padded to whole blocks.
+    fn entry(name: &str, typeflag: u8, content: &[u8]) -> Vec<u8> {
+        let mut data = vec![0; BLOCK_SIZE];
+        data[..name.len()].copy_from_slice(name.as_bytes());
+        data[124..135].copy_from_slice(format!("{:011o}", content.len()).as_bytes());
+        data[156] = typeflag;
+        data.extend_from_slice(content);
+        data.resize(data.len().next_multiple_of(BLOCK_SIZE), 0);
+        data
+    }
+
+    #[test]
+    fn long_names() {
+        let tile = [1u8; 10];
+        let data = [
+            entry("2/000/838/852.gph", b'0', &tile),
+            entry("././@LongLink", b'L', b"tiles/2/000/762/485.gph\0"),
+            entry("tiles/2/000/762/4", b'0', &tile),
+            entry("PaxHeaders/015.gph", b'x', b"22 path=0/003/015.gph\n"),
+            entry("015.gph", b'0', &tile),
+            // Long names apply only to the next entry
+            entry("././@LongLink", b'L', b"1/047/701.gph\0"),
+            entry("1/047/701.gph", b'5', &[]),
+            entry("index.bin", b'0', &tile),
+        ]
+        .concat();
+
+        let entries: Vec<_> = tar_entries(&data)
+            .unwrap()
+            .into_iter()
+            .map(|entry| (entry.id.file_suffix(), entry.offset, entry.size))
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                ("2/000/838/852.gph".to_string(), 512, 10),
+                ("2/000/762/485.gph".to_string(), 5 * 512, 10),
+                ("0/003/015.gph".to_string(), 9 * 512, 10),
+            ]
+        );
+
+        let invalid = entry("PaxHeaders/015.gph", b'x', b"99 path=0/003/015.gph\n");
+        assert!(tar_entries(&invalid).is_err());
+    }
+}
@@ -0,0 +1,950 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+};
+
+use crate::{
+    Access, DirectedEdge, EdgeUse, Error, GraphId, GraphLevel, GraphReader, GraphTile, LatLon,
+    RoadClass,
+};
+
+/// Form of way of a road in OpenLR location references.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FormOfWay {
+    Undefined = 0,
+    Motorway = 1,
+    MultipleCarriageway = 2,
+    SingleCarriageway = 3,
+    Roundabout = 4,
+    TrafficSquare = 5,
+    SlipRoad = 6,
+    Other = 7,
+}
+
+impl FormOfWay {
+    fn from_code(code: u8) -> Self {
+        match code & 0x07 {
+            1 => FormOfWay::Motorway,
+            2 => FormOfWay::MultipleCarriageway,
+            3 => FormOfWay::SingleCarriageway,
+            4 => FormOfWay::Roundabout,
+            5 => FormOfWay::TrafficSquare,
+            6 => FormOfWay::SlipRoad,
+            7 => FormOfWay::Other,
+            _ => FormOfWay::Undefined,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "UNDEFINED" => FormOfWay::Undefined,
+            "MOTORWAY" => FormOfWay::Motorway,
+            "MULTIPLE_CARRIAGEWAY" => FormOfWay::MultipleCarriageway,
+            "SINGLE_CARRIAGEWAY" => FormOfWay::SingleCarriageway,
+            "ROUNDABOUT" => FormOfWay::Roundabout,
+            "TRAFFICSQUARE" | "TRAFFIC_SQUARE" => FormOfWay::TrafficSquare,
+            "SLIPROAD" | "SLIP_ROAD" => FormOfWay::SlipRoad,
+            "OTHER" => FormOfWay::Other,
+            _ => return None,
+        })
+    }
+
+    /// Form of way derived from the edge attributes, as Valhalla doesn't store it.
+    fn of_edge(edge: &DirectedEdge) -> Self {
+        let edge_use = edge.use_type();
+        if edge.roundabout() {
+            FormOfWay::Roundabout
+        } else if edge_use == EdgeUse::kRamp || edge_use == EdgeUse::kTurnChannel {
+            FormOfWay::SlipRoad
+        } else if edge.classification() == RoadClass::kMotorway {
+            FormOfWay::Motorway
+        } else if edge_use == EdgeUse::kRoad {
+            FormOfWay::SingleCarriageway
+        } else {
+            FormOfWay::Other
+        }
+    }
+
+    /// Whether the referenced form of way can describe a road with the derived one. Valhalla doesn't distinguish
+    /// single and multiple carriageways, so they are considered the same.
+    fn matches(self, derived: Self) -> bool {
+        let carriageway = |fow| {
+            matches!(
+                fow,
+                FormOfWay::MultipleCarriageway | FormOfWay::SingleCarriageway
+            )
+        };
+        self == FormOfWay::Undefined
+            || self == derived
+            || (carriageway(self) && carriageway(derived))
+    }
+}
+
+/// Point of an OpenLR line location reference with attributes of the road at the point.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LocationReferencePoint {
+    pub coordinate: LatLon,
+    /// Functional road class from 0 (main road) to 7 (other road), same values as [`RoadClass`].
+    pub frc: u8,
+    pub fow: FormOfWay,
+    /// Bearing in degrees of the road leaving the point, or entering it for the last point.
+    pub bearing: f64,
+    /// Lowest functional road class on the path to the next point, unused for the last point.
+    pub lfrcnp: u8,
+    /// Distance in meters to the next point along the path, zero for the last point.
+    pub dnp: f64,
+}
+
+/// OpenLR line location reference, see the [OpenLR white paper](https://www.openlr-association.com/fileadmin/user_upload/openlr-whitepaper_v1.5.pdf).
+#[derive(Clone, Debug, PartialEq)]
+pub struct LineLocationReference {
+    /// At least two points, the first and the last one are the ends of the path.
+    pub points: Vec<LocationReferencePoint>,
+    /// Distance in meters from the first point to the start of the location.
+    pub positive_offset: f64,
+    /// Distance in meters from the end of the location to the last point.
+    pub negative_offset: f64,
+}
+
+/// Size of the header, the first point with absolute coordinates and the last point with relative ones.
+const BINARY_MIN_SIZE: usize = 1 + 9 + 6;
+/// Size of an intermediate point with relative coordinates.
+const BINARY_POINT_SIZE: usize = 7;
+
+impl LineLocationReference {
+    /// Parses a line location in the OpenLR binary format version 3.
+    pub fn from_binary(data: &[u8]) -> Result<Self, Error> {
+        let invalid =
+            |reason: &str| Error(format!("Invalid OpenLR line location: {reason}").into());
+        if data.len() < BINARY_MIN_SIZE {
+            return Err(invalid("too short"));
+        }
+        if data[0] & 0x07 != 3 {
+            return Err(invalid("only version 3 is supported"));
+        }
+        // Attribute flag set, point and area flags unset
+        if data[0] & 0x78 != 0x08 {
+            return Err(invalid("not a line location"));
+        }
+
+        // Up to two offset bytes follow the points, flagged in the last attribute byte of the last point.
+        // Offset bytes may look like flags, so the number of offsets must also fit the number of points.
+        let offset_flags = |offsets: usize| {
+            let flags = data[data.len() - 1 - offsets];
+            usize::from(flags & 0x40 != 0) + usize::from(flags & 0x20 != 0)
+        };
+        // Offset bytes may not fit into a minimal-size reference at all.
+        let (offsets, intermediate) = (0..=2)
+            .find_map(|offsets| {
+                let intermediate = data.len().checked_sub(BINARY_MIN_SIZE + offsets)?;
+                (offset_flags(offsets) == offsets && intermediate % BINARY_POINT_SIZE == 0)
+                    .then_some((offsets, intermediate))
+            })
+            .ok_or_else(|| invalid("size doesn't match the points and offset flags"))?;
+        let count = intermediate / BINARY_POINT_SIZE + 2;
+
+        // Each point starts with coordinates, absolute for the first point and relative to the previous one for
+        // the others, followed by attributes
+        let mut points = Vec::with_capacity(count);
+        let mut coordinate = LatLon(absolute_degrees(&data[4..7]), absolute_degrees(&data[1..4]));
+        let mut position = 7;
+        for _ in 0..count - 1 {
+            let attributes = &data[position..position + 3];
+            points.push(LocationReferencePoint {
+                coordinate,
+                frc: (attributes[0] >> 3) & 0x07,
+                fow: FormOfWay::from_code(attributes[0]),
+                bearing: sector_degrees(attributes[1]),
+                lfrcnp: (attributes[1] >> 5) & 0x07,
+                dnp: (f64::from(attributes[2]) + 0.5) * 58.6,
+            });
+            let relative = &data[position + 3..position + 7];
+            coordinate = LatLon(
+                coordinate.0 + relative_degrees(&relative[2..4]),
+                coordinate.1 + relative_degrees(&relative[0..2]),
+            );
+            position += 7;
+        }
+
+        // Last point has only two attribute bytes, with offset flags in the second one
+        let (last, offset_bytes) = (&data[position..position + 2], &data[position + 2..]);
+        points.push(LocationReferencePoint {
+            coordinate,
+            frc: (last[0] >> 3) & 0x07,
+            fow: FormOfWay::from_code(last[0]),
+            bearing: sector_degrees(last[1]),
+            lfrcnp: 7,
+            dnp: 0.0,
+        });
+        let (has_positive, has_negative) = (last[1] & 0x40 != 0, last[1] & 0x20 != 0);
+
+        // Offsets are relative to the distance between the first two and the last two points
+        let offset = |byte: u8, dnp: f64| (f64::from(byte) + 0.5) / 256.0 * dnp;
+        let positive_offset = match has_positive {
+            true => offset(offset_bytes[0], points[0].dnp),
+            false => 0.0,
+        };
+        let negative_offset = match has_negative {
+            true => offset(offset_bytes[offsets - 1], points[count - 2].dnp),
+            false => 0.0,
+        };
+        Ok(Self {
+            points,
+            positive_offset,
+            negative_offset,
+        })
+    }
+
+    /// Parses a base64-encoded line location in the OpenLR binary format, as it's usually sent in traffic feeds.
+    pub fn from_base64(encoded: &str) -> Result<Self, Error> {
+        let data = decode_base64(encoded)
+            .ok_or_else(|| Error(format!("Invalid base64: {encoded}").into()))?;
+        Self::from_binary(&data)
+    }
+
+    /// Parses the `LineLocationReference` element of an OpenLR XML document. Elements are matched by their
+    /// local names, so namespace prefixes are not supported.
+    pub fn from_xml(xml: &str) -> Result<Self, Error> {
+        let invalid = |reason: String| Error(format!("Invalid OpenLR XML: {reason}").into());
+        let number = |xml: &str, tag: &str| {
+            xml_text(xml, tag)?
+                .parse::<f64>()
+                .map_err(|_| invalid(format!("<{tag}> is not a number")))
+        };
+        let frc = |xml: &str, tag: &str| {
+            xml_text(xml, tag)?
+                .strip_prefix("FRC")
+                .and_then(|frc| frc.parse::<u8>().ok())
+                .filter(|&frc| frc <= 7)
+                .ok_or_else(|| invalid(format!("<{tag}> is not a functional road class")))
+        };
+        let point = |xml: &str, last: bool| {
+            Ok::<_, Error>(LocationReferencePoint {
+                coordinate: LatLon(number(xml, "Latitude")?, number(xml, "Longitude")?),
+                frc: frc(xml, "FRC")?,
+                fow: FormOfWay::from_name(xml_text(xml, "FOW")?)
+                    .ok_or_else(|| invalid("<FOW> is not a form of way".into()))?,
+                bearing: number(xml, "BEAR")?,
+                lfrcnp: if last { 7 } else { frc(xml, "LFRCNP")? },
+                dnp: if last { 0.0 } else { number(xml, "DNP")? },
+            })
+        };
+
+        let location = xml_text(xml, "LineLocationReference")?;
+        let mut points = xml_elements(location, "LocationReferencePoint")
+            .map(|xml| point(xml, false))
+            .collect::<Result<Vec<_>, _>>()?;
+        points.push(point(
+            xml_text(location, "LastLocationReferencePoint")?,
+            true,
+        )?);
+        if points.len() < 2 {
+            return Err(invalid(
+                "at least two location reference points are required".into(),
+            ));
+        }
+
+        let offsets = xml_elements(location, "Offsets").next().unwrap_or_default();
+        let offset = |tag| match xml_elements(offsets, tag).next() {
+            Some(_) => number(offsets, tag),
+            None => Ok(0.0),
+        };
+        Ok(Self {
+            points,
+            positive_offset: offset("PosOff")?,
+            negative_offset: offset("NegOff")?,
+        })
+    }
+}
+
+/// Part of a directed edge covered by a decoded location.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EdgeSpan {
+    pub id: GraphId,
+    /// Fraction of the edge length where the location starts, `0.0` for all edges but the first one.
+    pub start: f64,
+    /// Fraction of the edge length where the location ends, `1.0` for all edges but the last one.
+    pub end: f64,
+}
+
+/// Line location resolved onto the graph by [`OpenLrDecoder`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct LineLocation {
+    /// Consecutive edges of the path in the driving direction.
+    pub edges: Vec<EdgeSpan>,
+    /// Length of the location in meters.
+    pub length: f64,
+}
+
+/// Resolves OpenLR line locations onto graph edges, so traffic feeds that reference roads this way can be applied
+/// to the graph.
+///
+/// Each location reference point is matched to nearby edges with similar bearing, functional road class (derived
+/// from [`RoadClass`]) and form of way (derived from [`EdgeUse`]), and consecutive points are connected by the
+/// shortest path whose length is close to the referenced distance.
+/// ```rust
+/// use valhalla::{Config, GraphReader, LineLocationReference, OpenLrDecoder};
+///
+/// let reader = GraphReader::new(&Config::from_file("path/to/config.json").unwrap()).unwrap();
+/// let reference = LineLocationReference::from_base64("CwB67CGukRxiCACyAbwaMXU=").unwrap();
+/// let location = OpenLrDecoder::new(&reader).decode(&reference).unwrap();
+/// for span in location.edges {
+///     println!("{} from {:.2} to {:.2}", span.id, span.start, span.end);
+/// }
+/// ```
+pub struct OpenLrDecoder<'a> {
+    reader: &'a GraphReader,
+    search_radius: f64,
+    max_bearing_delta: f64,
+    frc_tolerance: u8,
+    max_candidates: usize,
+}
+
+impl<'a> OpenLrDecoder<'a> {
+    pub fn new(reader: &'a GraphReader) -> Self {
+        Self {
+            reader,
+            search_radius: 100.0,
+            max_bearing_delta: 45.0,
+            frc_tolerance: 2,
+            max_candidates: 5,
+        }
+    }
+
+    /// Maximum distance in meters between a location reference point and its candidate edges, 100 by default.
+    pub fn search_radius(mut self, meters: f64) -> Self {
+        self.search_radius = meters;
+        self
+    }
+
+    /// Maximum difference in degrees between the referenced bearing and the bearing of candidate edges,
+    /// 45 by default.
+    pub fn max_bearing_delta(mut self, degrees: f64) -> Self {
+        self.max_bearing_delta = degrees;
+        self
+    }
+
+    /// Number of classes the path between two points may go below the lowest referenced class, 2 by default.
+    pub fn frc_tolerance(mut self, classes: u8) -> Self {
+        self.frc_tolerance = classes;
+        self
+    }
+
+    /// Number of best candidate edges per point that are tried to find a path, 5 by default.
+    pub fn max_candidates(mut self, count: usize) -> Self {
+        self.max_candidates = count.max(1);
+        self
+    }
+
+    /// Resolves the location onto the graph. Fails if there are no candidate edges near any of the points
+    /// or no path between consecutive points matches the referenced distance.
+    pub fn decode(&self, reference: &LineLocationReference) -> Result<LineLocation, Error> {
+        let points = &reference.points;
+        if points.len() < 2 {
+            return Err(Error("Line location requires at least two points".into()));
+        }
+
+        let mut tiles = TileCache::new(self.reader);
+        let candidates = points
+            .iter()
+            .enumerate()
+            .map(|(index, point)| {
+                let candidates = self.candidates(&mut tiles, point, index + 1 == points.len());
+                if candidates.is_empty() {
+                    Err(Error(
+                        format!("No candidate edges for location reference point {index}").into(),
+                    ))
+                } else {
+                    Ok(candidates)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Paths are chained greedily: the end of each path is the start of the next one
+        let mut path: Vec<(GraphId, f64)> = Vec::new();
+        let mut starts = candidates[0].clone();
+        let mut first = None;
+        for (index, point) in points[..points.len() - 1].iter().enumerate() {
+            let mut pairs: Vec<_> = starts
+                .iter()
+                .flat_map(|start| candidates[index + 1].iter().map(move |end| (start, end)))
+                .collect();
+            pairs.sort_by(|a, b| (a.0.score + a.1.score).total_cmp(&(b.0.score + b.1.score)));
+
+            let tolerance = point.dnp * 0.25 + 60.0;
+            let found = pairs.into_iter().find_map(|(start, end)| {
+                let lowest_frc = point.lfrcnp.saturating_add(self.frc_tolerance);
+                let (edges, length) =
+                    self.route(&mut tiles, start, end, lowest_frc, point.dnp + tolerance)?;
+                ((length - point.dnp).abs() <= tolerance)
+                    .then(|| (start.clone(), end.clone(), edges))
+            });
+            let Some((start, end, edges)) = found else {
+                return Err(Error(
+                    format!(
+                        "No path between location reference points {index} and {}",
+                        index + 1
+                    )
+                    .into(),
+                ));
+            };
+
+            // Consecutive paths share the edge of the point between them
+            let skip = usize::from(!path.is_empty());
+            path.extend(edges.into_iter().skip(skip));
+            first.get_or_insert(start);
+            starts = vec![end];
+        }
+        let first = first.expect("There are at least two points");
+        let last = &starts[0];
+
+        // Location in meters along the path, from the first point projection to the last one, minus offsets
+        let total: f64 = path.iter().map(|(_, length)| length).sum();
+        let from = first.along + reference.positive_offset;
+        let to = total - (last.length - last.along) - reference.negative_offset;
+        if to <= from {
+            return Err(Error("Offsets are longer than the decoded location".into()));
+        }
+
+        let mut edges = Vec::new();
+        let mut position = 0.0;
+        for (id, length) in path {
+            let (start, end) = (from.max(position), to.min(position + length));
+            if end > start && length > 0.0 {
+                edges.push(EdgeSpan {
+                    id,
+                    start: (start - position) / length,
+                    end: (end - position) / length,
+                });
+            }
+            position += length;
+        }
+        Ok(LineLocation {
+            edges,
+            length: to - from,
+        })
+    }
+
+    /// Best matching edges for the point, sorted by score.
+    fn candidates(
+        &self,
+        tiles: &mut TileCache,
+        point: &LocationReferencePoint,
+        last: bool,
+    ) -> Vec<Candidate> {
+        let ll = point.coordinate;
+        let lat_delta = self.search_radius / METERS_PER_DEGREE;
+        let lon_delta = lat_delta / ll.0.to_radians().cos().max(0.01);
+        let (min, max) = (
+            LatLon(ll.0 - lat_delta, ll.1 - lon_delta),
+            LatLon(ll.0 + lat_delta, ll.1 + lon_delta),
+        );
+
+        let mut candidates = Vec::new();
+        for level in [GraphLevel::Highway, GraphLevel::Arterial, GraphLevel::Local] {
+            for tile_id in self.reader.tiles_in_bbox(min, max, level) {
+                let Some(tile) = tiles.get(tile_id) else {
+                    continue;
+                };
+                for (index, edge) in tile.directededges().iter().enumerate() {
+                    if edge.is_shortcut() || !edge.forwardaccess().contains(Access::AUTO) {
+                        continue;
+                    }
+                    // Cheap check by the end node before decoding the shape
+                    let length = f64::from(edge.length());
+                    let end_node = tiles.node_latlon(edge.endnode());
+                    if end_node.is_none_or(|end| distance(end, ll) > self.search_radius + length) {
+                        continue;
+                    }
+
+                    let shape = tile.edgeinfo(edge).shape_points();
+                    let (offset, along) = project(&shape, ll);
+                    if offset > self.search_radius {
+                        continue;
+                    }
+                    // Road has to leave the point, or enter it for the last point
+                    let shape_length = polyline_length(&shape);
+                    let remaining = if last { along } else { shape_length - along };
+                    if remaining < MIN_REMAINING_LENGTH {
+                        continue;
+                    }
+                    let bearing = if last {
+                        bearing_along(&shape, along, -BEARING_DISTANCE)
+                    } else {
+                        bearing_along(&shape, along, BEARING_DISTANCE)
+                    };
+                    let bearing_delta = angle_delta(bearing, point.bearing);
+                    if bearing_delta > self.max_bearing_delta {
+                        continue;
+                    }
+
+                    let frc = edge.classification().repr;
+                    let fow_penalty = match point.fow.matches(FormOfWay::of_edge(edge)) {
+                        true => 0.0,
+                        false => 0.25,
+                    };
+                    candidates.push(Candidate {
+                        edge: tile.edge_id(index as u32),
+                        length,
+                        along: match shape_length > 0.0 {
+                            true => along / shape_length * length,
+                            false => 0.0,
+                        },
+                        score: offset / self.search_radius
+                            + bearing_delta / self.max_bearing_delta
+                            + f64::from(frc.abs_diff(point.frc)) * 0.25
+                            + fow_penalty,
+                    });
+                }
+            }
+        }
+        candidates.sort_by(|a, b| a.score.total_cmp(&b.score));
+        candidates.truncate(self.max_candidates);
+        candidates
+    }
+
+    /// Shortest path from the start candidate to the end candidate that doesn't use roads below `lowest_frc`.
+    /// Returns edges with their lengths and the path length between the candidate projections.
+    fn route(
+        &self,
+        tiles: &mut TileCache,
+        start: &Candidate,
+        end: &Candidate,
+        lowest_frc: u8,
+        max_length: f64,
+    ) -> Option<(Vec<(GraphId, f64)>, f64)> {
+        if start.edge == end.edge && end.along >= start.along {
+            return Some((vec![(start.edge, start.length)], end.along - start.along));
+        }
+
+        // Labels of reached edges, costs are measured at the end of the edge
+        let mut labels = vec![Label {
+            edge: start.edge,
+            length: start.length,
+            previous: None,
+        }];
+        let mut costs = HashMap::from([(start.edge.value, start.length - start.along)]);
+        let mut queue = BinaryHeap::from([QueueItem {
+            cost: start.length - start.along,
+            label: 0,
+        }]);
+        let mut best: Option<(f64, usize)> = None;
+
+        while let Some(QueueItem { cost, label }) = queue.pop() {
+            if cost > max_length || best.is_some_and(|(best, _)| cost >= best) {
+                break;
+            }
+            let edge_id = labels[label].edge;
+            let Some(end_node) = tiles
+                .get(edge_id.tile())
+                .and_then(|tile| tile.directededge(edge_id.id()).map(|edge| edge.endnode()))
+            else {
+                continue;
+            };
+
+            for node_id in tiles.with_transitions(end_node) {
+                let Some(tile) = tiles.get(node_id.tile()) else {
+                    continue;
+                };
+                let Some(node) = tile.node(node_id.id()) else {
+                    continue;
+                };
+                for index in node.edge_index()..node.edge_index() + node.edge_count() {
+                    let Some(edge) = tile.directededge(index) else {
+                        continue;
+                    };
+                    let id = tile.edge_id(index);
+                    if id == end.edge {
+                        let total = cost + end.along;
+                        if best.is_none_or(|(best, _)| total < best) {
+                            best = Some((total, label));
+                        }
+                        continue;
+                    }
+                    if edge.is_shortcut()
+                        || !edge.forwardaccess().contains(Access::AUTO)
+                        || edge.classification().repr > lowest_frc
+                    {
+                        continue;
+                    }
+
+                    let length = f64::from(edge.length());
+                    let next_cost = cost + length;
+                    if costs
+                        .get(&id.value)
+                        .is_some_and(|&known| known <= next_cost)
+                    {
+                        continue;
+                    }
+                    costs.insert(id.value, next_cost);
+                    labels.push(Label {
+                        edge: id,
+                        length,
+                        previous: Some(label),
+                    });
+                    queue.push(QueueItem {
+                        cost: next_cost,
+                        label: labels.len() - 1,
+                    });
+                }
+            }
+        }
+
+        let (length, mut label) = best?;
+        let mut edges = vec![(end.edge, end.length)];
+        loop {
+            edges.push((labels[label].edge, labels[label].length));
+            match labels[label].previous {
+                Some(previous) => label = previous,
+                None => break,
+            }
+        }
+        edges.reverse();
+        Some((edges, length))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Candidate {
+    edge: GraphId,
+    /// Edge length in meters.
+    length: f64,
+    /// Distance in meters from the start of the edge to the projection of the point.
+    along: f64,
+    /// Lower is better.
+    score: f64,
+}
+
+struct Label {
+    edge: GraphId,
+    length: f64,
+    previous: Option<usize>,
+}
+
+/// Min-heap item ordered by cost.
+struct QueueItem {
+    cost: f64,
+    label: usize,
+}
+
+impl PartialEq for QueueItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for QueueItem {}
+
+impl PartialOrd for QueueItem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueueItem {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+/// Tiles loaded while decoding a single location.
+struct TileCache<'a> {
+    reader: &'a GraphReader,
+    tiles: HashMap<u64, Option<GraphTile>>,
+}
+
+impl<'a> TileCache<'a> {
+    fn new(reader: &'a GraphReader) -> Self {
+        Self {
+            reader,
+            tiles: HashMap::new(),
+        }
+    }
+
+    fn get(&mut self, id: GraphId) -> Option<GraphTile> {
+        let id = id.tile();
+        self.tiles
+            .entry(id.value)
+            .or_insert_with(|| self.reader.get_tile(id))
+            .clone()
+    }
+
+    fn node_latlon(&mut self, id: GraphId) -> Option<LatLon> {
+        let tile = self.get(id)?;
+        Some(tile.node_latlon(tile.node(id.id())?))
+    }
+
+    /// The node and the same node on other hierarchy levels.
+    fn with_transitions(&mut self, id: GraphId) -> Vec<GraphId> {
+        let mut nodes = vec![id];
+        if let Some(tile) = self.get(id) {
+            if let Some(node) = tile.node(id.id()) {
+                nodes.extend(tile.node_transitions(node));
+            }
+        }
+        nodes
+    }
+}
+
+const METERS_PER_DEGREE: f64 = 111_195.0;
+const EARTH_RADIUS: f64 = 6_371_000.0;
+/// Distance along the road used to compute its bearing at a location reference point, as defined by OpenLR.
+const BEARING_DISTANCE: f64 = 20.0;
+/// Candidate edges that only touch the point with one of their ends have no meaningful bearing.
+const MIN_REMAINING_LENGTH: f64 = 0.5;
+
+fn distance(a: LatLon, b: LatLon) -> f64 {
+    let (lat1, lat2) = (a.0.to_radians(), b.0.to_radians());
+    let dlat = lat2 - lat1;
+    let dlon = (b.1 - a.1).to_radians();
+    let h = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS * h.sqrt().asin()
+}
+
+/// Bearing in degrees clockwise from north.
+fn bearing(from: LatLon, to: LatLon) -> f64 {
+    let (lat1, lat2) = (from.0.to_radians(), to.0.to_radians());
+    let dlon = (to.1 - from.1).to_radians();
+    let y = dlon.sin() * lat2.cos();
+    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * dlon.cos();
+    y.atan2(x).to_degrees().rem_euclid(360.0)
+}
+
+fn angle_delta(a: f64, b: f64) -> f64 {
+    let delta = (a - b).rem_euclid(360.0);
+    delta.min(360.0 - delta)
+}
+
+fn polyline_length(shape: &[LatLon]) -> f64 {
+    shape.windows(2).map(|w| distance(w[0], w[1])).sum()
+}
+
+/// Distance from the point to the closest point of the shape, and distance along the shape to that closest point.
+fn project(shape: &[LatLon], point: LatLon) -> (f64, f64) {
+    let mut best = (f64::INFINITY, 0.0);
+    let mut along = 0.0;
+    for segment in shape.windows(2) {
+        let (a, b) = (segment[0], segment[1]);
+        // Local equirectangular projection is precise enough at the scale of a single segment
+        let scale = a.0.to_radians().cos();
+        let (bx, by) = ((b.1 - a.1) * scale, b.0 - a.0);
+        let (px, py) = ((point.1 - a.1) * scale, point.0 - a.0);
+        let squared = bx * bx + by * by;
+        let t = match squared > 0.0 {
+            true => ((px * bx + py * by) / squared).clamp(0.0, 1.0),
+            false => 0.0,
+        };
+        let closest = LatLon(a.0 + t * (b.0 - a.0), a.1 + t * (b.1 - a.1));
+        let offset = distance(closest, point);
+        let length = distance(a, b);
+        if offset < best.0 {
+            best = (offset, along + t * length);
+        }
+        along += length;
+    }
+    if shape.len() == 1 {
+        best = (distance(shape[0], point), 0.0);
+    }
+    best
+}
+
+/// Point at the distance along the shape, clamped to its ends.
+fn point_along(shape: &[LatLon], target: f64) -> LatLon {
+    let mut along = 0.0;
+    for segment in shape.windows(2) {
+        let length = distance(segment[0], segment[1]);
+        if along + length >= target && length > 0.0 {
+            let t = ((target - along) / length).clamp(0.0, 1.0);
+            return LatLon(
+                segment[0].0 + t * (segment[1].0 - segment[0].0),
+                segment[0].1 + t * (segment[1].1 - segment[0].1),
+            );
+        }
+        along += length;
+    }
+    match target <= 0.0 {
+        true => shape.first().copied().unwrap_or(LatLon(0.0, 0.0)),
+        false => shape.last().copied().unwrap_or(LatLon(0.0, 0.0)),
+    }
+}
+
+/// Bearing from the point at `along` to the point `offset` meters further, or back for negative offsets.
+fn bearing_along(shape: &[LatLon], along: f64, offset: f64) -> f64 {
+    bearing(
+        point_along(shape, along),
+        point_along(shape, along + offset),
+    )
+}
+
+/// Absolute coordinate in the binary format: 24-bit big-endian signed integer.
+fn absolute_degrees(bytes: &[u8]) -> f64 {
+    let value = i32::from_be_bytes([bytes[0], bytes[1], bytes[2], 0]) >> 8;
+    (f64::from(value) - f64::from(value.signum()) * 0.5) * 360.0 / f64::from(1 << 24)
+}
+
+/// Relative coordinate in the binary format: 16-bit big-endian signed integer in 1e-5 degrees.
+fn relative_degrees(bytes: &[u8]) -> f64 {
+    f64::from(i16::from_be_bytes([bytes[0], bytes[1]])) / 100_000.0
+}
+
+/// Bearing in the binary format: middle of one of 32 sectors, stored in the lowest 5 bits.
+fn sector_degrees(byte: u8) -> f64 {
+    (f64::from(byte & 0x1f) + 0.5) * 11.25
+}
+
+/// Trimmed contents of the first element with the tag name.
+fn xml_text<'x>(xml: &'x str, tag: &str) -> Result<&'x str, Error> {
+    xml_elements(xml, tag)
+        .next()
+        .map(str::trim)
+        .ok_or_else(|| Error(format!("Invalid OpenLR XML: missing <{tag}>").into()))
+}
+
+/// Contents of all elements with the tag name, not including nested elements with the same name.
+fn xml_elements<'x>(xml: &'x str, tag: &str) -> impl Iterator<Item = &'x str> {
+    let (open, close) = (format!("<{tag}"), format!("</{tag}>"));
+    let mut rest = xml;
+    std::iter::from_fn(move || {
+        loop {
+            let start = rest.find(&open)?;
+            let after = &rest[start + open.len()..];
+            // Skip elements that only start with the same name, e.g. `<FOW>` when looking for `<FO`
+            let Some(end_of_tag) = after.find('>') else {
+                rest = "";
+                return None;
+            };
+            if !after.starts_with(['>', ' ', '\t', '\r', '\n', '/']) {
+                rest = after;
+                continue;
+            }
+            if after[..end_of_tag].ends_with('/') {
+                // Self-closing element
+                rest = &after[end_of_tag + 1..];
+                return Some("");
+            }
+            let content = &after[end_of_tag + 1..];
+            let end = content.find(&close)?;
+            rest = &content[end + close.len()..];
+            return Some(&content[..end]);
+        }
+    })
+}
+
+fn decode_base64(encoded: &str) -> Option<Vec<u8>> {
+    let value = |c: u8| match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'+' | b'-' => Some(62),
+        b'/' | b'_' => Some(63),
+        _ => None,
+    };
+    let encoded = encoded.trim().trim_end_matches('=');
+    let mut data = Vec::with_capacity(encoded.len() * 3 / 4);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for c in encoded.bytes() {
+        buffer = (buffer << 6) | u32::from(value(c)?);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            data.push((buffer >> bits) as u8);
+        }
+    }
+    Some(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binary_line_location() {
+        // Example from the OpenLR white paper, section 7.5
+        let reference =
+            LineLocationReference::from_base64("CwRbWyNG9RpsCQCb/jsbtAT/6/+jK1lE").unwrap();
+        assert_eq!(reference.points.len(), 3);
+
+        let first = &reference.points[0];
+        assert!((first.coordinate.0 - 49.60852).abs() < 1e-5);
+        assert!((first.coordinate.1 - 6.12682).abs() < 1e-5);
+        assert_eq!(first.frc, 3);
+        assert_eq!(first.fow, FormOfWay::MultipleCarriageway);
+        assert_eq!(first.bearing, 140.625);
+        assert_eq!(first.lfrcnp, 3);
+        assert_eq!(first.dnp, 556.7);
+
+        let last = &reference.points[2];
+        assert!((last.coordinate.0 - 49.60306).abs() < 1e-5);
+        assert!((last.coordinate.1 - 6.12816).abs() < 1e-5);
+        assert_eq!(last.frc, 5);
+        assert_eq!(last.fow, FormOfWay::SingleCarriageway);
+        assert_eq!(last.dnp, 0.0);
+
+        assert!((reference.positive_offset - 150.0).abs() < 5.0);
+        assert_eq!(reference.negative_offset, 0.0);
+
+        assert!(LineLocationReference::from_binary(&[0x0b; 3]).is_err());
+
+        // Sizes that don't fit any number of points and offsets
+        let mut data = decode_base64("CwRbWyNG9RpsCQCb/jsbtAT/6/+jK1lE").unwrap();
+        for size in [19, 20, 21, 22, 26] {
+            data.resize(size, 0);
+            assert!(LineLocationReference::from_binary(&data).is_err());
+        }
+
+        // Minimal size with offset flags set leaves no room for the offset bytes
+        let mut data = [0; BINARY_MIN_SIZE];
+        (data[0], data[13], data[14], data[15]) = (0x0b, 0x60, 0x40, 0x40);
+        assert!(LineLocationReference::from_binary(&data).is_err());
+    }
+
+    #[test]
+    fn xml_line_location() {
+        let point = |lon, lat, frc, bear, path| {
+            format!(
+                "<Coordinates><Longitude>{lon}</Longitude><Latitude>{lat}</Latitude></Coordinates>\
+                 <LineAttributes><FRC>FRC{frc}</FRC><FOW>SINGLE_CARRIAGEWAY</FOW><BEAR>{bear}</BEAR></LineAttributes>\
+                 {path}"
+            )
+        };
+        let xml = format!(
+            "<OpenLR><XMLLocationReference><LineLocationReference>\
+             <LocationReferencePoint>{}</LocationReferencePoint>\
+             <LastLocationReferencePoint>{}</LastLocationReferencePoint>\
+             <Offsets><PosOff>12</PosOff></Offsets>\
+             </LineLocationReference></XMLLocationReference></OpenLR>",
+            point(
+                1.5,
+                42.5,
+                3,
+                90,
+                "<PathAttributes><LFRCNP>FRC4</LFRCNP><DNP>250</DNP></PathAttributes>"
+            ),
+            point(1.503, 42.5, 4, 270, ""),
+        );
+        let reference = LineLocationReference::from_xml(&xml).unwrap();
+        assert_eq!(
+            reference.points,
+            [
+                LocationReferencePoint {
+                    coordinate: LatLon(42.5, 1.5),
+                    frc: 3,
+                    fow: FormOfWay::SingleCarriageway,
+                    bearing: 90.0,
+                    lfrcnp: 4,
+                    dnp: 250.0,
+                },
+                LocationReferencePoint {
+                    coordinate: LatLon(42.5, 1.503),
+                    frc: 4,
+                    fow: FormOfWay::SingleCarriageway,
+                    bearing: 270.0,
+                    lfrcnp: 7,
+                    dnp: 0.0,
+                },
+            ]
+        );
+        assert_eq!(reference.positive_offset, 12.0);
+        assert_eq!(reference.negative_offset, 0.0);
+    }
+}
@@ -0,0 +1,147 @@
+use rayon::prelude::*;
+
+use crate::{Access, DirectedEdge, GraphId, GraphLevel, GraphReader, GraphTile, NodeInfo};
+
+/// Filter for [`GraphReader::par_edges()`] and [`GraphReader::par_nodes()`]. Default filter passes everything.
+/// ```rust
+/// use valhalla::{Access, GraphFilter, GraphLevel};
+///
+/// let filter = GraphFilter::default()
+///     .level(GraphLevel::Local)
+///     .skip_shortcuts()
+///     .access(Access::AUTO | Access::TRUCK);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct GraphFilter {
+    level: Option<GraphLevel>,
+    skip_shortcuts: bool,
+    access: Option<Access>,
+}
+
+impl GraphFilter {
+    /// Only tiles of the given hierarchy level.
+    pub fn level(mut self, level: GraphLevel) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Skip shortcut edges, which duplicate sequences of regular edges on higher levels.
+    pub fn skip_shortcuts(mut self) -> Self {
+        self.skip_shortcuts = true;
+        self
+    }
+
+    /// Only edges and nodes that allow any of the given modes, in any direction for edges.
+    pub fn access(mut self, access: Access) -> Self {
+        self.access = Some(access);
+        self
+    }
+
+    fn tile(&self, id: GraphId) -> bool {
+        self.level
+            .is_none_or(|level| id.level() == level.repr as u32)
+    }
+
+    fn edge(&self, edge: &DirectedEdge) -> bool {
+        (!self.skip_shortcuts || !edge.is_shortcut())
+            && self.access.is_none_or(|access| {
+                (edge.forwardaccess() | edge.reverseaccess()).intersects(access)
+            })
+    }
+
+    fn node(&self, node: &NodeInfo) -> bool {
+        self.access
+            .is_none_or(|access| Access::from_bits_retain(node.access()).intersects(access))
+    }
+}
+
+/// Directed edge yielded by [`GraphReader::par_edges()`], together with the tile that owns it.
+#[derive(Clone)]
+pub struct EdgeRef {
+    pub id: GraphId,
+    tile: GraphTile,
+}
+
+impl EdgeRef {
+    pub fn edge(&self) -> &DirectedEdge {
+        &self.tile.directededges()[self.id.id() as usize]
+    }
+
+    pub fn tile(&self) -> &GraphTile {
+        &self.tile
+    }
+}
+
+/// Node yielded by [`GraphReader::par_nodes()`], together with the tile that owns it.
+#[derive(Clone)]
+pub struct NodeRef {
+    pub id: GraphId,
+    tile: GraphTile,
+}
+
+impl NodeRef {
+    pub fn node(&self) -> &NodeInfo {
+        &self.tile.nodes()[self.id.id() as usize]
+    }
+
+    pub fn tile(&self) -> &GraphTile {
+        &self.tile
+    }
+}
+
+impl GraphReader {
+    /// Loads all tiles passing the `filter` in parallel.
+    pub fn par_tiles(&self, filter: &GraphFilter) -> impl ParallelIterator<Item = GraphTile> + '_ {
+        let tiles: Vec<_> = self
+            .tiles()
+            .into_iter()
+            .filter(|&id| filter.tile(id))
+            .collect();
+        tiles.into_par_iter().filter_map(|id| self.get_tile(id))
+    }
+
+    /// Iterates over all directed edges of all tiles in parallel.
+    /// ```rust
+    /// use rayon::prelude::*;
+    ///
+    /// let config = valhalla::Config::from_file("path/to/config.json").unwrap();
+    /// let reader = valhalla::GraphReader::new(&config).unwrap();
+    /// let filter = valhalla::GraphFilter::default().skip_shortcuts();
+    /// let length: u64 = reader
+    ///     .par_edges(&filter)
+    ///     .map(|edge| edge.edge().length() as u64)
+    ///     .sum();
+    /// ```
+    pub fn par_edges<'a>(
+        &'a self,
+        filter: &'a GraphFilter,
+    ) -> impl ParallelIterator<Item = EdgeRef> + 'a {
+        self.par_tiles(filter).flat_map_iter(move |tile| {
+            let ids: Vec<_> = (0..tile.directededges().len() as u32)
+                .filter(|&index| filter.edge(&tile.directededges()[index as usize]))
+                .map(|index| tile.edge_id(index))
+                .collect();
+            ids.into_iter().map(move |id| EdgeRef {
+                id,
+                tile: tile.clone(),
+            })
+        })
+    }
+
+    /// Iterates over all nodes of all tiles in parallel.
+    pub fn par_nodes<'a>(
+        &'a self,
+        filter: &'a GraphFilter,
+    ) -> impl ParallelIterator<Item = NodeRef> + 'a {
+        self.par_tiles(filter).flat_map_iter(move |tile| {
+            let ids: Vec<_> = (0..tile.nodes().len() as u32)
+                .filter(|&index| filter.node(&tile.nodes()[index as usize]))
+                .map(|index| tile.node_id(index))
+                .collect();
+            ids.into_iter().map(move |id| NodeRef {
+                id,
+                tile: tile.clone(),
+            })
+        })
+    }
+}
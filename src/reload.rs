@@ -0,0 +1,359 @@
+use std::{
+    path::PathBuf,
+    sync::{
+        Arc, Mutex, RwLock,
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use crate::{Actor, Config, Error, GraphReader};
+
+/// [`GraphReader`] handle that can switch to updated tile and traffic extracts without restarting the process.
+///
+/// New tileset is loaded while the current one keeps serving requests, and then atomically swapped, so readers
+/// returned by [`ReloadableGraphReader::current()`] before and [`GraphTile`](crate::GraphTile)s obtained from
+/// them stay valid. Extracts are memory-mapped, so new versions must be published by renaming a new file over
+/// the old one rather than by overwriting it in place.
+///
+/// Reloads first compare the metadata of the configured files with the loaded ones and skip loading if nothing
+/// changed. An [`Actor`] keeps the tileset it was created with, so use a [`ReloadableActor`] to route on reloaded
+/// tiles.
+/// ```rust
+/// use std::time::Duration;
+///
+/// let config = valhalla::Config::from_file("path/to/config.json").unwrap();
+/// let reader = valhalla::ReloadableGraphReader::new(&config).unwrap();
+/// let _watcher = reader.watch(Duration::from_secs(30), |result| {
+///     if let Err(err) = result {
+///         eprintln!("Failed to reload tiles: {err}");
+///     }
+/// });
+/// let tile = reader.current().get_tile(valhalla::GraphId::new(0));
+/// ```
+#[derive(Clone)]
+pub struct ReloadableGraphReader {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    /// Locked for the whole reload, so concurrent reloads don't load the same extracts twice.
+    state: Mutex<State>,
+    current: RwLock<Current>,
+    /// Number of live [`ReloadableActor`]s, to build that many actors for a new tileset during the reload.
+    actor_count: AtomicUsize,
+}
+
+/// Swapped as a whole, so actors are created from the configuration of the reader they follow.
+struct Current {
+    reader: GraphReader,
+    config: Config,
+    /// Incremented on every swap.
+    generation: u64,
+    /// Actors built for this tileset during the reload, taken by [`ReloadableActor`]s on their next request.
+    actors: Mutex<Vec<Actor>>,
+}
+
+/// Always refers to the same configuration as [`Current`].
+struct State {
+    config: Config,
+    /// Metadata of the loaded extract files, to skip loading them again if they didn't change.
+    stamps: [Option<FileStamp>; 2],
+    fingerprint: Fingerprint,
+}
+
+/// Identifies loaded data: tiles by their dataset ID, traffic by the extract file metadata, as it doesn't
+/// change the dataset ID.
+#[derive(Clone, Debug, PartialEq)]
+struct Fingerprint {
+    dataset_id: u64,
+    traffic: Option<FileStamp>,
+}
+
+/// Result of a successful [`ReloadableGraphReader::reload()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReloadOutcome {
+    /// New data was loaded and swapped in.
+    Reloaded { previous: u64, current: u64 },
+    /// Extracts contain the same data as the current tileset, which is kept.
+    Unchanged { dataset_id: u64 },
+}
+
+impl ReloadableGraphReader {
+    /// Loads the tileset from the given configuration. Subsequent reloads read the same paths.
+    pub fn new(config: &Config) -> Result<Self, Error> {
+        let reader = GraphReader::new(config)?;
+        let fingerprint = fingerprint(config, &reader);
+        Ok(Self {
+            inner: Arc::new(Inner {
+                state: Mutex::new(State {
+                    config: config.clone(),
+                    stamps: file_stamps(config),
+                    fingerprint,
+                }),
+                current: RwLock::new(Current {
+                    reader,
+                    config: config.clone(),
+                    generation: 0,
+                    actors: Mutex::new(Vec::new()),
+                }),
+                actor_count: AtomicUsize::new(0),
+            }),
+        })
+    }
+
+    /// Currently active tileset. Cheap to call, the returned reader is not affected by later reloads.
+    pub fn current(&self) -> GraphReader {
+        self.read_current().reader.clone()
+    }
+
+    /// Configuration of the current tileset.
+    pub fn config(&self) -> Config {
+        self.read_current().config.clone()
+    }
+
+    /// Loads extracts from the configured paths again and swaps them in if they contain different data.
+    /// On error the current tileset is kept.
+    pub fn reload(&self) -> Result<ReloadOutcome, Error> {
+        let mut state = self.lock_state();
+        let config = state.config.clone();
+        self.reload_locked(&mut state, config)
+    }
+
+    /// Same as [`ReloadableGraphReader::reload()`], but switches to a new configuration, e.g. pointing to a new
+    /// versioned extract. The configuration is kept only if loading succeeds. If the data is unchanged, only actors
+    /// created after the reload use the new configuration.
+    pub fn reload_with(&self, config: &Config) -> Result<ReloadOutcome, Error> {
+        let mut state = self.lock_state();
+        self.reload_locked(&mut state, config.clone())
+    }
+
+    /// Runs [`ReloadableGraphReader::reload()`] in a background thread.
+    pub fn reload_in_background(&self) -> thread::JoinHandle<Result<ReloadOutcome, Error>> {
+        let reader = self.clone();
+        thread::spawn(move || reader.reload())
+    }
+
+    /// Starts a background thread that checks configured extract files every `interval` and reloads them once
+    /// they change. `on_reload` is called with the result of every reload attempt. A failed reload is retried on
+    /// every check until it succeeds, e.g. once a partially copied file is complete. Watching stops when the
+    /// returned [`ReloadWatcher`] is dropped.
+    pub fn watch(
+        &self,
+        interval: Duration,
+        mut on_reload: impl FnMut(Result<ReloadOutcome, Error>) + Send + 'static,
+    ) -> ReloadWatcher {
+        let (stop, stopped) = mpsc::channel::<()>();
+        let reader = self.clone();
+        let mut stamps = reader.file_stamps();
+        let handle = thread::spawn(move || {
+            while let Err(mpsc::RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                let current = reader.file_stamps();
+                if current != stamps {
+                    let result = reader.reload();
+                    if result.is_ok() {
+                        stamps = current;
+                    }
+                    on_reload(result);
+                }
+            }
+        });
+        ReloadWatcher {
+            stop: Some(stop),
+            handle: Some(handle),
+        }
+    }
+
+    fn read_current(&self) -> std::sync::RwLockReadGuard<'_, Current> {
+        self.inner
+            .current
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn write_current(&self) -> std::sync::RwLockWriteGuard<'_, Current> {
+        self.inner
+            .current
+            .write()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.inner
+            .state
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn reload_locked(&self, state: &mut State, config: Config) -> Result<ReloadOutcome, Error> {
+        // Loading a whole tileset just to compare its dataset ID is expensive, so files are compared first
+        let stamps = file_stamps(&config);
+        if stamps == state.stamps {
+            return Ok(self.keep_tileset(state, config));
+        }
+        let reader = GraphReader::new(&config)?;
+        let fingerprint = fingerprint(&config, &reader);
+        if fingerprint == state.fingerprint {
+            state.stamps = stamps;
+            return Ok(self.keep_tileset(state, config));
+        }
+
+        // Actors are built here rather than on the first request, from the same tiles the reader serves
+        let actors = (0..self.inner.actor_count.load(Ordering::Acquire))
+            .map(|_| Actor::with_reader(&config, &reader))
+            .collect::<Result<Vec<_>, _>>()?;
+        let previous = state.fingerprint.dataset_id;
+        let current = fingerprint.dataset_id;
+        *state = State {
+            config: config.clone(),
+            stamps,
+            fingerprint,
+        };
+        let mut active = self.write_current();
+        *active = Current {
+            reader,
+            config,
+            generation: active.generation + 1,
+            actors: Mutex::new(actors),
+        };
+        Ok(ReloadOutcome::Reloaded { previous, current })
+    }
+
+    /// Keeps the current tileset, but switches to `config` for the following reloads and new actors.
+    fn keep_tileset(&self, state: &mut State, config: Config) -> ReloadOutcome {
+        self.write_current().config = config.clone();
+        state.config = config;
+        ReloadOutcome::Unchanged {
+            dataset_id: state.fingerprint.dataset_id,
+        }
+    }
+
+    /// Metadata of the configured extract files to detect their changes.
+    fn file_stamps(&self) -> [Option<FileStamp>; 2] {
+        file_stamps(&self.lock_state().config)
+    }
+}
+
+/// [`Actor`] that follows a [`ReloadableGraphReader`] and switches to a new actor on the first request after every
+/// reload, so routing switches to new extracts together with the reader.
+///
+/// Actors need `&mut` access, so services keep one `ReloadableActor` per worker thread, all created from clones
+/// of the same reader. Replacement actors are built by the reload from the same tiles as the new reader, one for
+/// every live `ReloadableActor`, so requests don't pay for it.
+/// ```rust
+/// use std::time::Duration;
+///
+/// let config = valhalla::Config::from_file("path/to/config.json").unwrap();
+/// let reader = valhalla::ReloadableGraphReader::new(&config).unwrap();
+/// let _watcher = reader.watch(Duration::from_secs(30), |_| {});
+///
+/// let mut actor = valhalla::ReloadableActor::new(&reader).unwrap();
+/// let request = valhalla::proto::Options::default();
+/// let response = actor.actor().unwrap().route(&request);
+/// ```
+pub struct ReloadableActor {
+    reader: ReloadableGraphReader,
+    actor: Actor,
+    /// Generation of the reader's tileset the actor was created for.
+    generation: u64,
+}
+
+impl ReloadableActor {
+    /// Creates an actor for the current tileset of the `reader`.
+    pub fn new(reader: &ReloadableGraphReader) -> Result<Self, Error> {
+        let (actor, generation) = {
+            let current = reader.read_current();
+            (
+                Actor::with_reader(&current.config, &current.reader)?,
+                current.generation,
+            )
+        };
+        reader.inner.actor_count.fetch_add(1, Ordering::AcqRel);
+        Ok(Self {
+            reader: reader.clone(),
+            actor,
+            generation,
+        })
+    }
+
+    /// Actor for the current tileset of the reader, replaced if the reader was reloaded since the last call.
+    /// An actor created concurrently with a reload may find no replacement built for it, then it's built here
+    /// from the tiles of the current reader. If that fails, the previous actor is kept and the error is returned,
+    /// so the next call tries again.
+    pub fn actor(&mut self) -> Result<&mut Actor, Error> {
+        let current = self.reader.read_current();
+        if current.generation != self.generation {
+            let prebuilt = current
+                .actors
+                .lock()
+                .unwrap_or_else(|err| err.into_inner())
+                .pop();
+            self.actor = match prebuilt {
+                Some(actor) => actor,
+                None => Actor::with_reader(&current.config, &current.reader)?,
+            };
+            self.generation = current.generation;
+        }
+        drop(current);
+        Ok(&mut self.actor)
+    }
+
+    /// Reader this actor follows.
+    pub fn reader(&self) -> &ReloadableGraphReader {
+        &self.reader
+    }
+}
+
+impl Drop for ReloadableActor {
+    fn drop(&mut self) {
+        self.reader.inner.actor_count.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Handle of the thread started by [`ReloadableGraphReader::watch()`]. Stops the thread when dropped.
+pub struct ReloadWatcher {
+    stop: Option<mpsc::Sender<()>>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl Drop for ReloadWatcher {
+    fn drop(&mut self) {
+        // Disconnecting the channel wakes the thread up immediately
+        drop(self.stop.take());
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct FileStamp {
+    path: PathBuf,
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl FileStamp {
+    fn read(config: &Config, key: &str) -> Option<Self> {
+        let path = PathBuf::from(config.get::<String>(key)?);
+        let metadata = std::fs::metadata(&path).ok()?;
+        Some(Self {
+            path,
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        })
+    }
+}
+
+fn file_stamps(config: &Config) -> [Option<FileStamp>; 2] {
+    ["mjolnir.tile_extract", "mjolnir.traffic_extract"].map(|key| FileStamp::read(config, key))
+}
+
+fn fingerprint(config: &Config, reader: &GraphReader) -> Fingerprint {
+    Fingerprint {
+        dataset_id: reader.dataset_id(),
+        traffic: FileStamp::read(config, "mjolnir.traffic_extract"),
+    }
+}
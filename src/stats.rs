@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+use crate::{Access, EdgeUse, GraphTile, RoadClass};
+
+/// Aggregated statistics of a tileset, see [`crate::GraphReader::stats()`].
+///
+/// Shortcuts duplicate the edges they consist of, so they are only counted in [`GraphStats::shortcuts`]
+/// and excluded from the other edge statistics.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GraphStats {
+    pub tiles: usize,
+    pub nodes: usize,
+    /// Number of directed edges, i.e. most roads are counted twice, once in each direction.
+    pub edges: usize,
+    pub shortcuts: usize,
+    pub edges_by_class: HashMap<RoadClass, usize>,
+    pub edges_by_use: HashMap<EdgeUse, usize>,
+    /// Length in kilometers of directed edges every access mode can travel along in the edge direction, keyed
+    /// by single modes like [`Access::AUTO`]. Reverse access is not counted, as it's the forward access of the
+    /// opposing edge.
+    pub km_by_access: HashMap<Access, f64>,
+}
+
+impl GraphStats {
+    /// Adds nodes and edges of the tile to the statistics.
+    pub fn add_tile(&mut self, tile: &GraphTile) {
+        self.tiles += 1;
+        self.nodes += tile.nodes().len();
+        for edge in tile.directededges() {
+            if edge.is_shortcut() {
+                self.shortcuts += 1;
+                continue;
+            }
+            self.edges += 1;
+            *self
+                .edges_by_class
+                .entry(edge.classification())
+                .or_default() += 1;
+            *self.edges_by_use.entry(edge.use_type()).or_default() += 1;
+            let km = f64::from(edge.length()) / 1000.0;
+            // Composite flags like `Access::ALL` are skipped, as are bits without a name
+            let modes = edge.forwardaccess().iter_names().map(|(_, mode)| mode);
+            for mode in modes.filter(|mode| mode.bits().is_power_of_two()) {
+                *self.km_by_access.entry(mode).or_default() += km;
+            }
+        }
+    }
+
+    /// Combines statistics of disjoint sets of tiles, e.g. collected in parallel.
+    pub fn merge(mut self, other: GraphStats) -> Self {
+        self.tiles += other.tiles;
+        self.nodes += other.nodes;
+        self.edges += other.edges;
+        self.shortcuts += other.shortcuts;
+        for (class, count) in other.edges_by_class {
+            *self.edges_by_class.entry(class).or_default() += count;
+        }
+        for (edge_use, count) in other.edges_by_use {
+            *self.edges_by_use.entry(edge_use).or_default() += count;
+        }
+        for (mode, km) in other.km_by_access {
+            *self.km_by_access.entry(mode).or_default() += km;
+        }
+        self
+    }
+}
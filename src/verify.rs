@@ -0,0 +1,234 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{CachedGraphReader, GraphId, GraphReader, GraphTile, LatLon, ffi, tile_size};
+
+/// Maximum distance in degrees between the end of an edge shape and its node, covering rounding of both.
+const SHAPE_TOLERANCE: f64 = 1e-5;
+
+/// Memory for tiles kept for lookups of end nodes in other tiles, so tilesets of any size are checked in bounded
+/// memory. Tiles are checked in ID order, so neighbours are mostly found in the cache.
+const TILE_CACHE_BUDGET: usize = 256 * 1024 * 1024;
+
+/// Result of [`GraphReader::verify()`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VerifyReport {
+    /// Number of tiles with readable data.
+    pub tiles: usize,
+    pub nodes: usize,
+    pub edges: usize,
+    pub issues: Vec<TileIssue>,
+}
+
+impl VerifyReport {
+    /// Whether no issues were found.
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Problem found by [`GraphReader::verify()`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum TileIssue {
+    /// Tile data is truncated or its header doesn't match it, so nodes and edges of the tile weren't checked.
+    /// Also reported for records that point outside of their sections, which are skipped instead.
+    Layout { tile: GraphId, message: String },
+    /// Traffic tile doesn't match the graph tile.
+    Traffic { tile: GraphId, message: String },
+    /// Tile data looks valid, but Valhalla can't read it.
+    Unreadable { tile: GraphId },
+    /// Node lies outside the bounds of its tile.
+    NodeOutsideTile { node: GraphId },
+    /// End node of the edge doesn't exist.
+    DanglingEndNode { edge: GraphId, end_node: GraphId },
+    /// End node of the edge has no edge at its opposing edge index.
+    MissingOpposingEdge { edge: GraphId },
+    /// Opposing edge doesn't lead back to the start node of the edge or has a different length.
+    OpposingEdgeMismatch { edge: GraphId, opposing: GraphId },
+    /// Shape of the edge doesn't start at its start node within the tile or doesn't end at its end node.
+    InvalidShape { edge: GraphId },
+}
+
+impl fmt::Display for TileIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TileIssue::Layout { tile, message } => write!(f, "tile {tile}: {message}"),
+            TileIssue::Traffic { tile, message } => write!(f, "tile {tile}: {message}"),
+            TileIssue::Unreadable { tile } => write!(f, "tile {tile} can't be read"),
+            TileIssue::NodeOutsideTile { node } => write!(f, "node {node} is outside its tile"),
+            TileIssue::DanglingEndNode { edge, end_node } => {
+                write!(f, "edge {edge} ends at missing node {end_node}")
+            }
+            TileIssue::MissingOpposingEdge { edge } => {
+                write!(f, "edge {edge} has no opposing edge")
+            }
+            TileIssue::OpposingEdgeMismatch { edge, opposing } => {
+                write!(f, "opposing edge {opposing} doesn't match edge {edge}")
+            }
+            TileIssue::InvalidShape { edge } => {
+                write!(f, "shape of edge {edge} doesn't connect its nodes")
+            }
+        }
+    }
+}
+
+/// Checks every tile once and caches recently used tiles for lookups of end nodes in other tiles.
+struct Verifier {
+    tiles: CachedGraphReader,
+    /// Whether the tile is readable, for every tile accessed so far.
+    readable: HashMap<u64, bool>,
+    report: VerifyReport,
+}
+
+pub(crate) fn verify(reader: &GraphReader) -> VerifyReport {
+    let mut verifier = Verifier {
+        tiles: CachedGraphReader::new(reader.clone(), TILE_CACHE_BUDGET),
+        readable: HashMap::new(),
+        report: VerifyReport::default(),
+    };
+    let mut tile_ids = reader.tiles();
+    tile_ids.sort_by_key(|id| id.value);
+    for tile_id in tile_ids {
+        if let Some(tile) = verifier.tile(tile_id) {
+            verifier.check_tile(&tile);
+        }
+    }
+    verifier.report
+}
+
+impl Verifier {
+    /// Tile if its data is valid, reporting layout issues on the first access.
+    fn tile(&mut self, id: GraphId) -> Option<GraphTile> {
+        let id = id.tile();
+        if let Some(&readable) = self.readable.get(&id.value) {
+            return if readable {
+                self.tiles.get_tile(id)
+            } else {
+                None
+            };
+        }
+
+        let mut readable = true;
+        for issue in self.tiles.reader().0.check_tile(id) {
+            if issue.traffic {
+                self.report.issues.push(TileIssue::Traffic {
+                    tile: id,
+                    message: issue.message,
+                });
+            } else {
+                readable = false;
+                self.report.issues.push(TileIssue::Layout {
+                    tile: id,
+                    message: issue.message,
+                });
+            }
+        }
+        let tile = if readable {
+            let tile = self.tiles.get_tile(id);
+            if tile.is_none() {
+                self.report.issues.push(TileIssue::Unreadable { tile: id });
+            }
+            tile
+        } else {
+            None
+        };
+        self.readable.insert(id.value, tile.is_some());
+        tile
+    }
+
+    fn check_tile(&mut self, tile: &GraphTile) {
+        let header = tile.header();
+        let size = tile_size(tile.id().level());
+        let min = LatLon(header.base_lat, header.base_lon);
+        let max = LatLon(header.base_lat + size, header.base_lon + size);
+        let in_tile = |ll: LatLon| {
+            (min.0 - SHAPE_TOLERANCE..=max.0 + SHAPE_TOLERANCE).contains(&ll.0)
+                && (min.1 - SHAPE_TOLERANCE..=max.1 + SHAPE_TOLERANCE).contains(&ll.1)
+        };
+
+        self.report.tiles += 1;
+        self.report.nodes += tile.nodes().len();
+        self.report.edges += tile.directededges().len();
+        for (node_index, node) in tile.nodes().iter().enumerate() {
+            let node_id = tile.node_id(node_index as u32);
+            let node_ll = tile.node_latlon(node);
+            if !in_tile(node_ll) {
+                self.report
+                    .issues
+                    .push(TileIssue::NodeOutsideTile { node: node_id });
+            }
+
+            for index in node.edge_index()..node.edge_index() + node.edge_count() {
+                let edge_id = tile.edge_id(index);
+                let Some(edge) = tile.directededge(index) else {
+                    self.report.issues.push(TileIssue::Layout {
+                        tile: tile.id(),
+                        message: format!("node {node_id} refers to missing edge {edge_id}"),
+                    });
+                    break;
+                };
+                let end_node_id = edge.endnode();
+                let end_tile = self.tile(end_node_id);
+                let end_node = end_tile
+                    .as_ref()
+                    .and_then(|end_tile| end_tile.node(end_node_id.id()));
+                let (Some(end_tile), Some(end_node)) = (&end_tile, end_node) else {
+                    self.report.issues.push(TileIssue::DanglingEndNode {
+                        edge: edge_id,
+                        end_node: end_node_id,
+                    });
+                    continue;
+                };
+
+                if edge.opp_index() >= end_node.edge_count() {
+                    self.report
+                        .issues
+                        .push(TileIssue::MissingOpposingEdge { edge: edge_id });
+                } else {
+                    let opposing_index = end_node.edge_index() + edge.opp_index();
+                    let opposing = end_tile.directededge(opposing_index);
+                    if opposing.is_none_or(|opposing| {
+                        opposing.endnode() != node_id || opposing.length() != edge.length()
+                    }) {
+                        self.report.issues.push(TileIssue::OpposingEdgeMismatch {
+                            edge: edge_id,
+                            opposing: end_tile.edge_id(opposing_index),
+                        });
+                    }
+                }
+
+                // Header sections are checked against the tile size, edge info offsets of edges are not
+                let edgeinfo_offset =
+                    u64::from(header.edgeinfo_offset) + u64::from(ffi::edgeinfo_offset(edge));
+                if edgeinfo_offset >= u64::from(header.textlist_offset) {
+                    self.report.issues.push(TileIssue::Layout {
+                        tile: tile.id(),
+                        message: format!(
+                            "edge {edge_id} has edge info at offset {edgeinfo_offset}, past text list \
+                             offset {}",
+                            header.textlist_offset
+                        ),
+                    });
+                    continue;
+                }
+
+                let shape = tile.edgeinfo(edge).shape_points();
+                let near = |a: LatLon, b: LatLon| {
+                    (a.0 - b.0).abs() <= SHAPE_TOLERANCE && (a.1 - b.1).abs() <= SHAPE_TOLERANCE
+                };
+                let valid = match (shape.first(), shape.last()) {
+                    (Some(&first), Some(&last)) => {
+                        in_tile(first)
+                            && near(first, node_ll)
+                            && near(last, end_tile.node_latlon(end_node))
+                    }
+                    _ => false,
+                };
+                if !valid {
+                    self.report
+                        .issues
+                        .push(TileIssue::InvalidShape { edge: edge_id });
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use std::{fs, io, path::Path};
+
+use crate::{Error, GraphId, GraphReader};
+
+/// Identifies the index file format and its version.
+const MAGIC: &[u8; 8] = b"VWAYIDX1";
+/// Magic, dataset ID and number of records.
+const HEADER_SIZE: usize = 24;
+/// Way ID and edge ID.
+const RECORD_SIZE: usize = 16;
+/// Position of a record in the order of edge IDs.
+const ORDER_SIZE: usize = 4;
+
+/// Index between OSM way IDs and IDs of the directed edges built from them, e.g. to apply traffic feeds that
+/// reference OSM ways. Shortcuts and edges without a way ID are not indexed.
+///
+/// The index is stored in a compact binary format that is used as is for lookups, so a memory-mapped file can be
+/// passed to [`WayIndex::from_bytes()`] without reading it into memory, unlike [`WayIndex::open()`].
+/// ```rust
+/// use valhalla::{Config, GraphReader, WayIndex};
+///
+/// let reader = GraphReader::new(&Config::from_file("path/to/config.json").unwrap()).unwrap();
+/// let index = WayIndex::build(&reader);
+/// index.save("path/to/ways.idx").unwrap();
+///
+/// let index = WayIndex::open("path/to/ways.idx").unwrap();
+/// assert_eq!(index.dataset_id(), reader.dataset_id());
+/// for edge_id in index.edges_for_way(123) {
+///     let tile = reader.get_tile(edge_id.tile()).unwrap();
+///     let edge = tile.directededge(edge_id.id()).unwrap();
+/// }
+/// ```
+pub struct WayIndex {
+    /// Header, records sorted by way ID and edge ID, then positions of records sorted by edge ID.
+    data: Box<dyn AsRef<[u8]> + Send + Sync>,
+}
+
+impl WayIndex {
+    /// Scans all tiles of the `reader`.
+    pub fn build(reader: &GraphReader) -> Self {
+        let mut records = Vec::new();
+        for tile_id in reader.tiles() {
+            let Some(tile) = reader.get_tile(tile_id) else {
+                continue;
+            };
+            for (index, edge) in tile.directededges().iter().enumerate() {
+                if edge.is_shortcut() {
+                    continue;
+                }
+                let way_id = tile.edgeinfo(edge).way_id;
+                if way_id != 0 {
+                    records.push((way_id, tile.edge_id(index as u32).value));
+                }
+            }
+        }
+        records.sort_unstable();
+
+        let mut order: Vec<u32> = (0..records.len() as u32).collect();
+        order.sort_unstable_by_key(|&position| records[position as usize].1);
+
+        let mut data = Vec::with_capacity(HEADER_SIZE + records.len() * (RECORD_SIZE + ORDER_SIZE));
+        data.extend(MAGIC);
+        data.extend(reader.dataset_id().to_le_bytes());
+        data.extend((records.len() as u64).to_le_bytes());
+        for (way_id, edge_id) in records {
+            data.extend(way_id.to_le_bytes());
+            data.extend(edge_id.to_le_bytes());
+        }
+        for position in order {
+            data.extend(position.to_le_bytes());
+        }
+        Self {
+            data: Box::new(data),
+        }
+    }
+
+    /// Uses the index in the format written by [`WayIndex::save()`], e.g. a memory-mapped file. Checks the size and
+    /// every position in the edge ID order, so corrupt files fail here instead of in lookups.
+    pub fn from_bytes(data: impl AsRef<[u8]> + Send + Sync + 'static) -> Result<Self, Error> {
+        let bytes = data.as_ref();
+        if bytes.len() < HEADER_SIZE || &bytes[..MAGIC.len()] != MAGIC {
+            return Err(Error("Not a way index".into()));
+        }
+        let len = read_u64(bytes, 16);
+        let size = usize::try_from(len)
+            .ok()
+            .and_then(|len| len.checked_mul(RECORD_SIZE + ORDER_SIZE))
+            .and_then(|size| size.checked_add(HEADER_SIZE));
+        if size != Some(bytes.len()) {
+            return Err(Error(
+                format!("Way index size doesn't match {len} records").into(),
+            ));
+        }
+        let index = Self {
+            data: Box::new(data),
+        };
+        // Lookups by edge ID follow the stored positions, so a corrupt one would point past the records
+        if let Some(position) =
+            (0..index.len()).find(|&position| index.order(position) >= index.len())
+        {
+            return Err(Error(
+                format!("Way index order entry {position} is out of {len} records").into(),
+            ));
+        }
+        Ok(index)
+    }
+
+    /// Reads the whole index file written by [`WayIndex::save()`] into memory. To keep a large index on disk,
+    /// memory-map the file instead and pass it to [`WayIndex::from_bytes()`].
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let data = fs::read(path)
+            .map_err(|err| Error(format!("Failed to read {}: {err}", path.display()).into()))?;
+        Self::from_bytes(data)
+    }
+
+    /// Writes the index to a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.as_bytes())
+    }
+
+    /// Serialized index, accepted by [`WayIndex::from_bytes()`].
+    pub fn as_bytes(&self) -> &[u8] {
+        (*self.data).as_ref()
+    }
+
+    /// [`GraphReader::dataset_id()`] of the tileset the index was built from.
+    pub fn dataset_id(&self) -> u64 {
+        read_u64(self.as_bytes(), 8)
+    }
+
+    /// Number of indexed edges.
+    pub fn len(&self) -> usize {
+        read_u64(self.as_bytes(), 16) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// IDs of directed edges in both directions built from the OSM way, sorted by ID.
+    pub fn edges_for_way(&self, way_id: u64) -> impl Iterator<Item = GraphId> + '_ {
+        let start = partition_point(self.len(), |position| self.record(position).0 < way_id);
+        (start..self.len())
+            .map(|position| self.record(position))
+            .take_while(move |&(way, _)| way == way_id)
+            .map(|(_, edge_id)| GraphId::new(edge_id))
+    }
+
+    /// OSM way ID of the directed edge, or `None` if the edge is not indexed.
+    pub fn way_for_edge(&self, edge_id: GraphId) -> Option<u64> {
+        let edge_record = |position| self.record(self.order(position));
+        let position = partition_point(self.len(), |position| {
+            edge_record(position).1 < edge_id.value
+        });
+        (position < self.len())
+            .then(|| edge_record(position))
+            .filter(|&(_, edge)| edge == edge_id.value)
+            .map(|(way_id, _)| way_id)
+    }
+
+    /// All indexed `(way_id, edge_id)` pairs, sorted by way ID.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, GraphId)> + '_ {
+        (0..self.len()).map(|position| {
+            let (way_id, edge_id) = self.record(position);
+            (way_id, GraphId::new(edge_id))
+        })
+    }
+
+    fn record(&self, position: usize) -> (u64, u64) {
+        let offset = HEADER_SIZE + position * RECORD_SIZE;
+        let bytes = self.as_bytes();
+        (read_u64(bytes, offset), read_u64(bytes, offset + 8))
+    }
+
+    fn order(&self, position: usize) -> usize {
+        let offset = HEADER_SIZE + self.len() * RECORD_SIZE + position * ORDER_SIZE;
+        let bytes: [u8; 4] = self.as_bytes()[offset..offset + 4].try_into().unwrap();
+        u32::from_le_bytes(bytes) as usize
+    }
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+/// Same as [`slice::partition_point()`] over positions `0..len`, for data that isn't a slice of values.
+fn partition_point(len: usize, pred: impl Fn(usize) -> bool) -> usize {
+    let (mut low, mut high) = (0, len);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if pred(mid) {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
@@ -1,5 +1,5 @@
 use valhalla::{
-    Actor, Config, Error, LatLon, Response,
+    Actor, Config, Error, GraphId, LatLon, Response,
     proto::{self, options::Format},
 };
 
@@ -261,6 +261,183 @@ fn request_response_format() {
     }
 }
 
+#[test]
+fn expansion_edges() {
+    let config = Config::from_file(ANDORRA_CONFIG).unwrap();
+    let mut actor = Actor::new(&config).unwrap();
+
+    let request = proto::Options {
+        costing_type: proto::costing::Type::Auto as i32,
+        has_expansion_action: Some(proto::options::HasExpansionAction::ExpansionAction(
+            proto::options::Action::Route as i32,
+        )),
+        locations: vec![
+            proto::Location {
+                ll: ANDORRA_TEST_LOC_1.into(),
+                ..Default::default()
+            },
+            proto::Location {
+                ll: ANDORRA_TEST_LOC_2.into(),
+                ..Default::default()
+            },
+        ],
+        // Should be overridden by `expansion_edges`
+        format: Format::Json as i32,
+        ..Default::default()
+    };
+    let edges = actor
+        .expansion_edges(&request)
+        .expect("Failed to get expansion");
+    assert!(!edges.is_empty());
+    for edge in &edges {
+        assert_ne!(edge.edge_id, GraphId::default(), "{edge:?}");
+        assert!(edge.edge_id.level() <= 2, "{edge:?}");
+    }
+    assert!(
+        edges
+            .iter()
+            .any(|edge| edge.status == proto::expansion::EdgeStatus::Settled)
+    );
+    assert!(edges.iter().any(|edge| edge.distance > 0 && edge.cost > 0));
+
+    // Same data as in the raw PBF response
+    let response = actor.expansion(&proto::Options {
+        format: Format::Pbf as i32,
+        expansion_properties: vec![proto::options::ExpansionProperties::EdgeId as i32],
+        ..request
+    });
+    let Ok(Response::Pbf(api)) = response else {
+        panic!("Expected PBF response, got: {response:?}");
+    };
+    let ids: Vec<_> = api
+        .expansion
+        .unwrap()
+        .edges()
+        .map(|edge| edge.edge_id)
+        .collect();
+    assert_eq!(
+        ids,
+        edges.iter().map(|edge| edge.edge_id).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn trace_match() {
+    let config = Config::from_file(ANDORRA_CONFIG).unwrap();
+    let mut actor = Actor::new(&config).unwrap();
+
+    let request = proto::Options {
+        costing_type: proto::costing::Type::Auto as i32,
+        has_encoded_polyline: Some(proto::options::HasEncodedPolyline::EncodedPolyline(
+            "qwnapA__c|A_CeOu@qEyAkMs@cISuFEePS_Ze@yG_A}EwNyc@iG_P_BoE".into(),
+        )),
+        ..Default::default()
+    };
+    let paths = actor.trace_match(&request).expect("Failed to match trace");
+    assert_eq!(paths.len(), 1);
+    let path = &paths[0];
+    assert_eq!(path.confidence, 1.0);
+    assert_eq!(path.points.len(), 13, "One matched point per input point");
+    assert!(!path.segments.is_empty());
+
+    for point in &path.points {
+        if point.candidates.is_empty() {
+            continue; // interpolated point
+        }
+        let selected: Vec<_> = point.candidates.iter().filter(|c| c.selected).collect();
+        assert_eq!(selected.len(), 1, "{point:?}");
+        assert_eq!(selected[0].edge_id, point.edge_id);
+        assert!(selected[0].emission_cost >= 0.0);
+    }
+    // The first point has nothing to transition from
+    assert!(
+        path.points[0]
+            .candidates
+            .iter()
+            .all(|c| c.transition_cost < 0.0)
+    );
+    // Edges of the path should be traversed in the order of the trace points
+    for segment in &path.segments {
+        assert!(segment.last_match_idx < path.points.len() as i32);
+        assert!(segment.first_match_idx <= segment.last_match_idx);
+    }
+
+    // Alternative paths share the same confidence budget
+    let request = proto::Options {
+        has_alternates: Some(proto::options::HasAlternates::Alternates(2)),
+        ..request
+    };
+    let paths = actor.trace_match(&request).expect("Failed to match trace");
+    assert!(!paths.is_empty() && paths.len() <= 3);
+    let total: f32 = paths.iter().map(|path| path.confidence).sum();
+    assert!((total - 1.0).abs() < 1e-5, "{total}");
+
+    // Not enough points to match
+    let request = proto::Options {
+        costing_type: proto::costing::Type::Auto as i32,
+        shape: vec![proto::Location {
+            ll: ANDORRA_TEST_LOC_1.into(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    assert!(actor.trace_match(&request).is_err());
+}
+
+#[test]
+fn height() {
+    let config = Config::from_file(ANDORRA_CONFIG).unwrap();
+    let mut actor = Actor::new(&config).unwrap();
+
+    // `tests/andorra/elevation` contains a single synthetic N42E001 tile with a constant height of 1234 m
+    let request = proto::Options {
+        shape: vec![
+            proto::Location {
+                ll: ANDORRA_TEST_LOC_1.into(),
+                ..Default::default()
+            },
+            proto::Location {
+                ll: ANDORRA_TEST_LOC_2.into(),
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+    let response = actor.height(&request);
+    let Ok(Response::Json(json)) = response else {
+        panic!("Expected JSON response, got: {response:?}");
+    };
+    assert!(json.contains(r#""height":[1234,1234]"#), "{json}");
+
+    // Same shape as encoded polyline with range output
+    let request = proto::Options {
+        has_encoded_polyline: Some(proto::options::HasEncodedPolyline::EncodedPolyline(
+            "qwnapA__c|A_CeOu@qEyAkMs@cISuFEePS_Ze@yG_A}EwNyc@iG_P_BoE".into(),
+        )),
+        has_range: Some(proto::options::HasRange::Range(true)),
+        ..Default::default()
+    };
+    let response = actor.height(&request);
+    let Ok(Response::Json(json)) = response else {
+        panic!("Expected JSON response, got: {response:?}");
+    };
+    assert!(json.contains(r#""range_height":[[0,1234],"#), "{json}");
+
+    // Points outside of the available elevation data have no height
+    let request = proto::Options {
+        shape: vec![proto::Location {
+            ll: LatLon(55.6086, 13.0005).into(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    let response = actor.height(&request);
+    let Ok(Response::Json(json)) = response else {
+        panic!("Expected JSON response, got: {response:?}");
+    };
+    assert!(json.contains(r#""height":[null]"#), "{json}");
+}
+
 #[test]
 fn parse_api() {
     assert!(Actor::parse_json_request("", proto::options::Action::Route).is_err());
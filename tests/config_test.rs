@@ -1,9 +1,12 @@
-use std::{io::Write, path::PathBuf};
+use std::{ffi::OsString, io::Write, os::unix::ffi::OsStringExt, path::PathBuf};
 
 use miniserde::{Serialize, json};
 use tempfile::NamedTempFile;
 
-use valhalla::{Actor, Config, GraphReader};
+use valhalla::{
+    Actor, Config, ConfigBuilder, ConfigIssue, ConfigLoader, ConfigSource, GraphReader, LatLon,
+    ValueKind, proto,
+};
 
 #[derive(Serialize)]
 struct ValhallaConfig {
@@ -143,3 +146,383 @@ fn from_tile_extract() {
     );
     assert!(GraphReader::new(&Config::from_tile_extract(ANDORRA_TILES).unwrap()).is_ok());
 }
+
+#[test]
+fn builder() {
+    let config = ConfigBuilder::new()
+        .tile_extract(ANDORRA_TILES)
+        .traffic_extract(ANDORRA_TRAFFIC)
+        .mjolnir(|mjolnir| mjolnir.max_cache_size = 64 * 1024 * 1024)
+        .service_limits(|limits| limits.auto.max_locations = 2)
+        .build()
+        .unwrap();
+    assert!(GraphReader::new(&config).is_ok());
+    let mut actor = Actor::new(&config).unwrap();
+    let location = |lat, lon| proto::Location {
+        ll: LatLon(lat, lon).into(),
+        ..Default::default()
+    };
+    let mut request = proto::Options {
+        costing_type: proto::costing::Type::Auto as i32,
+        locations: vec![location(42.50107, 1.51034), location(42.50627, 1.52173)],
+        ..Default::default()
+    };
+    assert!(actor.route(&request).is_ok());
+
+    // Service limits from the builder are applied
+    request.locations.push(location(42.50107, 1.51034));
+    assert!(actor.route(&request).is_err());
+
+    // Paths are escaped, so the config is valid JSON that just points to a missing file
+    let config = ConfigBuilder::new()
+        .tile_extract(r#"bad "path" \to\ tile_extract"#)
+        .build()
+        .unwrap();
+    assert!(GraphReader::new(&config).is_err());
+    assert!(ConfigBuilder::new().build().is_ok());
+}
+
+#[test]
+fn inspect_and_modify() {
+    let mut config = Config::from_file(ANDORRA_CONFIG).unwrap();
+    assert_eq!(
+        config.get::<String>("mjolnir.tile_extract").as_deref(),
+        Some(ANDORRA_TILES)
+    );
+    assert_eq!(
+        config.get::<f64>("service_limits.auto.max_distance"),
+        Some(5000000.0)
+    );
+    assert_eq!(config.get::<bool>("loki.use_connectivity"), Some(true));
+    assert_eq!(config.get::<u32>("mjolnir.tile_extract"), None); // not a number
+    assert_eq!(config.get::<String>("service_limits.auto"), None); // object
+    assert_eq!(config.get::<String>("missing.key"), None);
+
+    config.set("service_limits.auto.max_distance", 10_000_000);
+    config.set("custom.nested.key", "value");
+    assert_eq!(
+        config.get::<u32>("service_limits.auto.max_distance"),
+        Some(10_000_000)
+    );
+    assert_eq!(
+        config.get::<String>("custom.nested.key").as_deref(),
+        Some("value")
+    );
+    assert!(config.remove("custom"));
+    assert!(!config.remove("custom"));
+    assert!(config.children("custom").is_empty());
+
+    let entries = config.children("service_limits.auto");
+    assert!(
+        entries
+            .iter()
+            .any(|e| e.key == "max_distance" && e.value == "10000000")
+    );
+    assert!(entries.iter().all(|e| !e.has_children));
+    let actions = config.children("loki.actions");
+    assert!(actions.iter().all(|e| e.key.is_empty()));
+    assert!(actions.iter().any(|e| e.value == "route"));
+    assert!(
+        config
+            .children("")
+            .iter()
+            .any(|e| e.key == "mjolnir" && e.has_children)
+    );
+
+    // Clones are independent
+    let clone = config.clone();
+    config.set("mjolnir.tile_extract", "bad_path_to_tile_extract");
+    assert!(GraphReader::new(&config).is_err());
+    assert!(GraphReader::new(&clone).is_ok());
+}
+
+#[test]
+fn merge_and_to_json() {
+    let mut config = Config::from_file(ANDORRA_CONFIG).unwrap();
+    let overlay = Config::from_json(
+        r#"{"mjolnir":{"tile_extract":"bad_path_to_tile_extract"},"loki":{"actions":["route"]}}"#,
+    )
+    .unwrap();
+    config.merge(&overlay);
+    assert_eq!(
+        config.get::<String>("mjolnir.tile_extract").as_deref(),
+        Some("bad_path_to_tile_extract")
+    );
+    // Siblings are kept, arrays are replaced
+    assert_eq!(
+        config.get::<String>("mjolnir.traffic_extract").as_deref(),
+        Some(ANDORRA_TRAFFIC)
+    );
+    assert_eq!(config.children("loki.actions").len(), 1);
+    assert!(GraphReader::new(&config).is_err());
+
+    // Empty objects leave everything in place
+    let before = config.to_json();
+    config.merge(&Config::from_json("{}").unwrap());
+    assert_eq!(config.to_json(), before);
+    config.merge(&Config::from_json(r#"{"loki":{}}"#).unwrap());
+    assert_eq!(config.to_json(), before);
+    assert_eq!(config.children("loki.actions").len(), 1);
+
+    // Empty strings and arrays replace existing values
+    config.merge(
+        &Config::from_json(r#"{"mjolnir":{"tile_extract":""},"loki":{"actions":[]}}"#).unwrap(),
+    );
+    assert_eq!(
+        config.get::<String>("mjolnir.tile_extract").as_deref(),
+        Some("")
+    );
+    assert!(config.children("loki.actions").is_empty());
+    let mut empty = Config::from_json(r#"{"a":"b","c":[1]}"#).unwrap();
+    empty.merge(&Config::from_json(r#"{"a":"","c":[],"d":{}}"#).unwrap());
+    assert_eq!(empty.to_json(), r#"{"a":"","c":[],"d":{}}"#);
+
+    let json = Config::from_json(r#"{"a":{"b":1.5,"c":true,"d":"text","e":[1,2]}}"#)
+        .unwrap()
+        .to_json();
+    assert_eq!(json, r#"{"a":{"b":1.5,"c":true,"d":"text","e":[1,2]}}"#);
+    assert_eq!(Config::from_json("{}").unwrap().to_json(), "{}");
+
+    // Strings that look like numbers stay strings, replaced numbers stay numbers
+    let mut config = Config::from_json(r#"{"id":"007","n":"1","m":1}"#).unwrap();
+    assert_eq!(config.to_json(), r#"{"id":"007","n":"1","m":1}"#);
+    config.set("n", 2);
+    config.set("m", 2);
+    config.set("k", 3);
+    assert_eq!(config.to_json(), r#"{"id":"007","n":"2","m":2,"k":"3"}"#);
+    config.merge(&Config::from_json(r#"{"m":"text","n":[3]}"#).unwrap());
+    assert_eq!(
+        config.to_json(),
+        r#"{"id":"007","n":[3],"m":"text","k":"3"}"#
+    );
+
+    // Round trip keeps the config usable
+    let config = Config::from_json(&Config::from_file(ANDORRA_CONFIG).unwrap().to_json()).unwrap();
+    assert!(GraphReader::new(&config).is_ok());
+    assert!(Actor::new(&config).is_ok());
+}
+
+#[test]
+fn layered_loading() {
+    let mut overlay = NamedTempFile::new().expect("Failed to create temp file for config");
+    overlay
+        .write_all(br#"{"mjolnir":{"traffic_extract":"bad_path_to_traffic_extract","tile_dir":""},"loki":{"actions":["route"]},"odin":{}}"#)
+        .expect("Failed to write config");
+    let overlay_path = overlay.path().to_path_buf();
+
+    let (config, report) = ConfigLoader::new(ANDORRA_CONFIG)
+        .overlay(&overlay_path)
+        .env_vars([
+            ("VALHALLA__SERVICE_LIMITS__AUTO__MAX_DISTANCE", "10000000"),
+            ("VALHALLA__", "ignored"),
+            ("OTHER__MJOLNIR__TILE_EXTRACT", "ignored"),
+        ])
+        .load()
+        .unwrap();
+
+    assert_eq!(
+        config.get::<String>("mjolnir.traffic_extract").as_deref(),
+        Some("bad_path_to_traffic_extract")
+    );
+    assert_eq!(
+        config.get::<f64>("service_limits.auto.max_distance"),
+        Some(10_000_000.0)
+    );
+    assert!(GraphReader::new(&config).is_ok());
+
+    let base = ConfigSource::Base(ANDORRA_CONFIG.into());
+    assert_eq!(report.source("mjolnir.tile_extract"), Some(&base));
+    assert_eq!(
+        report.source("mjolnir.traffic_extract"),
+        Some(&ConfigSource::Overlay(overlay_path.clone()))
+    );
+    assert_eq!(
+        report.source("loki.actions"),
+        Some(&ConfigSource::Overlay(overlay_path.clone()))
+    );
+    // Empty strings are merged and reported, empty objects change nothing
+    assert_eq!(
+        config.get::<String>("mjolnir.tile_dir").as_deref(),
+        Some("")
+    );
+    assert_eq!(
+        report.source("mjolnir.tile_dir"),
+        Some(&ConfigSource::Overlay(overlay_path))
+    );
+    assert_eq!(report.source("odin"), None);
+    assert!(
+        report
+            .iter()
+            .filter(|(path, _)| path.starts_with("odin."))
+            .all(|(_, source)| source == &base)
+    );
+    assert_eq!(
+        report.source("service_limits.auto.max_distance"),
+        Some(&ConfigSource::Env(
+            "VALHALLA__SERVICE_LIMITS__AUTO__MAX_DISTANCE".into()
+        ))
+    );
+    assert_eq!(report.source("missing"), None);
+    assert!(
+        report
+            .to_string()
+            .contains("mjolnir.tile_extract = base tests/andorra/config.json\n")
+    );
+    assert!(report.skipped_env().is_empty());
+
+    // Variables that aren't valid UTF-8 are skipped instead of failing the load
+    let invalid = || OsString::from_vec(vec![b'x', 0xff]);
+    let mut invalid_name = OsString::from("VALHALLA__MJOLNIR__");
+    invalid_name.push(invalid());
+    let (config, report) = ConfigLoader::new(ANDORRA_CONFIG)
+        .env_vars([
+            (OsString::from("VALHALLA__MJOLNIR__TILE_EXTRACT"), invalid()),
+            (invalid_name, OsString::from("ignored")),
+            (OsString::from("OTHER"), invalid()),
+            (
+                OsString::from("VALHALLA__SERVICE_LIMITS__AUTO__MAX_DISTANCE"),
+                OsString::from("10000000"),
+            ),
+        ])
+        .load()
+        .unwrap();
+    assert_eq!(
+        config.get::<f64>("service_limits.auto.max_distance"),
+        Some(10_000_000.0)
+    );
+    assert_eq!(
+        report.skipped_env(),
+        [
+            "VALHALLA__MJOLNIR__TILE_EXTRACT",
+            "VALHALLA__MJOLNIR__x\u{fffd}"
+        ]
+    );
+    assert_eq!(report.source("mjolnir.tile_extract"), Some(&base));
+    assert!(
+        report
+            .to_string()
+            .contains("skipped env VALHALLA__MJOLNIR__TILE_EXTRACT: not valid UTF-8\n")
+    );
+
+    // Missing overlay fails the whole load
+    assert!(
+        ConfigLoader::new(ANDORRA_CONFIG)
+            .overlay("missing.json")
+            .env_vars(Vec::<(String, String)>::new())
+            .load()
+            .is_err()
+    );
+}
+
+#[test]
+fn validate() {
+    assert_eq!(
+        Config::from_file(ANDORRA_CONFIG).unwrap().validate(),
+        vec![]
+    );
+    assert_eq!(
+        Config::from_tile_extract(ANDORRA_TILES).unwrap().validate(),
+        vec![]
+    );
+
+    let config = Config::from_json(
+        r#"{"mjolnir":{"tiles_extract":"tests/andorra/tiles.tar","traffic_extract":"missing.tar","max_cache_size":"big"},"meili":{"verbose":"maybe","truck":{"beta":2}},"thor":"fast","mjolnr":{}}"#,
+    )
+    .unwrap();
+    let issues = config.validate();
+    let expected = [
+        ConfigIssue::UnknownKey {
+            path: "mjolnir.tiles_extract".into(),
+            suggestion: Some("mjolnir.tile_extract".into()),
+        },
+        ConfigIssue::InvalidType {
+            path: "mjolnir.max_cache_size".into(),
+            expected: ValueKind::Number,
+            value: "big".into(),
+        },
+        ConfigIssue::InvalidType {
+            path: "meili.verbose".into(),
+            expected: ValueKind::Bool,
+            value: "maybe".into(),
+        },
+        ConfigIssue::InvalidType {
+            path: "thor".into(),
+            expected: ValueKind::Object,
+            value: "fast".into(),
+        },
+        ConfigIssue::UnknownKey {
+            path: "mjolnr".into(),
+            suggestion: Some("mjolnir".into()),
+        },
+        ConfigIssue::Missing {
+            path: "mjolnir.tile_extract".into(),
+            alternative: Some("mjolnir.tile_dir".into()),
+        },
+        ConfigIssue::FileNotFound {
+            path: "mjolnir.traffic_extract".into(),
+            file: "missing.tar".into(),
+        },
+    ];
+    assert_eq!(issues, expected);
+    assert_eq!(
+        issues[0].to_string(),
+        "unknown key `mjolnir.tiles_extract`, did you mean `mjolnir.tile_extract`?"
+    );
+
+    // Valid keys not set by `valhalla_build_config` by default
+    let mut config = Config::from_tile_extract(ANDORRA_TILES).unwrap();
+    let keys = [
+        ("mjolnir.tile_url", "https://example.com/{tilePath}"),
+        ("mjolnir.tile_url_gz", "true"),
+        ("mjolnir.user_agent", "valhalla-rs"),
+        ("mjolnir.concurrency", "4"),
+        (
+            "additional_data.elevation_url",
+            "https://example.com/{tilePath}",
+        ),
+        ("meili.truck.search_radius", "30"),
+        ("meili.motorcycle.turn_penalty_factor", "50"),
+        ("meili.auto.gps_accuracy", "10"),
+        ("statsd.host", "localhost"),
+        ("service_limits.trace.max_best_paths", "4"),
+    ];
+    for (path, value) in keys {
+        config.set(path, value);
+    }
+    assert_eq!(config.validate(), vec![]);
+
+    // Actor values are required once there is a `loki` section
+    let config = Config::from_json(
+        r#"{"mjolnir":{"tile_extract":"tests/andorra/tiles.tar"},"loki":{"actions":["route"]}}"#,
+    )
+    .unwrap();
+    let issues = config.validate();
+    assert_eq!(
+        issues.first(),
+        Some(&ConfigIssue::Missing {
+            path: "loki.service_defaults.minimum_reachability".into(),
+            alternative: None,
+        })
+    );
+    assert!(
+        issues
+            .iter()
+            .all(|issue| matches!(issue, ConfigIssue::Missing { .. }))
+    );
+    assert!(issues.iter().any(|issue| issue.path() == "meili.default"));
+    assert!(
+        Config::from_json(r#"{"mjolnir":{"tile_extract":"tests/andorra/tiles.tar"}}"#)
+            .unwrap()
+            .validate()
+            .is_empty()
+    );
+
+    let config = Config::from_tile_extract("bad_path_to_tile_extract").unwrap();
+    assert_eq!(
+        config.validate(),
+        vec![ConfigIssue::FileNotFound {
+            path: "mjolnir.tile_extract".into(),
+            file: "bad_path_to_tile_extract".into(),
+        }]
+    );
+}
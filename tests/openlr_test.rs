@@ -0,0 +1,146 @@
+use valhalla::{
+    Access, Config, FormOfWay, GraphId, GraphReader, GraphTile, LatLon, LineLocationReference,
+    LocationReferencePoint, OpenLrDecoder,
+};
+
+const ANDORRA_TILES: &str = "tests/andorra/tiles.tar";
+
+fn bearing(from: LatLon, to: LatLon) -> f64 {
+    let (lat1, lat2) = (from.0.to_radians(), to.0.to_radians());
+    let dlon = (to.1 - from.1).to_radians();
+    let y = dlon.sin() * lat2.cos();
+    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * dlon.cos();
+    y.atan2(x).to_degrees().rem_euclid(360.0)
+}
+
+fn drivable(tile: &GraphTile, index: u32) -> bool {
+    let edge = tile.directededge(index).unwrap();
+    !edge.is_shortcut() && edge.forwardaccess().contains(Access::AUTO) && edge.length() > 50
+}
+
+/// Drivable path of up to `len` consecutive edges within a single tile, without U-turns.
+fn find_path(tile: &GraphTile, len: usize) -> Vec<GraphId> {
+    let start_node = |index: u32| {
+        tile.nodes()
+            .iter()
+            .position(|node| {
+                (node.edge_index()..node.edge_index() + node.edge_count()).contains(&index)
+            })
+            .map(|node| tile.node_id(node as u32))
+    };
+
+    let mut best = Vec::new();
+    for first in 0..tile.directededges().len() as u32 {
+        if !drivable(tile, first) {
+            continue;
+        }
+        let mut path = vec![first];
+        while path.len() < len {
+            let current = *path.last().unwrap();
+            let end = tile.directededge(current).unwrap().endnode();
+            if end.tile() != tile.id() {
+                break;
+            }
+            let node = tile.node(end.id()).unwrap();
+            let next = (node.edge_index()..node.edge_index() + node.edge_count()).find(|&next| {
+                drivable(tile, next)
+                    && !path.contains(&next)
+                    && Some(tile.directededge(next).unwrap().endnode()) != start_node(current)
+            });
+            match next {
+                Some(next) => path.push(next),
+                None => break,
+            }
+        }
+        if path.len() > best.len() {
+            best = path;
+        }
+        if best.len() == len {
+            break;
+        }
+    }
+    best.into_iter().map(|index| tile.edge_id(index)).collect()
+}
+
+/// Reference with a point at the start of every edge and the last point at the end of the path.
+fn reference(tile: &GraphTile, path: &[GraphId]) -> LineLocationReference {
+    let mut points = Vec::new();
+    for (i, id) in path.iter().enumerate() {
+        let edge = tile.directededge(id.id()).unwrap();
+        let shape = tile.edgeinfo(edge).shape_points();
+        points.push(LocationReferencePoint {
+            coordinate: shape[0],
+            frc: edge.classification().repr,
+            fow: FormOfWay::SingleCarriageway,
+            bearing: bearing(shape[0], shape[1]),
+            lfrcnp: 7,
+            dnp: f64::from(edge.length()),
+        });
+        if i + 1 == path.len() {
+            let n = shape.len();
+            points.push(LocationReferencePoint {
+                coordinate: shape[n - 1],
+                frc: edge.classification().repr,
+                fow: FormOfWay::SingleCarriageway,
+                bearing: bearing(shape[n - 1], shape[n - 2]),
+                lfrcnp: 7,
+                dnp: 0.0,
+            });
+        }
+    }
+    LineLocationReference {
+        points,
+        positive_offset: 0.0,
+        negative_offset: 0.0,
+    }
+}
+
+#[test]
+fn decode_path() {
+    let reader = GraphReader::new(&Config::from_tile_extract(ANDORRA_TILES).unwrap()).unwrap();
+    let tile = reader
+        .tiles()
+        .into_iter()
+        .filter(|id| id.level() == 2)
+        .map(|id| reader.get_tile(id).unwrap())
+        .max_by_key(|tile| tile.directededges().len())
+        .unwrap();
+    let path = find_path(&tile, 3);
+    assert_eq!(path.len(), 3);
+
+    let decoder = OpenLrDecoder::new(&reader);
+    let location = decoder.decode(&reference(&tile, &path)).unwrap();
+    let edges: Vec<_> = location.edges.iter().map(|span| span.id).collect();
+    assert_eq!(edges, path);
+    assert!(location.edges[0].start < 0.01);
+    assert!(location.edges[2].end > 0.99);
+    let length: u32 = path
+        .iter()
+        .map(|id| tile.directededge(id.id()).unwrap().length())
+        .sum();
+    assert!((location.length - f64::from(length)).abs() < 5.0);
+
+    // Only the first and the last point, the path in between is found by routing
+    let mut sparse = reference(&tile, &path);
+    sparse.points.drain(1..3);
+    sparse.points[0].dnp = f64::from(length);
+    sparse.positive_offset = 10.0;
+    sparse.negative_offset = 10.0;
+    let location = decoder.decode(&sparse).unwrap();
+    assert_eq!(
+        location
+            .edges
+            .iter()
+            .map(|span| span.id)
+            .collect::<Vec<_>>(),
+        path
+    );
+    let first_length = f64::from(tile.directededge(path[0].id()).unwrap().length());
+    assert!((location.edges[0].start - 10.0 / first_length).abs() < 0.01);
+    assert!(location.edges[2].end < 1.0);
+
+    // Points far away from any road
+    let mut nowhere = reference(&tile, &path);
+    nowhere.points[0].coordinate = LatLon(0.0, 0.0);
+    assert!(decoder.decode(&nowhere).is_err());
+}
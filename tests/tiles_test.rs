@@ -1,7 +1,13 @@
+use std::time::{Duration, SystemTime};
+
 use miniserde::{Serialize, json};
 use pretty_assertions::assert_eq;
 
-use valhalla::{Config, GraphId, GraphLevel, GraphReader, LatLon, TimeZoneInfo};
+use valhalla::{
+    CacheStats, CachedGraphReader, Config, ConfigBuilder, DirectedEdge, ExportFormat, ExportLayer,
+    GraphExport, GraphId, GraphLevel, GraphReader, LatLon, ReloadOutcome, ReloadableActor,
+    ReloadableGraphReader, TileDir, TileLoader, TimeZoneInfo, WayIndex,
+};
 
 #[derive(Serialize)]
 struct ValhallaConfig {
@@ -18,6 +24,13 @@ const ANDORRA_TILES: &str = "tests/andorra/tiles.tar";
 const ANDORRA_TRAFFIC: &str = "tests/andorra/traffic.tar";
 const ANDORRA_BBOX: (LatLon, LatLon) = (LatLon(42.373627, 1.301427), LatLon(42.72199, 1.892865));
 
+/// Unpacks the Andorra extract into a temporary directory with the layout of `mjolnir.tile_dir`.
+fn extract_tiles() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().expect("Failed to create temp dir");
+    TileDir::unpack(ANDORRA_TILES, dir.path()).expect("Failed to unpack tiles");
+    dir
+}
+
 #[test]
 fn dataset_id() {
     let config = ValhallaConfig {
@@ -229,3 +242,979 @@ fn tz_info() {
     assert_eq!(tz.name, "America/Los_Angeles");
     assert_eq!(tz.offset_seconds, -28800); // UTC-8
 }
+
+#[test]
+fn reload() {
+    let dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let tiles = dir.path().join("tiles.tar");
+    let traffic = dir.path().join("traffic.tar");
+    std::fs::copy(ANDORRA_TILES, &tiles).unwrap();
+    std::fs::copy(ANDORRA_TRAFFIC, &traffic).unwrap();
+    let config = ConfigBuilder::new()
+        .tile_extract(&tiles)
+        .traffic_extract(&traffic)
+        .build()
+        .unwrap();
+
+    let reader = ReloadableGraphReader::new(&config).unwrap();
+    let before = reader.current();
+    let tile = before.get_tile(before.tiles()[0]).unwrap();
+    assert_eq!(
+        reader.reload().unwrap(),
+        ReloadOutcome::Unchanged {
+            dataset_id: 12953172102
+        }
+    );
+    assert_eq!(
+        reader.reload_in_background().join().unwrap().unwrap(),
+        ReloadOutcome::Unchanged {
+            dataset_id: 12953172102
+        }
+    );
+
+    // Unchanged files aren't loaded again, but the new configuration is used from now on
+    let mut updated = reader.config();
+    updated.set("mjolnir.max_cache_size", 1000);
+    assert_eq!(
+        reader.reload_with(&updated).unwrap(),
+        ReloadOutcome::Unchanged {
+            dataset_id: 12953172102
+        }
+    );
+    assert_eq!(reader.config().get("mjolnir.max_cache_size"), Some(1000));
+
+    // Failed reload keeps the current tileset and configuration
+    assert!(
+        reader
+            .reload_with(&Config::from_tile_extract("bad_path_to_tile_extract").unwrap())
+            .is_err()
+    );
+    assert_eq!(reader.current().tiles(), before.tiles());
+    assert_eq!(reader.config().get("mjolnir.max_cache_size"), Some(1000));
+    assert!(reader.reload().is_ok());
+
+    // Publish a new traffic extract by renaming it over the old one
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let watcher = reader.watch(Duration::from_millis(10), move |result| {
+        let _ = sender.send(result.unwrap());
+    });
+    let next = dir.path().join("traffic.tar.next");
+    std::fs::copy(ANDORRA_TRAFFIC, &next).unwrap();
+    std::fs::File::options()
+        .write(true)
+        .open(&next)
+        .unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(3600))
+        .unwrap();
+    std::fs::rename(&next, &traffic).unwrap();
+    assert_eq!(
+        receiver.recv_timeout(Duration::from_secs(10)).unwrap(),
+        ReloadOutcome::Reloaded {
+            previous: 12953172102,
+            current: 12953172102
+        }
+    );
+    drop(watcher);
+
+    // Failed reloads are retried until the extract can be read again
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let watcher = reader.watch(Duration::from_millis(10), move |result| {
+        let _ = sender.send(result.is_ok());
+    });
+    let moved = dir.path().join("tiles.tar.moved");
+    std::fs::rename(&tiles, &moved).unwrap();
+    for _ in 0..2 {
+        assert!(!receiver.recv_timeout(Duration::from_secs(10)).unwrap());
+    }
+    std::fs::rename(&moved, &tiles).unwrap();
+    while !receiver.recv_timeout(Duration::from_secs(10)).unwrap() {}
+    drop(watcher);
+
+    // Readers and tiles obtained before the reload stay valid
+    assert_eq!(before.dataset_id(), reader.current().dataset_id());
+    assert!(!tile.directededges().is_empty());
+
+    // Actors are built by the reload from the tiles it loaded, so requests don't open the extracts
+    let mut actor = ReloadableActor::new(&reader).unwrap();
+    let next = dir.path().join("traffic.tar.next");
+    std::fs::copy(ANDORRA_TRAFFIC, &next).unwrap();
+    std::fs::File::options()
+        .write(true)
+        .open(&next)
+        .unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(7200))
+        .unwrap();
+    std::fs::rename(&next, &traffic).unwrap();
+    assert!(matches!(
+        reader.reload().unwrap(),
+        ReloadOutcome::Reloaded { .. }
+    ));
+    std::fs::rename(&tiles, &moved).unwrap();
+    assert!(actor.actor().is_ok());
+    assert!(ReloadableActor::new(&reader).is_ok());
+    std::fs::rename(&moved, &tiles).unwrap();
+}
+
+#[test]
+fn from_bytes_and_loader() {
+    let reader = GraphReader::new(&Config::from_tile_extract(ANDORRA_TILES).unwrap()).unwrap();
+    let sorted = |mut tiles: Vec<GraphId>| {
+        tiles.sort_by_key(|id| id.value);
+        tiles
+    };
+    let tiles = sorted(reader.tiles());
+    let edge_count = |reader: &GraphReader| -> usize {
+        tiles
+            .iter()
+            .map(|&id| reader.get_tile(id).unwrap().directededges().len())
+            .sum()
+    };
+
+    let data = std::fs::read(ANDORRA_TILES).unwrap();
+    let memory = GraphReader::from_bytes(data.into()).unwrap();
+    assert_eq!(sorted(memory.tiles()), tiles);
+    assert_eq!(memory.dataset_id(), 12953172102);
+    assert_eq!(edge_count(&memory), edge_count(&reader));
+    let tile = memory.get_tile(tiles[0]).unwrap();
+    drop(memory);
+    assert!(!tile.directededges().is_empty());
+
+    assert!(GraphReader::from_bytes(Vec::new().into()).is_err());
+    assert!(GraphReader::from_bytes(vec![1; 1024].into()).is_err());
+
+    // Unpacked extract has the same layout as `mjolnir.tile_dir`
+    let dir = extract_tiles();
+    let loader = TileDir::new(dir.path());
+    assert_eq!(sorted(loader.tiles()), tiles);
+    let path = dir.path().join(tiles[0].file_suffix());
+    assert!(path.exists(), "{path:?} should exist");
+
+    let loaded = GraphReader::from_loader(loader);
+    assert_eq!(sorted(loaded.tiles()), tiles);
+    assert_eq!(loaded.dataset_id(), 12953172102);
+    assert_eq!(edge_count(&loaded), edge_count(&reader));
+    assert!(
+        loaded
+            .get_tile(GraphId::from_parts(0, 0, 0).unwrap())
+            .is_none()
+    );
+
+    // Tiles removed after listing are reported as missing
+    std::fs::remove_file(path).unwrap();
+    assert!(loaded.get_tile(tiles[0]).is_none());
+}
+
+#[test]
+fn cached_reader() {
+    let reader = GraphReader::new(&Config::from_tile_extract(ANDORRA_TILES).unwrap()).unwrap();
+    let highway = GraphId::from_parts(0, 3015, 0).unwrap();
+    let arterial = GraphId::from_parts(1, 47701, 0).unwrap();
+    let local = GraphId::from_parts(2, 762485, 0).unwrap();
+    // Sizes of tiles in the tar extract
+    assert_eq!(reader.get_tile(highway).unwrap().size(), 388872);
+    assert_eq!(reader.get_tile(arterial).unwrap().size(), 272208);
+
+    // Budget fits highway and arterial tiles, but not all three
+    let cached = CachedGraphReader::new(reader.clone(), 388872 + 272208);
+    let tile = cached.get_tile(highway).unwrap();
+    assert_eq!(tile.id(), highway);
+    assert!(cached.get_tile(highway).is_some());
+    assert!(cached.get_tile(arterial).is_some());
+    assert_eq!(
+        cached.stats(),
+        CacheStats {
+            hits: 1,
+            misses: 2,
+            evictions: 0,
+            tiles: 2,
+            bytes: 388872 + 272208,
+        }
+    );
+
+    // Highway tile is used more recently, so arterial is evicted
+    assert!(cached.get_tile(highway).is_some());
+    assert!(cached.get_tile(local).is_some());
+    let stats = cached.stats();
+    assert_eq!((stats.hits, stats.misses, stats.evictions), (2, 3, 1));
+    assert_eq!(stats.tiles, 2);
+    assert!(stats.bytes <= 388872 + 272208);
+    // Clones share the cache
+    assert!(cached.clone().get_tile(highway).is_some());
+    assert_eq!(cached.stats().hits, 3);
+
+    // Missing tiles are not cached
+    assert!(
+        cached
+            .get_tile(GraphId::from_parts(0, 0, 0).unwrap())
+            .is_none()
+    );
+    assert_eq!(cached.stats().misses, 4);
+
+    // Tiles larger than the budget are returned, but not cached
+    let tiny = CachedGraphReader::new(reader, 1000);
+    assert!(tiny.get_tile(highway).is_some());
+    assert_eq!(tiny.stats().tiles, 0);
+
+    cached.clear();
+    assert_eq!(cached.stats().bytes, 0);
+    assert_eq!(cached.stats().tiles, 0);
+
+    // Shared between threads
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for id in [highway, arterial, local] {
+                    assert_eq!(cached.get_tile(id).unwrap().id(), id);
+                }
+            });
+        }
+    });
+    assert!(cached.stats().bytes <= 388872 + 272208);
+}
+
+#[test]
+fn edge_and_node_ids() {
+    let reader = GraphReader::new(&Config::from_tile_extract(ANDORRA_TILES).unwrap()).unwrap();
+    let tile = reader.get_tile(reader.tiles()[0]).unwrap();
+    let id = tile.edge_id(42);
+    assert_eq!(id.tile(), tile.id());
+    assert_eq!(id.id(), 42);
+    assert_eq!(tile.node_id(7).id(), 7);
+    assert_eq!(tile.node_id(7).tile(), tile.id());
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn par_edges_and_nodes() {
+    use rayon::prelude::*;
+    use valhalla::{Access, GraphFilter, GraphTile};
+
+    let reader = GraphReader::new(&Config::from_tile_extract(ANDORRA_TILES).unwrap()).unwrap();
+    let tiles: Vec<_> = reader
+        .tiles()
+        .into_iter()
+        .map(|id| reader.get_tile(id).unwrap())
+        .collect();
+    let edge_count = |predicate: &dyn Fn(&GraphTile, &valhalla::DirectedEdge) -> bool| {
+        tiles
+            .iter()
+            .map(|tile| {
+                tile.directededges()
+                    .iter()
+                    .filter(|edge| predicate(tile, edge))
+                    .count()
+            })
+            .sum::<usize>()
+    };
+
+    let all = GraphFilter::default();
+    assert_eq!(reader.par_edges(&all).count(), edge_count(&|_, _| true));
+    assert_eq!(
+        reader.par_nodes(&all).count(),
+        tiles.iter().map(|tile| tile.nodes().len()).sum::<usize>()
+    );
+
+    let local = GraphFilter::default()
+        .level(GraphLevel::Local)
+        .skip_shortcuts()
+        .access(Access::PEDESTRIAN);
+    let expected = edge_count(&|tile, edge| {
+        tile.id().level() == 2
+            && !edge.is_shortcut()
+            && (edge.forwardaccess() | edge.reverseaccess()).contains(Access::PEDESTRIAN)
+    });
+    assert_ne!(expected, 0);
+    assert_eq!(reader.par_edges(&local).count(), expected);
+
+    // Ids point to the yielded edges
+    reader.par_edges(&local).for_each(|edge| {
+        assert_eq!(edge.id.tile(), edge.tile().id());
+        assert!(!edge.edge().is_shortcut());
+        assert_eq!(
+            edge.tile().directededge(edge.id.id()).unwrap().length(),
+            edge.edge().length()
+        );
+    });
+    reader.par_nodes(&local).for_each(|node| {
+        assert_eq!(node.id.level(), 2);
+        assert!(Access::from_bits_retain(node.node().access()).contains(Access::PEDESTRIAN));
+    });
+}
+
+#[test]
+fn export() {
+    let reader = GraphReader::new(&Config::from_tile_extract(ANDORRA_TILES).unwrap()).unwrap();
+    let bbox = (LatLon(42.50, 1.51), LatLon(42.51, 1.53));
+    let export = GraphExport::new(&reader)
+        .bbox(bbox.0, bbox.1)
+        .skip_shortcuts();
+    let write = |layer, format| {
+        let mut out = Vec::new();
+        let count = export.write(layer, format, &mut out).unwrap();
+        (count, out)
+    };
+
+    let (edges, geojson) = write(ExportLayer::Edges, ExportFormat::GeoJson);
+    assert_ne!(edges, 0);
+    let geojson = String::from_utf8(geojson).unwrap();
+    assert!(geojson.starts_with(r#"{"type":"FeatureCollection","features":[{"type":"Feature""#));
+    assert!(geojson.ends_with("]}"));
+    assert_eq!(geojson.matches(r#"{"type":"Feature","#).count(), edges);
+
+    let (count, seq) = write(ExportLayer::Edges, ExportFormat::GeoJsonSeq);
+    assert_eq!(count, edges);
+    let lines: Vec<_> = std::str::from_utf8(&seq).unwrap().lines().collect();
+    assert_eq!(lines.len(), edges);
+    assert!(lines[0].starts_with(r#"{"type":"Feature","geometry":{"type":"LineString""#));
+    assert!(
+        lines
+            .iter()
+            .all(|line| !line.contains(r#""shortcut":true"#))
+    );
+
+    let (count, csv) = write(ExportLayer::Edges, ExportFormat::Csv);
+    assert_eq!(count, edges);
+    let csv = String::from_utf8(csv).unwrap();
+    let mut rows = csv.lines();
+    assert_eq!(
+        rows.next(),
+        Some(
+            "wkt,id,way_id,length,speed,speed_limit,forward_access,reverse_access,use,road_class,shortcut"
+        )
+    );
+    assert!(rows.all(|row| row.starts_with("\"LINESTRING (")));
+
+    let (count, fgb) = write(ExportLayer::Edges, ExportFormat::FlatGeobuf);
+    assert_eq!(count, edges);
+    assert_eq!(&fgb[..8], b"fgb\x03fgb\x00");
+    {
+        // Output must be readable by the reference implementation, not only by our own decoder
+        use flatgeobuf::{geozero::FeatureProperties, *};
+
+        let mut cursor = std::io::Cursor::new(&fgb);
+        let mut reader = FgbReader::open(&mut cursor).unwrap().select_all().unwrap();
+        assert_eq!(reader.header().geometry_type(), GeometryType::LineString);
+        assert_eq!(reader.header().features_count(), edges as u64);
+        let mut features = 0;
+        while let Some(feature) = reader.next().unwrap() {
+            assert!(!feature.property::<String>("id").unwrap().is_empty());
+            assert!(!feature.property::<String>("road_class").unwrap().is_empty());
+            let geometry = feature.geometry().unwrap();
+            // At least two points, as interleaved coordinates
+            assert!(geometry.xy().unwrap().len() >= 4);
+            features += 1;
+        }
+        assert_eq!(features, edges);
+    }
+
+    // Nodes are within the bbox
+    let (nodes, csv) = write(ExportLayer::Nodes, ExportFormat::Csv);
+    assert_ne!(nodes, 0);
+    for row in String::from_utf8(csv).unwrap().lines().skip(1) {
+        let point = row
+            .strip_prefix("POINT (")
+            .and_then(|row| row.split_once(')'))
+            .unwrap()
+            .0;
+        let (lon, lat) = point.split_once(' ').unwrap();
+        assert!((bbox.0.0..=bbox.1.0).contains(&lat.parse().unwrap()));
+        assert!((bbox.0.1..=bbox.1.1).contains(&lon.parse().unwrap()));
+    }
+
+    // Explicit tile list exports everything in these tiles
+    let tile = reader.tiles_in_bbox(bbox.0, bbox.1, GraphLevel::Local)[0];
+    let mut out = Vec::new();
+    let count = GraphExport::new(&reader)
+        .tiles([tile])
+        .write(ExportLayer::Nodes, ExportFormat::Csv, &mut out)
+        .unwrap();
+    assert_eq!(count, reader.get_tile(tile).unwrap().nodes().len());
+
+    // Edges starting in a neighbouring tile are exported where they cross the bbox
+    let (edge_id, end) = reader
+        .tiles_in_bbox(bbox.0, bbox.1, GraphLevel::Local)
+        .into_iter()
+        .find_map(|tile_id| {
+            let tile = reader.get_tile(tile_id).unwrap();
+            let (index, edge) = tile.directededges().iter().enumerate().find(|(_, edge)| {
+                // Short enough to end in a tile next to the start one
+                edge.leaves_tile()
+                    && !edge.is_shortcut()
+                    && edge.length() < 1000
+                    && reader.get_tile(edge.endnode()).is_some()
+            })?;
+            Some((tile.edge_id(index as u32), edge.endnode()))
+        })
+        .unwrap();
+    let end_tile = reader.get_tile(end).unwrap();
+    let end = end_tile.node_latlon(end_tile.node(end.id()).unwrap());
+    let margin = 1e-6;
+    let mut out = Vec::new();
+    GraphExport::new(&reader)
+        .bbox(
+            LatLon(end.0 - margin, end.1 - margin),
+            LatLon(end.0 + margin, end.1 + margin),
+        )
+        .write(ExportLayer::Edges, ExportFormat::GeoJsonSeq, &mut out)
+        .unwrap();
+    assert!(
+        String::from_utf8(out)
+            .unwrap()
+            .contains(&format!(r#""id":"{edge_id}""#))
+    );
+}
+
+#[test]
+fn way_index() {
+    let reader = GraphReader::new(&Config::from_tile_extract(ANDORRA_TILES).unwrap()).unwrap();
+    let index = WayIndex::build(&reader);
+    assert_eq!(index.dataset_id(), reader.dataset_id());
+
+    let mut expected = 0;
+    for tile in reader.tiles() {
+        let tile = reader.get_tile(tile).unwrap();
+        for (i, edge) in tile.directededges().iter().enumerate() {
+            let edge_id = tile.edge_id(i as u32);
+            if edge.is_shortcut() {
+                assert_eq!(index.way_for_edge(edge_id), None);
+                continue;
+            }
+            let way_id = tile.edgeinfo(edge).way_id;
+            assert_eq!(index.way_for_edge(edge_id), Some(way_id));
+            assert!(index.edges_for_way(way_id).any(|id| id == edge_id));
+            expected += 1;
+        }
+    }
+    assert_eq!(index.len(), expected);
+
+    // Both directions of a way are found
+    let (way_id, _) = index.iter().next().unwrap();
+    let edges: Vec<_> = index.edges_for_way(way_id).collect();
+    assert!(edges.len() >= 2);
+    assert!(edges.windows(2).all(|pair| pair[0].value < pair[1].value));
+    assert_eq!(index.edges_for_way(u64::MAX).count(), 0);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("ways.idx");
+    index.save(&path).unwrap();
+    let loaded = WayIndex::open(&path).unwrap();
+    assert_eq!(loaded.as_bytes(), index.as_bytes());
+    assert_eq!(loaded.edges_for_way(way_id).collect::<Vec<_>>(), edges);
+
+    let truncated = index.as_bytes()[..index.as_bytes().len() - 1].to_vec();
+    assert!(WayIndex::from_bytes(truncated).is_err());
+    assert!(WayIndex::from_bytes(b"garbage".to_vec()).is_err());
+
+    // Record count that overflows the expected size
+    let mut overflow = index.as_bytes()[..24].to_vec();
+    overflow[16..24].copy_from_slice(&u64::MAX.to_le_bytes());
+    assert!(WayIndex::from_bytes(overflow).is_err());
+
+    // Order entry that points past the records
+    let mut corrupted = index.as_bytes().to_vec();
+    let last = corrupted.len() - 4;
+    corrupted[last..].copy_from_slice(&(index.len() as u32).to_le_bytes());
+    assert!(WayIndex::from_bytes(corrupted).is_err());
+}
+
+#[test]
+fn turn_restrictions() {
+    let reader = GraphReader::new(&Config::from_tile_extract(ANDORRA_TILES).unwrap()).unwrap();
+    // Nodes are compared by location, as a path may continue on another hierarchy level
+    let start_node = |id: GraphId| {
+        let tile = reader.get_tile(id.tile()).unwrap();
+        let node = tile
+            .nodes()
+            .iter()
+            .find(|node| {
+                (node.edge_index()..node.edge_index() + node.edge_count()).contains(&id.id())
+            })
+            .unwrap();
+        tile.node_latlon(node)
+    };
+    let end_node = |id: GraphId| {
+        let tile = reader.get_tile(id.tile()).unwrap();
+        let end_node = tile.directededge(id.id()).unwrap().endnode();
+        let end_tile = reader.get_tile(end_node.tile()).unwrap();
+        end_tile.node_latlon(end_tile.node(end_node.id()).unwrap())
+    };
+
+    let mut simple = 0;
+    let mut complex = 0;
+    for tile_id in reader.tiles() {
+        let tile = reader.get_tile(tile_id).unwrap();
+        for (index, edge) in tile.directededges().iter().enumerate() {
+            if edge.restrictions() == 0 || edge.is_shortcut() {
+                continue;
+            }
+            let restricted = reader.restricted_turns(tile.edge_id(index as u32));
+            assert!(!restricted.is_empty());
+            // Restricted edges leave the end node of the edge
+            for id in restricted {
+                let out_tile = reader.get_tile(id.tile()).unwrap();
+                let out = out_tile.directededge(id.id()).unwrap();
+                assert_ne!(edge.restrictions() & (1 << out.localedgeidx()), 0);
+            }
+            simple += 1;
+        }
+
+        for restriction in tile.complex_restrictions() {
+            assert_eq!(restriction.to.tile(), tile_id);
+            let to = tile.directededge(restriction.to.id()).unwrap();
+            assert!(to.end_restriction().intersects(restriction.access()));
+            let from_tile = reader.get_tile(restriction.from.tile()).unwrap();
+            let from = from_tile.directededge(restriction.from.id()).unwrap();
+            assert!(from.start_restriction().intersects(restriction.access()));
+            assert!(!restriction.access().is_empty());
+            assert_eq!(restriction.time_domain().is_some(), restriction.has_time);
+
+            // Via edges connect `from` with `to`
+            let path: Vec<GraphId> = std::iter::once(restriction.from)
+                .chain(restriction.vias.iter().copied())
+                .chain(std::iter::once(restriction.to))
+                .collect();
+            for pair in path.windows(2) {
+                assert_eq!(end_node(pair[0]), start_node(pair[1]), "{restriction:?}");
+            }
+            complex += 1;
+        }
+    }
+    assert_ne!(simple, 0);
+    assert_ne!(complex, 0);
+
+    // Edges without restrictions
+    let tile = reader.get_tile(reader.tiles()[0]).unwrap();
+    let (index, _) = tile
+        .directededges()
+        .iter()
+        .enumerate()
+        .find(|(_, edge)| edge.restrictions() == 0)
+        .unwrap();
+    assert_eq!(reader.restricted_turns(tile.edge_id(index as u32)), vec![]);
+}
+
+#[test]
+fn access_restrictions() {
+    use valhalla::RestrictionValue;
+
+    let reader = GraphReader::new(&Config::from_tile_extract(ANDORRA_TILES).unwrap()).unwrap();
+    let tiles = reader.tiles();
+    for (index, &tile_id) in tiles.iter().enumerate() {
+        let tile = reader.get_tile(tile_id).unwrap();
+        let other = reader.get_tile(tiles[(index + 1) % tiles.len()]).unwrap();
+        for edge in tile.directededges() {
+            // Edges of other tiles are not looked up
+            assert_eq!(other.access_restrictions(edge), vec![]);
+            let restrictions = tile.access_restrictions(edge);
+            if edge.access_restriction().is_empty() {
+                assert_eq!(restrictions, vec![]);
+                continue;
+            }
+            assert!(!restrictions.is_empty());
+            for restriction in restrictions {
+                // Restrictions are stored for the modes in the edge's mask
+                assert!(restriction.modes.intersects(edge.access_restriction()));
+                match restriction.value {
+                    RestrictionValue::MaxHeight { meters }
+                    | RestrictionValue::MaxWidth { meters }
+                    | RestrictionValue::MaxLength { meters } => assert!(meters > 0.0),
+                    RestrictionValue::MaxWeight { tonnes }
+                    | RestrictionValue::MaxAxleLoad { tonnes } => assert!(tonnes > 0.0),
+                    RestrictionValue::TimedAllowed(time)
+                    | RestrictionValue::TimedDenied(time)
+                    | RestrictionValue::DestinationAllowed(time) => {
+                        assert!(!time.to_string().is_empty())
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn signs() {
+    use valhalla::SignType;
+
+    let reader = GraphReader::new(&Config::from_tile_extract(ANDORRA_TILES).unwrap()).unwrap();
+    let mut edges_with_signs = 0;
+    let tiles = reader.tiles();
+    for (index, &tile_id) in tiles.iter().enumerate() {
+        let tile = reader.get_tile(tile_id).unwrap();
+        let other = reader.get_tile(tiles[(index + 1) % tiles.len()]).unwrap();
+        for edge in tile.directededges() {
+            // Edges of other tiles are not looked up
+            assert!(other.signs(edge).is_empty());
+            let signs = tile.signs(edge);
+            assert_eq!(signs.is_empty(), !edge.sign());
+            for sign in &signs {
+                assert!(!sign.text.is_empty());
+                assert!(sign.sign_type != SignType::kJunctionName);
+                assert!(sign.sign_type != SignType::kLinguistic);
+                assert_eq!(sign.pronunciation().is_some(), sign.has_linguistic);
+            }
+            edges_with_signs += usize::from(!signs.is_empty());
+        }
+        for node in tile.nodes() {
+            assert!(other.node_signs(node).is_empty());
+            let signs = tile.node_signs(node);
+            assert_eq!(signs.is_empty(), !node.named_intersection());
+            for sign in &signs {
+                assert!(sign.sign_type == SignType::kJunctionName);
+                assert!(!sign.text.is_empty());
+            }
+        }
+    }
+    assert_ne!(edges_with_signs, 0);
+}
+
+#[test]
+fn lanes() {
+    use valhalla::TurnLanes;
+
+    let reader = GraphReader::new(&Config::from_tile_extract(ANDORRA_TILES).unwrap()).unwrap();
+    let mut edges_with_turn_lanes = 0;
+    let tiles = reader.tiles();
+    for (index, &tile_id) in tiles.iter().enumerate() {
+        let tile = reader.get_tile(tile_id).unwrap();
+        let other = reader.get_tile(tiles[(index + 1) % tiles.len()]).unwrap();
+        for edge in tile.directededges() {
+            // Edges of other tiles are not looked up
+            assert!(other.turn_lanes(edge).is_empty());
+            assert!(other.lane_connectivity(edge).is_empty());
+            let turn_lanes = tile.turn_lanes(edge);
+            assert_eq!(turn_lanes.is_empty(), !edge.turnlanes());
+            for lane in &turn_lanes {
+                assert!(TurnLanes::all().contains(*lane));
+            }
+            edges_with_turn_lanes += usize::from(!turn_lanes.is_empty());
+
+            for connectivity in tile.lane_connectivity(edge) {
+                assert!(edge.laneconnectivity());
+                assert_ne!(connectivity.from_way_id, 0);
+                assert!(!connectivity.to_lanes.is_empty());
+            }
+        }
+    }
+    assert_ne!(edges_with_turn_lanes, 0);
+}
+
+#[test]
+fn admins() {
+    let reader = GraphReader::new(&Config::from_tile_extract(ANDORRA_TILES).unwrap()).unwrap();
+    let mut andorra = 0;
+    let tiles = reader.tiles();
+    for (index, &tile_id) in tiles.iter().enumerate() {
+        let tile = reader.get_tile(tile_id).unwrap();
+        let other = reader.get_tile(tiles[(index + 1) % tiles.len()]).unwrap();
+        let admins: Vec<_> = tile.admins().collect();
+        assert!(!admins.is_empty());
+        for node in tile.nodes() {
+            let admin = tile.admin(node).unwrap();
+            assert_eq!(admin, admins[node.admin_index() as usize]);
+            assert_eq!(other.admin(node), None);
+            // Edges have the admin of their start node
+            let edges = node.edge_index()..node.edge_index() + node.edge_count();
+            for edge in edges.map(|index| tile.directededge(index).unwrap()) {
+                assert_eq!(tile.edge_admin(edge), Some(admin.clone()));
+                assert_eq!(other.edge_admin(edge), None);
+            }
+            if admin.country_iso == "AD" {
+                assert_eq!(admin.country_text, "Andorra");
+                andorra += 1;
+            }
+        }
+    }
+    assert_ne!(andorra, 0);
+}
+
+#[test]
+fn transit() {
+    // The extract is built without GTFS feeds, transit records are checked against a fixture tile in unit tests
+    // that run with the `test-fixtures` feature
+    let reader = GraphReader::new(&Config::from_tile_extract(ANDORRA_TILES).unwrap()).unwrap();
+    for tile_id in reader.tiles() {
+        let tile = reader.get_tile(tile_id).unwrap();
+        let header = tile.header();
+        assert_eq!(tile.transit_stops().count(), 0);
+        assert_eq!(header.stop_count, 0);
+        // No transit data is not an error
+        assert!(tile.transit_departures().unwrap().is_empty());
+        assert!(tile.transit_transfers().unwrap().is_empty());
+    }
+}
+
+#[test]
+fn header_and_stats() {
+    use valhalla::{Access, RoadClass};
+
+    let reader = GraphReader::new(&Config::from_tile_extract(ANDORRA_TILES).unwrap()).unwrap();
+    let mut nodes = 0;
+    let mut edges = 0;
+    for tile_id in reader.tiles() {
+        let tile = reader.get_tile(tile_id).unwrap();
+        let header = tile.header();
+        assert!(!header.version.is_empty());
+        assert_eq!(header.dataset_id, reader.dataset_id());
+        assert_eq!(header.node_count as usize, tile.nodes().len());
+        assert_eq!(
+            header.directed_edge_count as usize,
+            tile.directededges().len()
+        );
+        assert_eq!(header.admin_count as usize, tile.admins().count());
+        assert!(header.created() > SystemTime::UNIX_EPOCH + Duration::from_secs(1_388_534_400));
+        // Base is the south-west corner of the tile
+        for node in tile.nodes() {
+            let LatLon(lat, lon) = tile.node_latlon(node);
+            assert!(lat >= header.base_lat - 1e-6 && lon >= header.base_lon - 1e-6);
+        }
+        nodes += header.node_count as usize;
+        edges += header.directed_edge_count as usize;
+    }
+
+    let stats = reader.stats();
+    assert_eq!(stats.tiles, reader.tiles().len());
+    assert_eq!(stats.nodes, nodes);
+    assert_eq!(stats.edges + stats.shortcuts, edges);
+    assert_eq!(stats.edges_by_class.values().sum::<usize>(), stats.edges);
+    assert_eq!(stats.edges_by_use.values().sum::<usize>(), stats.edges);
+    assert!(stats.edges_by_class[&RoadClass::kResidential] > 0);
+    assert!(stats.km_by_access[&Access::AUTO] > 100.0);
+    assert!(
+        stats
+            .km_by_access
+            .keys()
+            .all(|mode| mode.bits().is_power_of_two())
+    );
+}
+
+#[test]
+fn graph_diff() {
+    use valhalla::{AttributeChange, EdgeChange, GraphDiff};
+
+    let reader = GraphReader::new(&Config::from_tile_extract(ANDORRA_TILES).unwrap()).unwrap();
+    let same = GraphReader::new(&Config::from_tile_extract(ANDORRA_TILES).unwrap()).unwrap();
+    let report = GraphDiff::new(&reader, &same).compare();
+    assert!(report.edges.is_empty());
+    assert_eq!(report.tiles.len(), reader.tiles().len());
+    assert!(report.tiles.iter().all(|tile| tile.unchanged > 0));
+
+    // The new build misses a tile
+    let dir = extract_tiles();
+    let local = GraphId::from_parts(2, 762485, 0).unwrap();
+    let missing = GraphReader::from_loader(TileDir::new(dir.path()));
+    std::fs::remove_file(dir.path().join(local.file_suffix())).unwrap();
+
+    let report = GraphDiff::new(&reader, &missing).tiles([local]).compare();
+    assert_eq!(report.tiles.len(), 1);
+    let summary = report.tiles[0];
+    assert_eq!(summary.tile, local);
+    assert_eq!(
+        (summary.added, summary.changed, summary.unchanged),
+        (0, 0, 0)
+    );
+    assert_eq!(summary.removed, report.edges.len());
+    assert_ne!(summary.removed, 0);
+    assert!(
+        report
+            .edges
+            .iter()
+            .all(|edge| edge.kind() == EdgeChange::Removed)
+    );
+
+    // Reversed, the same edges are added
+    let reversed = GraphDiff::new(&missing, &reader).tiles([local]).compare();
+    assert_eq!(reversed.tiles[0].added, summary.removed);
+
+    let mut geojson = Vec::new();
+    let count = report.write_geojson(&mut geojson).unwrap();
+    assert_eq!(count, summary.removed);
+    let geojson = String::from_utf8(geojson).unwrap();
+    assert!(geojson.starts_with(r#"{"type":"FeatureCollection","features":[{"type":"Feature""#));
+    assert_eq!(geojson.matches(r#""change":"removed""#).count(), count);
+
+    // The new build has a different speed on one edge
+    let dir = extract_tiles();
+    let tile = reader.get_tile(local).unwrap();
+    let (index, edge) = tile
+        .directededges()
+        .iter()
+        .enumerate()
+        .find(|(_, edge)| !edge.is_shortcut() && tile.edgeinfo(edge).way_id != 0)
+        .unwrap();
+    let edge_id = tile.edge_id(index as u32);
+    let record: Vec<u8> = edge
+        .data
+        .iter()
+        .flat_map(|word| word.to_le_bytes())
+        .collect();
+    let path = dir.path().join(local.file_suffix());
+    let mut data = std::fs::read(&path).unwrap();
+    let offset = data
+        .windows(record.len())
+        .position(|window| window == record)
+        .unwrap();
+    // Speed is the lowest byte of the third word of the edge
+    let speed = edge.speed();
+    assert_eq!(u32::from(data[offset + 16]), speed);
+    let new_speed = if speed > 100 { speed - 10 } else { speed + 10 };
+    data[offset + 16] = new_speed as u8;
+    std::fs::write(&path, data).unwrap();
+
+    let modified = GraphReader::from_loader(TileDir::new(dir.path()));
+    let report = GraphDiff::new(&reader, &modified).compare();
+    assert_eq!(report.edges.len(), 1);
+    let diff = &report.edges[0];
+    assert_eq!(diff.kind(), EdgeChange::Changed);
+    assert_eq!((diff.old_id, diff.new_id), (Some(edge_id), Some(edge_id)));
+    assert_eq!(
+        diff.changes,
+        vec![AttributeChange::Speed {
+            old: speed,
+            new: new_speed
+        }]
+    );
+    let summary = report.tiles.iter().find(|tile| tile.tile == local).unwrap();
+    assert_eq!((summary.added, summary.removed, summary.changed), (0, 0, 1));
+    assert!(
+        report
+            .tiles
+            .iter()
+            .all(|tile| tile.added == 0 && tile.removed == 0)
+    );
+
+    let mut geojson = Vec::new();
+    report.write_geojson(&mut geojson).unwrap();
+    let geojson = String::from_utf8(geojson).unwrap();
+    assert!(geojson.contains(&format!(r#""changes":["speed: {speed} -> {new_speed}"]"#)));
+}
+
+#[test]
+fn verify() {
+    use valhalla::TileIssue;
+
+    let reader = GraphReader::new(&Config::from_tile_extract(ANDORRA_TILES).unwrap()).unwrap();
+    // Sections computed from header counts must end where the header places variable-size data
+    let report = reader.verify();
+    assert!(report.is_ok(), "{:?}", report.issues);
+    assert_eq!(report.tiles, reader.tiles().len());
+    assert_ne!(report.nodes, 0);
+    assert_ne!(report.edges, 0);
+
+    // A truncated tile is reported instead of being read
+    let dir = extract_tiles();
+    let local = GraphId::from_parts(2, 762485, 0).unwrap();
+    let path = dir.path().join(local.file_suffix());
+    let file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+    file.set_len(100).unwrap();
+    drop(file);
+
+    let broken = GraphReader::from_loader(TileDir::new(dir.path()));
+    let report = broken.verify();
+    assert!(!report.is_ok());
+    assert_eq!(report.tiles, reader.tiles().len() - 1);
+    assert!(
+        report
+            .issues
+            .iter()
+            .any(|issue| matches!(issue, TileIssue::Layout { tile, .. } if *tile == local))
+    );
+    // Edges of other tiles leading into the broken one are dangling
+    assert!(report.issues.iter().all(|issue| match issue {
+        TileIssue::Layout { tile, .. } => *tile == local,
+        TileIssue::DanglingEndNode { end_node, .. } => end_node.tile() == local,
+        _ => false,
+    }));
+    assert!(!report.issues[0].to_string().is_empty());
+
+    // An edge with edge info past its section is reported instead of being decoded
+    let dir = extract_tiles();
+    let path = dir.path().join(local.file_suffix());
+    let mut data = std::fs::read(&path).unwrap();
+    let tile = reader.get_tile(local).unwrap();
+    let edge = &tile.directededges()[0];
+    // Safety: `DirectedEdge` is a plain 48-byte record, the same as in the tile file
+    let record = unsafe { &*(edge as *const DirectedEdge as *const [u8; 48]) };
+    let position = data
+        .windows(record.len())
+        .position(|window| window == record)
+        .unwrap();
+    // Edge info offset is in the lowest 25 bits of the second word of the record
+    let word = position + 8;
+    let value = u64::from_le_bytes(data[word..word + 8].try_into().unwrap()) | 0x1ff_ffff;
+    data[word..word + 8].copy_from_slice(&value.to_le_bytes());
+    std::fs::write(&path, data).unwrap();
+
+    let corrupted = GraphReader::from_loader(TileDir::new(dir.path()));
+    let report = corrupted.verify();
+    assert_eq!(report.tiles, reader.tiles().len());
+    assert_eq!(report.issues.len(), 1, "{:?}", report.issues);
+    assert!(matches!(
+        &report.issues[0],
+        TileIssue::Layout { tile, message } if *tile == local && message.contains("edge info")
+    ));
+}
+
+#[test]
+fn connectivity() {
+    use valhalla::{Access, GraphConnectivity};
+
+    let reader = GraphReader::new(&Config::from_tile_extract(ANDORRA_TILES).unwrap()).unwrap();
+    let report = GraphConnectivity::new(&reader, Access::AUTO).analyze();
+    assert_ne!(report.components, 0);
+    assert_eq!(report.tiles.len(), reader.tiles().len());
+
+    // Both ends of a two-way road are in the same component
+    let tile = reader.get_tile(reader.tiles()[0]).unwrap();
+    let (index, edge) = tile
+        .directededges()
+        .iter()
+        .enumerate()
+        .find(|(_, edge)| {
+            !edge.is_shortcut()
+                && edge.forwardaccess().contains(Access::AUTO)
+                && edge.reverseaccess().contains(Access::AUTO)
+        })
+        .unwrap();
+    let start = tile
+        .nodes()
+        .iter()
+        .position(|node| {
+            (node.edge_index()..node.edge_index() + node.edge_count()).contains(&(index as u32))
+        })
+        .unwrap();
+    let component = report.component(tile.node_id(start as u32));
+    assert!(component.is_some());
+    assert_eq!(report.component(edge.endnode()), component);
+
+    for island in &report.islands {
+        assert_ne!(island.component, 0);
+        assert!(!island.edges.is_empty() && island.edges.len() <= 100);
+        assert_eq!(island.edges.len(), island.shapes.len());
+        let (min, max) = island.bbox;
+        assert!(min.0 <= max.0 && min.1 <= max.1);
+    }
+    let none = GraphConnectivity::new(&reader, Access::AUTO)
+        .max_island_edges(0)
+        .analyze();
+    assert!(none.islands.is_empty());
+    assert_eq!(none.components, report.components);
+
+    let mut geojson = Vec::new();
+    let count = report.write_islands_geojson(&mut geojson).unwrap();
+    assert_eq!(count, report.islands.len());
+    let mut geojson = Vec::new();
+    let count = report
+        .write_tiles_geojson(GraphLevel::Local, &mut geojson)
+        .unwrap();
+    assert_eq!(
+        count,
+        reader
+            .tiles()
+            .iter()
+            .filter(|id| id.level() == GraphLevel::Local.repr as u32)
+            .count()
+    );
+    let geojson = String::from_utf8(geojson).unwrap();
+    assert!(geojson.contains(r#""type":"Polygon""#));
+}